@@ -0,0 +1,80 @@
+use alloy_primitives::{Address, B256};
+use alloy_provider::{Provider, RootProvider};
+use alloy_rpc_types::{BlockId, TransactionRequest};
+use alloy_sol_types::SolCall;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use canoe_bindings::{IEigenDACertVerifierBase, StatusCode};
+use canoe_provider::CertVerifierCall;
+use canoe_verifier_address_fetcher::{
+    CanoeVerifierAddressFetcher, CanoeVerifierAddressFetcherDeployedByEigenLabs,
+};
+use eigenda_cert::AltDACommitment;
+use std::fmt::Debug;
+
+/// Decides the validity of a cert when the host does not rely on eigenda-proxy for it
+#[async_trait]
+pub trait CertValidityChecker: Debug + Send + Sync {
+    /// Returns if the cert passes the onchain cert verification
+    async fn is_valid_cert(&self, altda_commitment: &AltDACommitment) -> Result<bool>;
+}
+
+/// [CertValidityChecker] calling `checkDACert` of the cert verifier on L1 at the L1 head block,
+/// which is the call canoe proves in the zkVM.
+#[derive(Debug, Clone)]
+pub struct EthCallCertValidityChecker {
+    /// Provider of the L1 execution node
+    l1_provider: RootProvider,
+    /// Hash of the L1 head block the call is made at
+    l1_head: B256,
+    /// Chain id of L1, to look up the cert verifier deployed by EigenLabs
+    l1_chain_id: u64,
+    /// Address of the cert verifier, overriding the one deployed by EigenLabs
+    cert_verifier_address: Option<Address>,
+}
+
+impl EthCallCertValidityChecker {
+    /// Creates a new instance of the [EthCallCertValidityChecker]
+    pub fn new(l1_provider: RootProvider, l1_head: B256, l1_chain_id: u64) -> Self {
+        Self {
+            l1_provider,
+            l1_head,
+            l1_chain_id,
+            cert_verifier_address: None,
+        }
+    }
+
+    /// Calls the cert verifier at `address` instead of the one deployed by EigenLabs for the L1
+    pub fn with_cert_verifier_address(mut self, address: Address) -> Self {
+        self.cert_verifier_address = Some(address);
+        self
+    }
+}
+
+#[async_trait]
+impl CertValidityChecker for EthCallCertValidityChecker {
+    async fn is_valid_cert(&self, altda_commitment: &AltDACommitment) -> Result<bool> {
+        let address = match self.cert_verifier_address {
+            Some(address) => address,
+            None => CanoeVerifierAddressFetcherDeployedByEigenLabs
+                .fetch_address(self.l1_chain_id, &altda_commitment.versioned_cert)?,
+        };
+
+        let input = match CertVerifierCall::build(altda_commitment) {
+            CertVerifierCall::ABIEncodeInterface(call) => call.abi_encode(),
+        };
+        let tx = TransactionRequest::default()
+            .to(address)
+            .input(input.into());
+        let output = self
+            .l1_provider
+            .call(tx)
+            .block(BlockId::hash(self.l1_head))
+            .await
+            .map_err(|e| anyhow!("failed to call cert verifier {address}: {e}"))?;
+
+        let status = IEigenDACertVerifierBase::checkDACertCall::abi_decode_returns(&output)
+            .map_err(|e| anyhow!("failed to decode status of cert verifier {address}: {e}"))?;
+        Ok(status == StatusCode::SUCCESS as u8)
+    }
+}
@@ -1,8 +1,20 @@
+use crate::cert_verifier::EthCallCertValidityChecker;
 use crate::eigenda_preimage::OnlineEigenDAPreimageProvider;
 use crate::handler::SingleChainHintHandlerWithEigenDA;
+use crate::prefetch::{EigenDAPrefetcher, DEFAULT_PREFETCH_CONCURRENCY};
+use crate::relay::{GrpcRelayClient, RelayRetriever};
+use crate::retry::{
+    ProxyRetryPolicy, DEFAULT_CIRCUIT_BREAKER_COOLDOWN_MS, DEFAULT_CIRCUIT_BREAKER_THRESHOLD,
+    DEFAULT_INITIAL_BACKOFF_MS, DEFAULT_MAX_BACKOFF_MS, DEFAULT_MAX_RETRIES,
+    DEFAULT_REQUEST_TIMEOUT_MS,
+};
+use crate::witness_cache::DiskWitnessCache;
 use alloy_op_evm::post_exec::PostExecEvmFactoryAdapter;
+use alloy_primitives::Address;
+use alloy_provider::Provider;
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use hokulea_eigenda::EncodedPayloadFetchMode;
 use hokulea_proof::hint::ExtendedHintType;
 use kona_cli::cli_styles;
 use kona_client::fpvm_evm::FpvmOpEvmFactory;
@@ -17,9 +29,26 @@ use kona_proof::HintType;
 use kona_std_fpvm::{FileChannel, FileDescriptor};
 use reqwest::Url;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::task::{self, JoinHandle};
 
+/// Default size limit of the on-disk witness cache, 4GiB holds 256 blobs of 16MiB
+pub const DEFAULT_WITNESS_CACHE_MAX_BYTES: u64 = 4 << 30;
+
+/// Where the host retrieves the data of EigenDA certs from
+#[derive(Default, ValueEnum, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EigenDARetrievalBackend {
+    /// eigenda-proxy, which also decides the validity and the recency of certs
+    #[default]
+    Proxy,
+    /// The EigenDA relays, the validity of certs is decided by calling the cert verifier on L1
+    Relay,
+}
+
 /// The host Eigenda binary CLI application arguments.
 #[derive(Default, Parser, Serialize, Clone, Debug)]
 #[command(styles = cli_styles())]
@@ -27,16 +56,107 @@ pub struct SingleChainHostWithEigenDA {
     #[clap(flatten)]
     pub kona_cfg: kona_host::single::SingleChainHost,
 
-    /// URL of the EigenDA RPC endpoint.
+    /// URLs of the EigenDA proxy, comma separated or repeated. The first one is the primary, the
+    /// others are failed over to in order.
     #[clap(
         long,
         visible_alias = "eigenda",
+        value_delimiter = ',',
+        requires = "l2_node_address",
+        requires = "l1_node_address",
+        requires = "l1_beacon_address",
+        env
+    )]
+    pub eigenda_proxy_address: Vec<String>,
+
+    /// Requires a second EigenDA proxy to return the same validity, recency verdict and encoded
+    /// payload of every cert before writing them to the key-value store. A disagreement fails
+    /// the hint as a critical error. Needs at least two proxy addresses.
+    #[clap(long, requires = "eigenda_proxy_address", env)]
+    pub eigenda_proxy_cross_check: bool,
+
+    /// Directory of the on-disk cache of what the EigenDA proxy returned for a cert, keyed by
+    /// the cert digest. Re-running the same block range then does not query the proxy again.
+    /// Disabled if not set.
+    #[clap(long, env)]
+    pub eigenda_witness_cache_dir: Option<PathBuf>,
+
+    /// Size limit of the on-disk witness cache in bytes, the least recently used entries are
+    /// evicted beyond it.
+    #[clap(long, default_value_t = DEFAULT_WITNESS_CACHE_MAX_BYTES, env)]
+    pub eigenda_witness_cache_max_bytes: u64,
+
+    /// Timeout of a request to the EigenDA proxy in milliseconds, until the response body is read.
+    #[clap(long, default_value_t = DEFAULT_REQUEST_TIMEOUT_MS, env)]
+    pub eigenda_proxy_timeout_ms: u64,
+
+    /// Number of retries of a request to the EigenDA proxy failing with a transport error, a
+    /// timeout, a 5xx or a 429.
+    #[clap(long, default_value_t = DEFAULT_MAX_RETRIES, env)]
+    pub eigenda_proxy_max_retries: u32,
+
+    /// Backoff in milliseconds before the first retry, doubled on every further retry with jitter.
+    #[clap(long, default_value_t = DEFAULT_INITIAL_BACKOFF_MS, env)]
+    pub eigenda_proxy_initial_backoff_ms: u64,
+
+    /// Upper bound of the backoff between retries in milliseconds.
+    #[clap(long, default_value_t = DEFAULT_MAX_BACKOFF_MS, env)]
+    pub eigenda_proxy_max_backoff_ms: u64,
+
+    /// Number of requests to the EigenDA proxy failing in a row, after all their retries, that
+    /// pauses further requests for the cooldown. 0 disables the circuit breaker.
+    #[clap(long, default_value_t = DEFAULT_CIRCUIT_BREAKER_THRESHOLD, env)]
+    pub eigenda_proxy_circuit_breaker_threshold: u32,
+
+    /// How long requests to the EigenDA proxy are paused in milliseconds once the circuit
+    /// breaker opens.
+    #[clap(long, default_value_t = DEFAULT_CIRCUIT_BREAKER_COOLDOWN_MS, env)]
+    pub eigenda_proxy_circuit_breaker_cooldown_ms: u64,
+
+    /// Where the data of EigenDA certs is retrieved from. With `relay`, the blobs are fetched
+    /// from the EigenDA relays and checked against the kzg commitment of the cert, and the
+    /// validity of certs is decided by calling the cert verifier on L1 at the L1 head. The EigenDA
+    /// proxy is then only needed for keccak commitments.
+    #[clap(long, value_enum, default_value_t = EigenDARetrievalBackend::Proxy, env)]
+    pub eigenda_retrieval_backend: EigenDARetrievalBackend,
+
+    /// Addresses of the EigenDA relays as `<relay key>=<url>`, comma separated or repeated. Used
+    /// by the `relay` retrieval backend.
+    #[clap(
+        long,
+        value_delimiter = ',',
         requires = "l2_node_address",
         requires = "l1_node_address",
         requires = "l1_beacon_address",
         env
     )]
-    pub eigenda_proxy_address: Option<String>,
+    pub eigenda_relay_address: Vec<String>,
+
+    /// Timeout of a request to an EigenDA relay in milliseconds. The retries, backoff and circuit
+    /// breaker of the requests to the relays and to the cert verifier follow the
+    /// `--eigenda-proxy-*` options.
+    #[clap(long, default_value_t = DEFAULT_REQUEST_TIMEOUT_MS, env)]
+    pub eigenda_relay_timeout_ms: u64,
+
+    /// Address of the EigenDA cert verifier called by the `relay` retrieval backend. Defaults to
+    /// the one deployed by EigenLabs on the L1 chain.
+    #[clap(long, env)]
+    pub eigenda_cert_verifier_address: Option<Address>,
+
+    /// Address of the batcher inbox of the rollup. If set, the EigenDA certs posted to it are
+    /// prefetched once the client reads the header or the receipts of their L1 block, instead of
+    /// waiting for the client to hint them.
+    #[clap(long, env)]
+    pub eigenda_prefetch_batch_inbox_address: Option<Address>,
+
+    /// Number of EigenDA certs prefetched at the same time.
+    #[clap(long, default_value_t = DEFAULT_PREFETCH_CONCURRENCY, env)]
+    pub eigenda_prefetch_concurrency: usize,
+
+    /// Stores the encoded payload of EigenDA certs in chunks of 4KiB instead of field elements,
+    /// for clients fetching it with `EncodedPayloadFetchMode::Chunk`. Only one layout is stored.
+    #[clap(long, env)]
+    pub eigenda_encoded_payload_chunks: bool,
 
     /// Verbosity level (-v, -vv, -vvv, etc.)
     /// TODO: think this should be upstreamed to kona_cfg
@@ -117,22 +237,115 @@ impl SingleChainHostWithEigenDA {
     ) -> Result<SingleChainProvidersWithEigenDA, SingleChainHostError> {
         let kona_providers = self.kona_cfg.create_providers().await?;
 
-        let url_str = self
-            .eigenda_proxy_address
-            .as_ref()
-            .ok_or_else(|| SingleChainHostError::Other("EigenDA API URL must be set"))?;
+        let mut base_urls = self.eigenda_proxy_address.iter().map(|url_str| {
+            Url::parse(url_str)
+                .map_err(|_| SingleChainHostError::Other("Failed to parse EigenDA API URL"))
+        });
+
+        let mut eigenda_preimage_provider = match self.eigenda_retrieval_backend {
+            EigenDARetrievalBackend::Proxy => {
+                let base_url = base_urls
+                    .next()
+                    .ok_or(SingleChainHostError::Other("EigenDA API URL must be set"))??;
+                OnlineEigenDAPreimageProvider::new_http(base_url)
+            }
+            EigenDARetrievalBackend::Relay => OnlineEigenDAPreimageProvider::new_relay(
+                self.create_relay_retriever(&kona_providers).await?,
+            ),
+        }
+        .with_retry_policy(self.proxy_retry_policy());
+        if self.eigenda_encoded_payload_chunks {
+            eigenda_preimage_provider =
+                eigenda_preimage_provider.with_fetch_mode(EncodedPayloadFetchMode::Chunk);
+        }
+        for base_url in base_urls {
+            eigenda_preimage_provider = eigenda_preimage_provider.with_failover_endpoint(base_url?);
+        }
 
-        let base_url = Url::parse(url_str)
-            .map_err(|_| SingleChainHostError::Other("Failed to parse EigenDA API URL"))?;
+        if self.eigenda_proxy_cross_check {
+            if self.eigenda_retrieval_backend != EigenDARetrievalBackend::Proxy {
+                return Err(SingleChainHostError::Other(
+                    "Cross-checking is only supported by the proxy retrieval backend",
+                ));
+            }
+            if self.eigenda_proxy_address.len() < 2 {
+                return Err(SingleChainHostError::Other(
+                    "Cross-checking needs at least two EigenDA API URLs",
+                ));
+            }
+            eigenda_preimage_provider = eigenda_preimage_provider.with_cross_check(true);
+        }
+        if let Some(dir) = &self.eigenda_witness_cache_dir {
+            let witness_cache = DiskWitnessCache::open(dir, self.eigenda_witness_cache_max_bytes)
+                .map_err(|_| {
+                SingleChainHostError::Other("Failed to open EigenDA witness cache")
+            })?;
+            eigenda_preimage_provider =
+                eigenda_preimage_provider.with_witness_cache(Arc::new(witness_cache));
+        }
 
-        let eigenda_preimage_provider = OnlineEigenDAPreimageProvider::new_http(base_url);
+        let eigenda_prefetcher = match self.eigenda_prefetch_batch_inbox_address {
+            Some(batch_inbox_address) => {
+                if self.eigenda_prefetch_concurrency == 0 {
+                    return Err(SingleChainHostError::Other(
+                        "EigenDA prefetch concurrency must be positive",
+                    ));
+                }
+                Some(EigenDAPrefetcher::new(
+                    kona_providers.l1.clone(),
+                    eigenda_preimage_provider.clone(),
+                    batch_inbox_address,
+                    self.eigenda_prefetch_concurrency,
+                ))
+            }
+            None => None,
+        };
 
         Ok(SingleChainProvidersWithEigenDA {
             kona_providers,
             eigenda_preimage_provider,
+            eigenda_prefetcher,
         })
     }
 
+    /// Creates the [RelayRetriever] of the relay retrieval backend, calling the cert verifier
+    /// with the L1 provider of kona
+    async fn create_relay_retriever(
+        &self,
+        kona_providers: &SingleChainProviders,
+    ) -> Result<RelayRetriever, SingleChainHostError> {
+        let relays = parse_relay_addresses(&self.eigenda_relay_address)?;
+        if relays.is_empty() {
+            return Err(SingleChainHostError::Other(
+                "EigenDA relay addresses must be set",
+            ));
+        }
+        let relay_client = GrpcRelayClient::new(
+            &relays,
+            Duration::from_millis(self.eigenda_relay_timeout_ms),
+        )
+        .map_err(|_| SingleChainHostError::Other("Failed to create EigenDA relay client"))?;
+
+        let l1_chain_id = kona_providers
+            .l1
+            .get_chain_id()
+            .await
+            .map_err(|_| SingleChainHostError::Other("Failed to fetch L1 chain id"))?;
+        let mut cert_validity_checker = EthCallCertValidityChecker::new(
+            kona_providers.l1.clone(),
+            self.kona_cfg.l1_head,
+            l1_chain_id,
+        );
+        if let Some(address) = self.eigenda_cert_verifier_address {
+            cert_validity_checker = cert_validity_checker.with_cert_verifier_address(address);
+        }
+
+        Ok(
+            RelayRetriever::new(Arc::new(relay_client), Arc::new(cert_validity_checker))
+                .with_retry_policy(self.proxy_retry_policy()),
+        )
+    }
+
     /// Starts the host in native mode, running both the client and preimage server in the same
     /// process.
     async fn start_native(&self) -> Result<(), SingleChainHostError> {
@@ -161,8 +374,49 @@ impl SingleChainHostWithEigenDA {
 impl SingleChainHostWithEigenDA {
     /// Returns `true` if the host is running in offline mode.
     pub const fn is_offline(&self) -> bool {
-        self.kona_cfg.is_offline() && self.eigenda_proxy_address.is_none()
+        self.kona_cfg.is_offline()
+            && self.eigenda_proxy_address.is_empty()
+            && self.eigenda_relay_address.is_empty()
+    }
+
+    /// Returns the [ProxyRetryPolicy] of the requests to the EigenDA proxy.
+    pub fn proxy_retry_policy(&self) -> ProxyRetryPolicy {
+        ProxyRetryPolicy {
+            request_timeout: Duration::from_millis(self.eigenda_proxy_timeout_ms),
+            max_retries: self.eigenda_proxy_max_retries,
+            initial_backoff: Duration::from_millis(self.eigenda_proxy_initial_backoff_ms),
+            max_backoff: Duration::from_millis(self.eigenda_proxy_max_backoff_ms),
+            circuit_breaker_threshold: self.eigenda_proxy_circuit_breaker_threshold,
+            circuit_breaker_cooldown: Duration::from_millis(
+                self.eigenda_proxy_circuit_breaker_cooldown_ms,
+            ),
+        }
+    }
+}
+
+/// Parses the relay addresses, given as `<relay key>=<url>`, into urls keyed by relay key
+fn parse_relay_addresses(
+    relay_addresses: &[String],
+) -> Result<HashMap<u32, Url>, SingleChainHostError> {
+    let mut relays = HashMap::new();
+    for relay_address in relay_addresses {
+        let (relay_key, url) = relay_address
+            .split_once('=')
+            .ok_or(SingleChainHostError::Other(
+                "EigenDA relay address must be <relay key>=<url>",
+            ))?;
+        let relay_key = relay_key
+            .parse()
+            .map_err(|_| SingleChainHostError::Other("Failed to parse EigenDA relay key"))?;
+        let url = Url::parse(url)
+            .map_err(|_| SingleChainHostError::Other("Failed to parse EigenDA relay URL"))?;
+        if relays.insert(relay_key, url).is_some() {
+            return Err(SingleChainHostError::Other(
+                "EigenDA relay key is set more than once",
+            ));
+        }
     }
+    Ok(relays)
 }
 
 /// Specify the wrapper type
@@ -176,4 +430,6 @@ pub struct SingleChainProvidersWithEigenDA {
     pub kona_providers: SingleChainProviders,
     /// The EigenDA preimage provider
     pub eigenda_preimage_provider: OnlineEigenDAPreimageProvider,
+    /// Prefetches the EigenDA certs of the L1 blocks read by the client, if configured
+    pub eigenda_prefetcher: Option<EigenDAPrefetcher>,
 }
@@ -1,39 +1,116 @@
-use alloy_primitives::{hex, Bytes};
+use alloy_primitives::{hex, keccak256, Bytes, B256};
 use anyhow::{anyhow, Result};
-use reqwest::{self, Url};
+use reqwest::{self, StatusCode, Url};
+use std::sync::Arc;
+use tracing::{debug, warn};
 
 use async_trait::async_trait;
-use eigenda_cert::AltDACommitment;
+use eigenda_cert::{AltDACommitment, CertView, GenericAltDACommitment};
 use hokulea_eigenda::{
-    EigenDAPreimageProvider, EncodedPayload, HokuleaErrorKind, HokuleaPreimageError,
+    EigenDAPreimageProvider, EncodedPayload, EncodedPayloadFetchMode, HokuleaErrorKind,
+    HokuleaPreimageError, BYTES_PER_FIELD_ELEMENT,
 };
 
+use crate::errors::HokuleaHostError;
+use crate::kzg::check_kzg_commitment;
+use crate::relay::RelayRetriever;
+use crate::retry::{CircuitBreaker, ProxyRetryPolicy};
 use crate::status_code::{DerivationError, HostHandlerError, HTTP_RESPONSE_STATUS_CODE_TEAPOT};
+use crate::witness_cache::DiskWitnessCache;
 
 /// Currently Hokulea hosts relies on Eigenda-proxy for preimage retrieval.
 /// It relies on the [DerivationError] status code returned by the proxy to decide when to stop retrieving
-/// data and return early.  
-#[derive(Debug, Clone)]
+/// data and return early. The [RelayRetriever] returns the same stages without proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProxyDerivationStage {
     // if cert has been attested by DA network and offchain derivation version is correct
     pub is_valid_cert: bool,
-    // if recency test is passed
-    pub pass_recency_check: bool,
+    // if recency test is passed, None if the host did not decide the recency of the cert. Proxy
+    // only checks recency given the L1 inclusion block number, which the host does not know
+    pub pass_recency_check: Option<bool>,
     // encoded_payload
     pub encoded_payload: Vec<u8>,
 }
 
-/// Fetches preimage from EigenDA via an eigenda-proxy instance.
+impl ProxyDerivationStage {
+    /// Returns what differs between two derivation stages of the same cert, if anything. A proxy
+    /// may trim the trailing zeros of the blob, hence the encoded payloads are compared as if
+    /// both were zero padded to the blob length.
+    fn mismatch(&self, other: &Self) -> Option<&'static str> {
+        if self.is_valid_cert != other.is_valid_cert {
+            Some("validity")
+        } else if self.pass_recency_check != other.pass_recency_check {
+            Some("recency verdict")
+        } else if !same_padded(&self.encoded_payload, &other.encoded_payload) {
+            Some("encoded payload")
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns if the two byte strings are equal once the shorter one is zero padded
+fn same_padded(a: &[u8], b: &[u8]) -> bool {
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    longer.starts_with(shorter) && longer[shorter.len()..].iter().all(|byte| *byte == 0)
+}
+
+/// Two eigenda-proxy instances returned different data for the same cert. Retrying cannot
+/// resolve it, one of them is faulty or misconfigured.
+#[derive(Debug, thiserror::Error)]
+#[error("eigenda-proxy {first} and {second} disagree on the {field} of cert {digest}")]
+pub struct ProxyMismatchError {
+    /// Digest of the cert
+    pub digest: B256,
+    /// What the proxies disagree on
+    pub field: &'static str,
+    /// Base url of the proxy queried first
+    pub first: Url,
+    /// Base url of the proxy queried to cross-check
+    pub second: Url,
+}
+
+/// An eigenda-proxy instance
 #[derive(Debug, Clone)]
-pub struct OnlineEigenDAPreimageProvider {
+struct ProxyEndpoint {
     /// The base url.
     base: Url,
+    /// Throttles the requests once the proxy keeps failing
+    circuit_breaker: CircuitBreaker,
+}
+
+impl ProxyEndpoint {
+    fn new(base: Url) -> Self {
+        Self {
+            base,
+            circuit_breaker: CircuitBreaker::default(),
+        }
+    }
+}
+
+/// Fetches preimage from EigenDA via eigenda-proxy instances. The proxies are queried in order,
+/// failing over to the next one on errors. Alternatively, certs are retrieved directly from the
+/// EigenDA relays by a [RelayRetriever], then proxies only serve keccak commitments.
+#[derive(Debug, Clone)]
+pub struct OnlineEigenDAPreimageProvider {
+    /// The proxies, the first one is the primary
+    endpoints: Vec<ProxyEndpoint>,
+    /// Retrieves certs from the relays instead of the proxies, if set
+    relay_retriever: Option<RelayRetriever>,
     /// The inner reqwest client. Used to talk to proxy
     inner: reqwest::Client,
     /// Cache holds the last fetched entry. The typical access pattern is:
     /// 1. get_validity() populates the cache
     /// 2. get_encoded_payload() retrieves the same entry immediately after
     last_entry: Option<(AltDACommitment, ProxyDerivationStage)>,
+    /// Optional cache on disk consulted before querying the proxy, it outlives the host process
+    witness_cache: Option<Arc<DiskWitnessCache>>,
+    /// Timeout, retries and backoff of the requests to proxy
+    retry_policy: ProxyRetryPolicy,
+    /// If the data of a cert returned by a proxy must be confirmed by a second one
+    cross_check: bool,
+    /// The address layout the client fetches the encoded payload with, the only one stored
+    fetch_mode: EncodedPayloadFetchMode,
 }
 
 // Query parameters configuration for proxy behavior:
@@ -52,76 +129,313 @@ impl OnlineEigenDAPreimageProvider {
     /// provided.
     pub fn new_http(base: Url) -> Self {
         Self {
-            base,
+            endpoints: vec![ProxyEndpoint::new(base)],
+            relay_retriever: None,
+            inner: reqwest::Client::new(),
+            last_entry: None,
+            witness_cache: None,
+            retry_policy: ProxyRetryPolicy::default(),
+            cross_check: false,
+            fetch_mode: EncodedPayloadFetchMode::default(),
+        }
+    }
+
+    /// Creates a new instance of the [OnlineEigenDAPreimageProvider] retrieving certs from the
+    /// EigenDA relays. Proxies added with [Self::with_failover_endpoint] serve keccak commitments.
+    pub fn new_relay(relay_retriever: RelayRetriever) -> Self {
+        Self {
+            endpoints: vec![],
+            relay_retriever: Some(relay_retriever),
             inner: reqwest::Client::new(),
             last_entry: None,
+            witness_cache: None,
+            retry_policy: ProxyRetryPolicy::default(),
+            cross_check: false,
+            fetch_mode: EncodedPayloadFetchMode::default(),
         }
     }
 
-    /// Fetch data from proxy without caching (takes `&self` for handler usage).
+    /// Adds a proxy to fail over to, after the ones already added
+    pub fn with_failover_endpoint(mut self, base: Url) -> Self {
+        self.endpoints.push(ProxyEndpoint::new(base));
+        self
+    }
+
+    /// Requires a second proxy to return the same validity, recency verdict and encoded payload
+    /// of a cert, otherwise a [ProxyMismatchError] is returned. It needs at least two endpoints.
+    pub fn with_cross_check(mut self, cross_check: bool) -> Self {
+        self.cross_check = cross_check;
+        self
+    }
+
+    /// Sets the timeout, retries and backoff of the requests to proxy
+    pub fn with_retry_policy(mut self, retry_policy: ProxyRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the address layout the client fetches the encoded payload with. The encoded payload
+    /// is only stored in this layout.
+    pub fn with_fetch_mode(mut self, fetch_mode: EncodedPayloadFetchMode) -> Self {
+        self.fetch_mode = fetch_mode;
+        self
+    }
+
+    /// The address layout the encoded payload is stored in
+    pub fn fetch_mode(&self) -> EncodedPayloadFetchMode {
+        self.fetch_mode
+    }
+
+    /// Consults the [DiskWitnessCache] before querying the proxy, and stores what the proxy
+    /// returned into it
+    pub fn with_witness_cache(mut self, witness_cache: Arc<DiskWitnessCache>) -> Self {
+        self.witness_cache = Some(witness_cache);
+        self
+    }
+
+    /// Fetch data of the altda commitment from the witness cache if configured, otherwise from
+    /// the relays if configured, or from proxy, cross-checked by a second one if configured. When
+    /// cross-checking, a witness cache hit must still be confirmed by a proxy. A structurally
+    /// malformed cert is only logged, its validity is still decided by the proxy or the cert
+    /// verifier.
+    pub async fn fetch_data_for_commitment(
+        &self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<ProxyDerivationStage, HokuleaHostError> {
+        if let Err(e) = altda_commitment.versioned_cert.validate_structure() {
+            warn!(
+                target = "hokulea-host",
+                "malformed cert {}: {e}",
+                altda_commitment.to_digest()
+            );
+        }
+
+        let digest = altda_commitment.to_digest();
+        let cached = self
+            .witness_cache
+            .as_ref()
+            .and_then(|witness_cache| witness_cache.get(&digest));
+        if let Some(derivation_stage) = cached {
+            debug!(target = "hokulea-host", "witness cache hit {digest}");
+            if !self.cross_check {
+                return Ok(derivation_stage);
+            }
+            // the cache is a single source, like the answer of a single proxy
+            let confirmed = self
+                .fetch_data_cross_checked(altda_commitment, Some(&derivation_stage))
+                .await?;
+            if confirmed == derivation_stage {
+                return Ok(confirmed);
+            }
+            return Ok(self.cache_witness(&digest, confirmed));
+        }
+
+        let derivation_stage = match &self.relay_retriever {
+            Some(relay_retriever) => relay_retriever.fetch_data(altda_commitment).await?,
+            None => {
+                self.fetch_data_cross_checked(altda_commitment, None)
+                    .await?
+            }
+        };
+        Ok(self.cache_witness(&digest, derivation_stage))
+    }
+
+    /// Stores the data of a cert into the witness cache if configured, and hands it back
+    fn cache_witness(
+        &self,
+        digest: &B256,
+        derivation_stage: ProxyDerivationStage,
+    ) -> ProxyDerivationStage {
+        // failing to cache only costs a query to the proxy next time
+        if let Some(witness_cache) = &self.witness_cache {
+            if let Err(e) = witness_cache.insert(digest, &derivation_stage) {
+                warn!(
+                    target = "hokulea-host",
+                    "failed to cache witness {digest}: {e}"
+                );
+            }
+        }
+        derivation_stage
+    }
+
+    /// Fetch data from proxy, and from a second one if cross-checking is configured. A `cached`
+    /// witness the first proxy agrees with needs no second proxy, otherwise it is ignored.
+    async fn fetch_data_cross_checked(
+        &self,
+        altda_commitment: &AltDACommitment,
+        cached: Option<&ProxyDerivationStage>,
+    ) -> Result<ProxyDerivationStage, HokuleaHostError> {
+        let altda_commitment_bytes = altda_commitment.to_rlp_bytes().into();
+        let (first, derivation_stage) = self
+            .fetch_data_with_failover(&altda_commitment_bytes, Some(altda_commitment), None)
+            .await?;
+        if let Some(cached) = cached {
+            match cached.mismatch(&derivation_stage) {
+                None => return Ok(cached.clone()),
+                Some(field) => warn!(
+                    target = "hokulea-host",
+                    "witness cache and eigenda-proxy {} disagree on the {field} of cert {}",
+                    self.endpoints[first].base,
+                    altda_commitment.to_digest()
+                ),
+            }
+        }
+        if self.cross_check {
+            let (second, other) = self
+                .fetch_data_with_failover(
+                    &altda_commitment_bytes,
+                    Some(altda_commitment),
+                    Some(first),
+                )
+                .await?;
+            if let Some(field) = derivation_stage.mismatch(&other) {
+                return Err(ProxyMismatchError {
+                    digest: altda_commitment.to_digest(),
+                    field,
+                    first: self.endpoints[first].base.clone(),
+                    second: self.endpoints[second].base.clone(),
+                }
+                .into());
+            }
+        }
+        Ok(derivation_stage)
+    }
+
+    /// Fetch data from proxy without caching (takes `&self` for handler usage), failing over
+    /// the proxies in order. The encoded payload is returned as is, unchecked against the cert.
     pub async fn fetch_data_from_proxy(
         &self,
         altda_commitment_bytes: &Bytes,
-    ) -> Result<ProxyDerivationStage> {
+    ) -> Result<ProxyDerivationStage, HokuleaHostError> {
+        self.fetch_data_with_failover(altda_commitment_bytes, None, None)
+            .await
+            .map(|(_, derivation_stage)| derivation_stage)
+    }
+
+    /// Fetch data from the first proxy which answers, except `skip`. Returns the index of the
+    /// proxy along with the data. If the cert is given, a proxy returning an encoded payload not
+    /// committed to by the cert is failed over as well. Once every proxy failed, the error is
+    /// retryable if any proxy failed for a transient reason.
+    async fn fetch_data_with_failover(
+        &self,
+        altda_commitment_bytes: &Bytes,
+        altda_commitment: Option<&AltDACommitment>,
+        skip: Option<usize>,
+    ) -> Result<(usize, ProxyDerivationStage), HokuleaHostError> {
+        let mut errors = vec![];
+        let mut retryable = false;
+        for index in self.endpoint_order(skip) {
+            let endpoint = &self.endpoints[index];
+            match self
+                .fetch_data_from_endpoint(endpoint, altda_commitment_bytes, altda_commitment)
+                .await
+            {
+                Ok(derivation_stage) => return Ok((index, derivation_stage)),
+                Err(e) => {
+                    warn!(
+                        target = "hokulea-host",
+                        "eigenda-proxy {} failed: {e}", endpoint.base
+                    );
+                    retryable |= e.is_retryable();
+                    errors.push(format!("{}: {e}", endpoint.base));
+                }
+            }
+        }
+        let msg = format!("no eigenda-proxy returned data: [{}]", errors.join(", "));
+        Err(if retryable {
+            HokuleaHostError::ProxyTransport(msg)
+        } else {
+            HokuleaHostError::ProxyProtocolViolation(msg)
+        })
+    }
+
+    /// Indices of the endpoints to query in order, except `skip`. Endpoints whose circuit is
+    /// open go last, such that a healthy proxy is preferred over waiting for the cooldown.
+    fn endpoint_order(&self, skip: Option<usize>) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.endpoints.len())
+            .filter(|index| Some(*index) != skip)
+            .collect();
+        // stable, the configured order is kept among the healthy endpoints
+        order.sort_by_key(|index| {
+            self.endpoints[*index]
+                .circuit_breaker
+                .remaining_open()
+                .unwrap_or_default()
+        });
+        order
+    }
+
+    /// Fetch data from a single proxy, and check the encoded payload against the cert if given
+    async fn fetch_data_from_endpoint(
+        &self,
+        endpoint: &ProxyEndpoint,
+        altda_commitment_bytes: &Bytes,
+        altda_commitment: Option<&AltDACommitment>,
+    ) -> Result<ProxyDerivationStage, HokuleaHostError> {
         // Build URL with proper joining and query parameters
         let commitment_hex = hex::encode(altda_commitment_bytes);
-        let mut url = self
+        let mut url = endpoint
             .base
             .join(&format!("get/{commitment_hex}"))
-            .map_err(|e| anyhow!("failed to construct URL: {e}"))?;
+            .map_err(|e| {
+                HokuleaHostError::ProxyTransport(format!("failed to construct URL: {e}"))
+            })?;
         url.set_query(Some(GET_QUERY_PARAMS_ENCODED_PAYLOAD));
 
         // Fetch the encoded payload from the eigenda network
-        let response = self
-            .inner
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| anyhow!("failed to fetch eigenda encoded payload: {e}"))?;
+        let (status, body) = self.get_with_retry(endpoint, url).await?;
 
         let mut is_valid_cert = true;
-        let mut pass_recency_check = true;
+        let mut pass_recency_check = None;
         let mut encoded_payload = vec![];
 
         // Handle response based on status code
-        if !response.status().is_success() {
+        if !status.is_success() {
             // Handle non-success responses. 400 errors are not possible here since the altda commitment
-            // deserialized successfully to reach this point. All 500 errors trigger infinite retries.
-            if response.status().as_u16() != HTTP_RESPONSE_STATUS_CODE_TEAPOT {
-                // The error is handled by host library in kona, currently this triggers an infinite retry loop.
-                // https://github.com/op-rs/kona/blob/98543fe6d91f755b2383941391d93aa9bea6c9ab/bin/host/src/backend/online.rs#L135
-                return Err(anyhow!(
-                    "failed to fetch eigenda encoded payload, status {:?}",
-                    response.error_for_status()
-                ));
+            // deserialized successfully to reach this point. 500 errors have already been retried.
+            if status.as_u16() != HTTP_RESPONSE_STATUS_CODE_TEAPOT {
+                // Retrying does not help, the status is not one of a transient failure
+                return Err(HokuleaHostError::ProxyProtocolViolation(format!(
+                    "failed to fetch eigenda encoded payload, status {status}"
+                )));
             }
 
             // Handle teapot (418) status code with DerivationError
-            let status_code: DerivationError = response
-                .json()
-                .await
-                .map_err(|e| anyhow!("failed to deserialize 418 body: {e}"))?;
+            let status_code: DerivationError = serde_json::from_slice(&body).map_err(|e| {
+                HokuleaHostError::ProxyProtocolViolation(format!(
+                    "failed to deserialize 418 body: {e}"
+                ))
+            })?;
 
             match status_code.into() {
                 HostHandlerError::HokuleaPreimageError(c) => match c {
                     HokuleaPreimageError::InvalidCert => is_valid_cert = false,
                 },
-                HostHandlerError::HokuleaRecencyCheckError => pass_recency_check = false,
+                HostHandlerError::HokuleaRecencyCheckError => pass_recency_check = Some(false),
                 HostHandlerError::HokuleaEncodedPayloadDecodingError(e)
                 | HostHandlerError::IllogicalStatusCodeError(e)
                 | HostHandlerError::UndefinedStatusCodeError(e) => {
-                    return Err(anyhow!("failed to handle http response: {e}"))
+                    return Err(HokuleaHostError::ProxyProtocolViolation(format!(
+                        "failed to handle http response: {e}"
+                    )))
                 }
             }
         } else {
             // Handle success response
-            encoded_payload = response
-                .bytes()
-                .await
-                .map_err(|e| {
-                    anyhow!("should be able to get encoded payload from http response {e}")
-                })?
-                .into();
+            encoded_payload = body.into();
+        }
+
+        // A misbehaving proxy would otherwise only be caught by the batch verification in the
+        // zkVM, after its payload is cached and stored along with the validity of the cert
+        if let Some(altda_commitment) = altda_commitment {
+            if is_valid_cert && pass_recency_check != Some(false) {
+                check_encoded_payload(&encoded_payload, altda_commitment).map_err(|e| {
+                    HokuleaHostError::MalformedPayload {
+                        digest: altda_commitment.to_digest(),
+                        reason: e.to_string(),
+                    }
+                })?;
+            }
         }
 
         let derivation_stage = ProxyDerivationStage {
@@ -133,6 +447,132 @@ impl OnlineEigenDAPreimageProvider {
         Ok(derivation_stage)
     }
 
+    /// Fetch the input data of a keccak altda commitment from proxy, and check it against the
+    /// keccak256 hash. The proxies are failed over in order, the hash makes cross-checking moot.
+    /// Once every proxy failed, the error is retryable if any proxy failed for a transient reason.
+    pub async fn fetch_keccak_preimage_from_proxy(
+        &self,
+        commitment: B256,
+    ) -> Result<Bytes, HokuleaHostError> {
+        // the relays only serve EigenDA blobs
+        if self.endpoints.is_empty() {
+            return Err(HokuleaHostError::InvalidHint(format!(
+                "keccak commitment {commitment} needs an eigenda-proxy, none is configured"
+            )));
+        }
+        let mut errors = vec![];
+        let mut retryable = false;
+        for index in self.endpoint_order(None) {
+            let endpoint = &self.endpoints[index];
+            match self
+                .fetch_keccak_preimage_from_endpoint(endpoint, commitment)
+                .await
+            {
+                Ok(input_data) => return Ok(input_data),
+                Err(e) => {
+                    warn!(
+                        target = "hokulea-host",
+                        "eigenda-proxy {} failed: {e}", endpoint.base
+                    );
+                    retryable |= e.is_retryable();
+                    errors.push(format!("{}: {e}", endpoint.base));
+                }
+            }
+        }
+        let msg = format!(
+            "no eigenda-proxy returned keccak preimage: [{}]",
+            errors.join(", ")
+        );
+        Err(if retryable {
+            HokuleaHostError::ProxyTransport(msg)
+        } else {
+            HokuleaHostError::ProxyProtocolViolation(msg)
+        })
+    }
+
+    async fn fetch_keccak_preimage_from_endpoint(
+        &self,
+        endpoint: &ProxyEndpoint,
+        commitment: B256,
+    ) -> Result<Bytes, HokuleaHostError> {
+        // keccak commitment is routed by its commitment type byte, no query parameter is needed
+        let commitment_hex = hex::encode(GenericAltDACommitment::Keccak(commitment).to_bytes());
+        let url = endpoint
+            .base
+            .join(&format!("get/{commitment_hex}"))
+            .map_err(|e| {
+                HokuleaHostError::ProxyTransport(format!("failed to construct URL: {e}"))
+            })?;
+
+        let (status, input_data) = self.get_with_retry(endpoint, url).await?;
+        if !status.is_success() {
+            // Retrying does not help, the status is not one of a transient failure
+            return Err(HokuleaHostError::ProxyProtocolViolation(format!(
+                "failed to fetch keccak preimage, status {status}"
+            )));
+        }
+
+        // the client would fail to read a preimage not matching the hash, stop early
+        if keccak256(&input_data) != commitment {
+            return Err(HokuleaHostError::ProxyProtocolViolation(format!(
+                "keccak preimage returned by proxy does not match commitment {commitment}"
+            )));
+        }
+        Ok(input_data)
+    }
+
+    /// Sends a GET request to proxy and reads the response body, following the
+    /// [ProxyRetryPolicy]. Transport errors, timeouts, 5xx and 429 are retried with backoff, any
+    /// other status, including the 418 carrying a derivation error, is returned to the caller.
+    async fn get_with_retry(
+        &self,
+        endpoint: &ProxyEndpoint,
+        url: Url,
+    ) -> Result<(StatusCode, Bytes), HokuleaHostError> {
+        let circuit_breaker = &endpoint.circuit_breaker;
+        circuit_breaker.wait_until_closed().await;
+
+        let policy = &self.retry_policy;
+        let mut attempt = 0;
+        loop {
+            let error = match self.get_once(url.clone()).await {
+                Ok((status, _)) if is_retryable_status(status) => format!("status {status}"),
+                Ok(response) => {
+                    circuit_breaker.record_success();
+                    return Ok(response);
+                }
+                Err(e) => e.to_string(),
+            };
+
+            if attempt >= policy.max_retries {
+                circuit_breaker.record_failure(policy);
+                return Err(HokuleaHostError::ProxyTransport(format!(
+                    "{error}, after {} retries",
+                    policy.max_retries
+                )));
+            }
+            let backoff = policy.backoff(attempt);
+            warn!(
+                target = "hokulea-host",
+                "request to eigenda-proxy {} failed: {error}, retrying in {backoff:?}",
+                endpoint.base
+            );
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
+    async fn get_once(&self, url: Url) -> reqwest::Result<(StatusCode, Bytes)> {
+        let response = self
+            .inner
+            .get(url)
+            .timeout(self.retry_policy.request_timeout)
+            .send()
+            .await?;
+        let status = response.status();
+        Ok((status, response.bytes().await?.into()))
+    }
+
     /// Cached fetch. `&mut self` provides exclusive access, no Mutex needed.
     async fn get_or_fetch_payload(
         &mut self,
@@ -145,12 +585,8 @@ impl OnlineEigenDAPreimageProvider {
             }
         }
 
-        // Not in cache, fetch from proxy
-        let altda_commitment_bytes = altda_commitment.to_rlp_bytes().into();
-        let derivation_stage = self
-            .fetch_data_from_proxy(&altda_commitment_bytes)
-            .await
-            .map_err(|e| HokuleaErrorKind::Temporary(format!("fetch failed: {e}")))?;
+        // Not in cache, fetch from proxy. Only transient failures are worth retrying
+        let derivation_stage = self.fetch_data_for_commitment(altda_commitment).await?;
 
         // Update cache with new entry
         self.last_entry = Some((altda_commitment.clone(), derivation_stage.clone()));
@@ -158,6 +594,56 @@ impl OnlineEigenDAPreimageProvider {
     }
 }
 
+/// Server errors and rate limiting are transient, the request is worth retrying
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Checks that the encoded payload returned for a valid cert is well formed and committed to by
+/// the cert. The trailing zeros of the encoded payload may be trimmed by the proxy.
+fn check_encoded_payload(encoded_payload: &[u8], altda_commitment: &AltDACommitment) -> Result<()> {
+    // Verify encoded_payload data is properly formatted
+    if encoded_payload.is_empty()
+        || !encoded_payload
+            .len()
+            .is_multiple_of(BYTES_PER_FIELD_ELEMENT)
+    {
+        return Err(anyhow!(
+            "length {} is not a nonzero multiple of {BYTES_PER_FIELD_ELEMENT}",
+            encoded_payload.len()
+        ));
+    }
+
+    // Preliminary defense check against malicious eigenda proxy host
+    // verify there is an empty byte for every 31 bytes. This is a harder constraint than field element range check.
+    for chunk in encoded_payload.chunks_exact(BYTES_PER_FIELD_ELEMENT) {
+        // very conservative check on Field element range. It allows us to detect
+        // misbehaving at the host side when providing the field element. So we can stop early.
+        // the field element of on bn254 curve is some number less than 2^254
+        // that means both 255 and 254 th bits must be 0. out of conservation, we require the
+        // 253 bit to be 0. It aligns with our encoding scheme below that the first 8bits
+        // should be 0.
+        // Field elements are interpreted as big endian
+        // We don't have the check that the first 8 bits are zero, because it is a more restrictive check, that might
+        // affect future payload encoding scheme
+        if chunk[0] & 0b1110_0000 != 0 {
+            return Err(anyhow!("invalid field element encoding"));
+        }
+    }
+
+    // encoded_payload has identical length as eigenda blob once zero filled
+    let blob_length = altda_commitment.get_num_field_element() as usize * BYTES_PER_FIELD_ELEMENT;
+    if encoded_payload.len() > blob_length {
+        return Err(anyhow!(
+            "{} bytes are longer than the blob of {blob_length} bytes",
+            encoded_payload.len()
+        ));
+    }
+    let mut padded_payload = encoded_payload.to_vec();
+    padded_payload.resize(blob_length, 0);
+    check_kzg_commitment(&padded_payload, altda_commitment)
+}
+
 #[async_trait]
 impl EigenDAPreimageProvider for OnlineEigenDAPreimageProvider {
     type Error = HokuleaErrorKind;
@@ -181,4 +667,24 @@ impl EigenDAPreimageProvider for OnlineEigenDAPreimageProvider {
             encoded_payload: derivation_stage.encoded_payload.into(),
         })
     }
+
+    /// Get the input data of a keccak altda commitment
+    async fn get_keccak_preimage(
+        &mut self,
+        commitment: B256,
+    ) -> Result<Option<Bytes>, Self::Error> {
+        self.fetch_keccak_preimage_from_proxy(commitment)
+            .await
+            .map(Some)
+            .map_err(HokuleaErrorKind::from)
+    }
+
+    /// Get the recency verdict of proxy
+    async fn get_recency_verdict(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<Option<bool>, Self::Error> {
+        let derivation_stage = self.get_or_fetch_payload(altda_commitment).await?;
+        Ok(derivation_stage.pass_recency_check)
+    }
 }
@@ -0,0 +1,87 @@
+//! Error type of the host when fetching the preimages of an EigenDA hint
+
+use alloy_primitives::B256;
+use hokulea_eigenda::HokuleaErrorKind;
+
+use crate::eigenda_preimage::ProxyMismatchError;
+use crate::relay::RelayError;
+
+/// Failure to fetch or store the preimages of an EigenDA hint. Kona retries a failed hint
+/// forever, which only helps when the failure is transient, see [HokuleaHostError::is_retryable].
+#[derive(Debug, thiserror::Error)]
+pub enum HokuleaHostError {
+    /// The hint does not carry a well formed altda commitment
+    #[error("invalid hint: {0}")]
+    InvalidHint(String),
+    /// eigenda-proxy could not be reached, timed out, or kept answering with a server error or
+    /// rate limiting after all the retries
+    #[error("eigenda-proxy transport error: {0}")]
+    ProxyTransport(String),
+    /// eigenda-proxy answered what it should not, such as a client error status or an undefined
+    /// derivation status code
+    #[error("eigenda-proxy protocol violation: {0}")]
+    ProxyProtocolViolation(String),
+    /// Two eigenda-proxy instances disagree on the data of a cert
+    #[error(transparent)]
+    ProxyMismatch(#[from] ProxyMismatchError),
+    /// The call to the cert verifier on L1 failed after all the retries
+    #[error("cert verifier error: {0}")]
+    CertVerifier(String),
+    /// No relay returned the blob of a cert, with the failure of every relay listed in the cert
+    #[error("no relay returned blob {blob_key}: [{}]", join_errors(.errors))]
+    RelayRetrieval {
+        /// Key of the blob
+        blob_key: B256,
+        /// Failure of every relay, in the order they were queried
+        errors: Vec<RelayError>,
+    },
+    /// The encoded payload returned for a cert cannot be what the cert commits to
+    #[error("malformed encoded payload of cert {digest}: {reason}")]
+    MalformedPayload {
+        /// Digest of the cert
+        digest: B256,
+        /// What is wrong with the encoded payload
+        reason: String,
+    },
+    /// The key-value store failed to write a preimage
+    #[error("key-value store failure: {0}")]
+    KvStore(String),
+}
+
+impl HokuleaHostError {
+    /// Returns if fetching the hint again may succeed. A misbehaving proxy, a malformed hint or a
+    /// failing key-value store is not fixed by retrying, and needs the attention of an operator.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            HokuleaHostError::ProxyTransport(_) | HokuleaHostError::CertVerifier(_) => true,
+            // another relay may be back, none is there to retry if the cert lists no relay
+            HokuleaHostError::RelayRetrieval { errors, .. } => {
+                errors.iter().any(RelayError::is_retryable)
+            }
+            HokuleaHostError::InvalidHint(_)
+            | HokuleaHostError::ProxyProtocolViolation(_)
+            | HokuleaHostError::ProxyMismatch(_)
+            | HokuleaHostError::MalformedPayload { .. }
+            | HokuleaHostError::KvStore(_) => false,
+        }
+    }
+}
+
+fn join_errors(errors: &[RelayError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// define conversion error
+impl From<HokuleaHostError> for HokuleaErrorKind {
+    fn from(e: HokuleaHostError) -> Self {
+        if e.is_retryable() {
+            HokuleaErrorKind::Temporary(format!("fetch failed: {e}"))
+        } else {
+            HokuleaErrorKind::Critical(e.to_string())
+        }
+    }
+}
@@ -1,20 +1,21 @@
-use alloy_primitives::{keccak256, Bytes};
+use alloy_primitives::{Bytes, B256};
 
 use crate::cfg::SingleChainHostWithEigenDA;
 use crate::eigenda_preimage::OnlineEigenDAPreimageProvider;
-use anyhow::{anyhow, Result};
+use crate::errors::HokuleaHostError;
+use anyhow::Result;
 use async_trait::async_trait;
 use eigenda_cert::AltDACommitment;
 use hokulea_eigenda::{
-    BYTES_PER_FIELD_ELEMENT, RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY,
-    RESERVED_EIGENDA_API_BYTE_INDEX,
+    EigenDAPreimageKey, EncodedPayloadFetchMode, HokuleaErrorKind, BYTES_PER_ENCODED_PAYLOAD_CHUNK,
+    BYTES_PER_FIELD_ELEMENT,
 };
 use hokulea_proof::hint::ExtendedHintType;
 use kona_host::SharedKeyValueStore;
 use kona_host::{single::SingleChainHintHandler, HintHandler, OnlineHostBackendCfg};
 use kona_preimage::{PreimageKey, PreimageKeyType};
-use kona_proof::Hint;
-use tracing::{info, trace};
+use kona_proof::{Hint, HintType};
+use tracing::{error, info, trace};
 
 /// The [HintHandler] for the [SingleChainHostWithEigenDA].
 #[derive(Debug, Clone, Copy)]
@@ -34,9 +35,32 @@ impl HintHandler for SingleChainHintHandlerWithEigenDA {
         // route the hint to the right fetcher based on the hint type.
         match hint.ty {
             ExtendedHintType::EigenDACert => {
-                fetch_eigenda_hint(hint.data, &providers.eigenda_preimage_provider, kv).await?;
+                // the data is stored once the prefetch of the cert is done, unless it was still
+                // queued, which is then skipped in favor of fetching the cert right away
+                if let Some(prefetcher) = &providers.eigenda_prefetcher {
+                    if prefetcher.wait(&hint.data).await {
+                        return Ok(());
+                    }
+                }
+                fetch_eigenda_hint(hint.data, &providers.eigenda_preimage_provider, kv)
+                    .await
+                    .map_err(hint_error)?;
+            }
+            ExtendedHintType::AltDAKeccakCommitment => {
+                fetch_keccak_hint(hint.data, &providers.eigenda_preimage_provider, kv)
+                    .await
+                    .map_err(hint_error)?;
             }
             ExtendedHintType::Original(ty) => {
+                // the client reads the header and the receipts of an L1 block before deriving
+                // from its transactions, which leaves time to retrieve the certs it carries
+                if let Some(prefetcher) = &providers.eigenda_prefetcher {
+                    if matches!(ty, HintType::L1BlockHeader | HintType::L1Receipts)
+                        && hint.data.len() == B256::len_bytes()
+                    {
+                        prefetcher.prefetch_block(B256::from_slice(&hint.data), kv.clone());
+                    }
+                }
                 let hint_original = Hint {
                     ty,
                     data: hint.data,
@@ -58,22 +82,23 @@ impl HintHandler for SingleChainHintHandlerWithEigenDA {
 /// We insert the cert_validity, and encoded_payload_data.
 /// For all returned errors, they are handled by the kona host library, and currently this triggers an infinite retry loop.
 /// <https://github.com/op-rs/kona/blob/98543fe6d91f755b2383941391d93aa9bea6c9ab/bin/host/src/backend/online.rs#L135>
+/// Hence [HokuleaHostError::is_retryable] tells if the error is temporary or critical.
 pub async fn fetch_eigenda_hint(
     altda_commitment_bytes: Bytes,
     eigenda_preimage_provider: &OnlineEigenDAPreimageProvider,
     kv: SharedKeyValueStore,
-) -> Result<()> {
+) -> Result<(), HokuleaHostError> {
     trace!(target: "fetcher_with_eigenda_support", "Fetching EigenDA hint: {altda_commitment_bytes}");
 
     // Convert commitment bytes to AltDACommitment
-    let altda_commitment: AltDACommitment = altda_commitment_bytes
-        .as_ref()
-        .try_into()
-        .map_err(|e| anyhow!("failed to parse AltDACommitment: {e}"))?;
+    let altda_commitment: AltDACommitment =
+        altda_commitment_bytes.as_ref().try_into().map_err(|e| {
+            HokuleaHostError::InvalidHint(format!("failed to parse AltDACommitment: {e}"))
+        })?;
 
     // Fetch preimage data and process response
     let derivation_stage = eigenda_preimage_provider
-        .fetch_data_from_proxy(&altda_commitment_bytes)
+        .fetch_data_for_commitment(&altda_commitment)
         .await?;
 
     // Write validity and correct offchain code version to key-value store
@@ -84,6 +109,14 @@ pub async fn fetch_eigenda_hint(
     )
     .await?;
 
+    // Write the recency verdict of proxy if it decided one, the client only reads it for debugging
+    store_recency_verdict(
+        kv.clone(),
+        &altda_commitment,
+        derivation_stage.pass_recency_check,
+    )
+    .await?;
+
     // If cert or offchain version is invalid, log and return early
     if !derivation_stage.is_valid_cert {
         info!(
@@ -96,7 +129,7 @@ pub async fn fetch_eigenda_hint(
 
     // If cert does not pass recency check, discard it
     // the hokulea client would not request anything further
-    if !derivation_stage.pass_recency_check {
+    if derivation_stage.pass_recency_check == Some(false) {
         info!(
             target = "hokulea-host",
             "discard a cert for not passing recency test {}",
@@ -105,87 +138,149 @@ pub async fn fetch_eigenda_hint(
         return Ok(());
     }
 
-    // Store encoded payload data field-by-field in key-value store
+    // Store encoded payload data in key-value store, in the layout the client fetches it with
     store_encoded_payload(
         kv.clone(),
         &altda_commitment,
         derivation_stage.encoded_payload,
+        eigenda_preimage_provider.fetch_mode(),
     )
     .await?;
 
     Ok(())
 }
 
+/// Converts the failure of an EigenDA or keccak hint into the error kind kona expects
+fn hint_error(e: HokuleaHostError) -> anyhow::Error {
+    // kona retries the hint regardless, which does not resolve a critical error
+    if !e.is_retryable() {
+        error!(
+            target = "hokulea-host",
+            "EigenDA hint failed, retrying does not help: {e}"
+        );
+    }
+    HokuleaErrorKind::from(e).into()
+}
+
+/// Fetch the input data of a keccak altda commitment and insert it into the key-value store,
+/// addressed by its keccak256 hash. Errors are classified as for [fetch_eigenda_hint].
+pub async fn fetch_keccak_hint(
+    commitment_bytes: Bytes,
+    eigenda_preimage_provider: &OnlineEigenDAPreimageProvider,
+    kv: SharedKeyValueStore,
+) -> Result<(), HokuleaHostError> {
+    trace!(target: "fetcher_with_eigenda_support", "Fetching keccak hint: {commitment_bytes}");
+
+    if commitment_bytes.len() != B256::len_bytes() {
+        return Err(HokuleaHostError::InvalidHint(format!(
+            "invalid keccak commitment length {}",
+            commitment_bytes.len()
+        )));
+    }
+    let commitment = B256::from_slice(&commitment_bytes);
+
+    let input_data = eigenda_preimage_provider
+        .fetch_keccak_preimage_from_proxy(commitment)
+        .await?;
+
+    let mut kv_write_lock = kv.write().await;
+    kv_write_lock
+        .set(
+            PreimageKey::new(*commitment, PreimageKeyType::Keccak256).into(),
+            input_data.into(),
+        )
+        .map_err(kv_store_error)?;
+
+    Ok(())
+}
+
 /// Store certificate validity in key-value store
 async fn store_cert_validity(
     kv: SharedKeyValueStore,
     altda_commitment: &AltDACommitment,
     is_valid: bool,
-) -> Result<()> {
+) -> Result<(), HokuleaHostError> {
     // Acquire a lock on the key-value store
     let mut kv_write_lock = kv.write().await;
-    let mut validity_address = altda_commitment.digest_template();
-    validity_address[RESERVED_EIGENDA_API_BYTE_INDEX] = RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY;
+    let validity_key = EigenDAPreimageKey::validity_key(altda_commitment);
+
+    kv_write_lock
+        .set(
+            PreimageKey::new(*validity_key.hash(), PreimageKeyType::GlobalGeneric).into(),
+            vec![is_valid as u8],
+        )
+        .map_err(kv_store_error)?;
+
+    Ok(())
+}
+
+/// Store the recency verdict of proxy in key-value store. An undecided recency is stored as an
+/// empty preimage, which carries no verdict, since the client would otherwise wait for a preimage
+/// never written.
+async fn store_recency_verdict(
+    kv: SharedKeyValueStore,
+    altda_commitment: &AltDACommitment,
+    pass_recency_check: Option<bool>,
+) -> Result<(), HokuleaHostError> {
+    let mut kv_write_lock = kv.write().await;
+    let recency_key = EigenDAPreimageKey::recency_key(altda_commitment);
 
-    kv_write_lock.set(
-        PreimageKey::new(*keccak256(validity_address), PreimageKeyType::GlobalGeneric).into(),
-        vec![is_valid as u8],
-    )?;
+    kv_write_lock
+        .set(
+            PreimageKey::new(*recency_key.hash(), PreimageKeyType::GlobalGeneric).into(),
+            pass_recency_check
+                .map(|pass| vec![pass as u8])
+                .unwrap_or_default(),
+        )
+        .map_err(kv_store_error)?;
 
     Ok(())
 }
 
-/// Store encoded payload data in key-value store
+/// Store encoded payload data in key-value store, a field element or a chunk per preimage
+/// depending on `fetch_mode`. The encoded payload was checked against the cert when fetched.
 async fn store_encoded_payload(
     kv: SharedKeyValueStore,
     altda_commitment: &AltDACommitment,
     encoded_payload: Vec<u8>,
-) -> Result<()> {
+    fetch_mode: EncodedPayloadFetchMode,
+) -> Result<(), HokuleaHostError> {
+    // The trailing zeros of the encoded payload may be trimmed by the proxy, they are zero filled
+    // up to the blob length
+    let blob_length = altda_commitment.get_num_field_element() as usize * BYTES_PER_FIELD_ELEMENT;
+    let mut padded_payload = encoded_payload;
+    padded_payload.resize(blob_length, 0);
+
     // Acquire a lock on the key-value store
     let mut kv_write_lock = kv.write().await;
-    // encoded_payload has identical length as eigenda blob
-    let blob_length_fe = altda_commitment.get_num_field_element();
-    // Verify encoded_payload data is properly formatted
-    assert!(encoded_payload.len().is_multiple_of(32) && !encoded_payload.is_empty());
-
-    // Preliminary defense check against malicious eigenda proxy host
-    // verify there is an empty byte for every 31 bytes. This is a harder constraint than field element range check.
-    for chunk in encoded_payload.chunks_exact(BYTES_PER_FIELD_ELEMENT) {
-        // very conservative check on Field element range. It allows us to detect
-        // misbehaving at the host side when providing the field element. So we can stop early.
-        // the field element of on bn254 curve is some number less than 2^254
-        // that means both 255 and 254 th bits must be 0. out of conservation, we require the
-        // 253 bit to be 0. It aligns with our encoding scheme below that the first 8bits
-        // should be 0.
-        // Field elements are interpreted as big endian
-        // We don't have the check that the first 8 bits are zero, because it is a more restrictive check, that might
-        // affect future payload encoding scheme
-        if chunk[0] & 0b1110_0000 != 0 {
-            return Err(anyhow!("invalid field element encoding"));
-        }
-    }
 
-    let fetch_num_element = (encoded_payload.len() / BYTES_PER_FIELD_ELEMENT) as u64;
-    // Store each field element
-    let mut field_element_key = altda_commitment.digest_template();
-    for i in 0..blob_length_fe {
-        field_element_key[72..].copy_from_slice(i.to_be_bytes().as_ref());
-        let encoded_payload_key_hash = keccak256(field_element_key.as_ref());
-
-        if i < fetch_num_element {
-            // Store actual encoded payload data
-            kv_write_lock.set(
-                PreimageKey::new(*encoded_payload_key_hash, PreimageKeyType::GlobalGeneric).into(),
-                encoded_payload[(i as usize) << 5..(i as usize + 1) << 5].to_vec(),
-            )?;
-        } else {
-            // Fill remaining elements with zeros
-            kv_write_lock.set(
-                PreimageKey::new(*encoded_payload_key_hash, PreimageKeyType::GlobalGeneric).into(),
-                vec![0u8; 32],
-            )?;
-        }
+    let (preimage_len, preimage_key): (usize, fn(&AltDACommitment, u64) -> EigenDAPreimageKey) =
+        match fetch_mode {
+            EncodedPayloadFetchMode::FieldElement => (
+                BYTES_PER_FIELD_ELEMENT,
+                EigenDAPreimageKey::field_element_key,
+            ),
+            EncodedPayloadFetchMode::Chunk => (
+                BYTES_PER_ENCODED_PAYLOAD_CHUNK,
+                EigenDAPreimageKey::encoded_payload_chunk_key,
+            ),
+        };
+    // Store each field element or chunk, the last chunk of a blob smaller than a chunk is as long
+    // as the blob
+    for (i, preimage) in padded_payload.chunks(preimage_len).enumerate() {
+        let preimage_key_hash = preimage_key(altda_commitment, i as u64).hash();
+        kv_write_lock
+            .set(
+                PreimageKey::new(*preimage_key_hash, PreimageKeyType::GlobalGeneric).into(),
+                preimage.to_vec(),
+            )
+            .map_err(kv_store_error)?;
     }
 
     Ok(())
 }
+
+/// The key-value store failed to write a preimage
+fn kv_store_error(e: anyhow::Error) -> HokuleaHostError {
+    HokuleaHostError::KvStore(e.to_string())
+}
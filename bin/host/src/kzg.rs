@@ -0,0 +1,24 @@
+use alloy_primitives::U256;
+use anyhow::{anyhow, Result};
+use eigenda_cert::AltDACommitment;
+use hokulea_compute_proof::compute_kzg_commitment;
+
+/// Checks that the encoded payload is the one committed to by the cert
+pub fn check_kzg_commitment(
+    encoded_payload: &[u8],
+    altda_commitment: &AltDACommitment,
+) -> Result<()> {
+    let commitment = compute_kzg_commitment(encoded_payload)
+        .map_err(|e| anyhow!("failed to compute kzg commitment: {e}"))?;
+    let expected = altda_commitment.get_kzg_commitment();
+    if commitment[..32] != expected.x.to_be_bytes::<32>()
+        || commitment[32..] != expected.y.to_be_bytes::<32>()
+    {
+        return Err(anyhow!(
+            "kzg commitment ({}, {}) of the encoded payload does not match the one of the cert",
+            U256::from_be_slice(&commitment[..32]),
+            U256::from_be_slice(&commitment[32..]),
+        ));
+    }
+    Ok(())
+}
@@ -1,11 +1,25 @@
 pub mod eigenda_preimage;
 
+pub mod errors;
+
 pub mod cfg;
 
+pub mod cert_verifier;
+
 pub mod handler;
 
+pub mod kzg;
+
+pub mod prefetch;
+
+pub mod relay;
+
+pub mod retry;
+
 pub mod status_code;
 
+pub mod witness_cache;
+
 use tracing_subscriber::{filter::LevelFilter, prelude::*, EnvFilter};
 pub fn init_tracing_subscriber(verbosity_level: u8) -> anyhow::Result<(), anyhow::Error> {
     // Convert verbosity_level to a LevelFilter
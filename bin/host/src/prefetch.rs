@@ -0,0 +1,381 @@
+use alloy_consensus::Transaction;
+use alloy_primitives::{keccak256, Address, Bytes, B256};
+use alloy_provider::{Provider, RootProvider};
+use anyhow::{anyhow, Result};
+use eigenda_cert::GenericAltDACommitment;
+use hokulea_eigenda::ALTDA_DERIVATION_VERSION;
+use kona_host::SharedKeyValueStore;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{watch, Semaphore};
+use tracing::{debug, warn};
+
+use crate::eigenda_preimage::OnlineEigenDAPreimageProvider;
+use crate::handler::fetch_eigenda_hint;
+
+/// Default number of EigenDA certs prefetched at the same time
+pub const DEFAULT_PREFETCH_CONCURRENCY: usize = 4;
+/// Number of L1 blocks remembered as scanned, the oldest is forgotten first
+const MAX_SCANNED_BLOCKS: usize = 1024;
+/// Number of certs remembered as prefetched, the oldest is forgotten first and skipped if it is
+/// still queued. It bounds the prefetches waiting for a permit, whatever is posted to the inbox.
+const MAX_PREFETCHED_CERTS: usize = 1024;
+
+/// Progress of the prefetch of a cert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PrefetchState {
+    /// Waiting for a permit of the semaphore
+    Queued,
+    /// Fetching the data of the cert
+    Running,
+    /// Dropped while queued, as the cert was hinted or forgotten
+    Skipped,
+    /// Finished, with whether the data was stored
+    Done(bool),
+}
+
+/// Fetches the data of the EigenDA certs posted to the batcher inbox of an L1 block into the
+/// key-value store, ahead of the client hinting them. It hides the latency of the retrieval from
+/// the derivation loop, which otherwise waits on every cert in turn.
+///
+/// The prefetch is best effort, a failure is logged and the cert is fetched again once hinted.
+/// Transactions to the batcher inbox are not filtered by sender, the batcher address lives in
+/// the system config, hence certs discarded by the derivation may be prefetched too. A hinted cert
+/// is thus never held up behind the queued prefetches, only behind its own prefetch if it is
+/// running.
+#[derive(Debug, Clone)]
+pub struct EigenDAPrefetcher {
+    /// Provider of the L1 execution node, to read the transactions of a block
+    l1_provider: RootProvider,
+    /// Fetches the data of the certs, shared with the hint handler
+    eigenda_preimage_provider: OnlineEigenDAPreimageProvider,
+    /// Address of the batcher inbox of the rollup
+    batch_inbox_address: Address,
+    /// Bounds the number of certs fetched at the same time
+    semaphore: Arc<Semaphore>,
+    /// Hashes of the L1 blocks recently scanned
+    scanned_blocks: Arc<Mutex<BoundedMap<()>>>,
+    /// Progress of the recently prefetched certs, keyed by the hash of the commitment bytes
+    prefetched: Arc<Mutex<BoundedMap<Arc<watch::Sender<PrefetchState>>>>>,
+}
+
+impl EigenDAPrefetcher {
+    /// Creates a new instance of the [EigenDAPrefetcher], fetching up to `concurrency` certs at
+    /// the same time
+    pub fn new(
+        l1_provider: RootProvider,
+        eigenda_preimage_provider: OnlineEigenDAPreimageProvider,
+        batch_inbox_address: Address,
+        concurrency: usize,
+    ) -> Self {
+        Self {
+            l1_provider,
+            eigenda_preimage_provider,
+            batch_inbox_address,
+            semaphore: Arc::new(Semaphore::new(concurrency)),
+            scanned_blocks: Arc::new(Mutex::new(BoundedMap::new(MAX_SCANNED_BLOCKS))),
+            prefetched: Arc::new(Mutex::new(BoundedMap::new(MAX_PREFETCHED_CERTS))),
+        }
+    }
+
+    /// Scans the L1 block in the background, and prefetches the EigenDA certs posted to the
+    /// batcher inbox. A block recently scanned is not scanned again.
+    pub fn prefetch_block(&self, block_hash: B256, kv: SharedKeyValueStore) {
+        {
+            let mut scanned_blocks = self
+                .scanned_blocks
+                .lock()
+                .expect("prefetcher lock poisoned");
+            if !scanned_blocks.insert(block_hash, ()) {
+                return;
+            }
+            scanned_blocks.evict();
+        }
+
+        let prefetcher = self.clone();
+        tokio::spawn(async move {
+            match prefetcher.scan_block(block_hash).await {
+                Ok(commitments) => {
+                    for altda_commitment_bytes in commitments {
+                        prefetcher.prefetch(altda_commitment_bytes, kv.clone());
+                    }
+                }
+                Err(e) => warn!(
+                    target = "hokulea-host",
+                    "failed to scan L1 block {block_hash} for EigenDA certs: {e}"
+                ),
+            }
+        });
+    }
+
+    /// Returns the commitment bytes of the EigenDA certs posted to the batcher inbox in the block
+    pub async fn scan_block(&self, block_hash: B256) -> Result<Vec<Bytes>> {
+        let block = self
+            .l1_provider
+            .get_block_by_hash(block_hash)
+            .full()
+            .await
+            .map_err(|e| anyhow!("failed to fetch block: {e}"))?
+            .ok_or_else(|| anyhow!("block not found"))?;
+        let calldata = block
+            .transactions
+            .txns()
+            .map(|tx| (tx.to(), tx.input().as_ref()));
+        Ok(eigenda_commitments(calldata, self.batch_inbox_address))
+    }
+
+    /// Fetches the data of the cert into the key-value store in the background, unless it is
+    /// recently prefetched. The commitment bytes are the ones carried by the EigenDA cert hint.
+    pub fn prefetch(&self, altda_commitment_bytes: Bytes, kv: SharedKeyValueStore) {
+        let key = keccak256(&altda_commitment_bytes);
+        // the prefetch is running as soon as a permit is free
+        let permit = self.semaphore.clone().try_acquire_owned().ok();
+        let state = Arc::new(
+            watch::channel(match permit {
+                Some(_) => PrefetchState::Running,
+                None => PrefetchState::Queued,
+            })
+            .0,
+        );
+        {
+            let mut prefetched = self.prefetched.lock().expect("prefetcher lock poisoned");
+            if !prefetched.insert(key, state.clone()) {
+                return;
+            }
+            if let Some(forgotten) = prefetched.evict() {
+                skip_if_queued(&forgotten);
+            }
+        }
+
+        let mut receiver = state.subscribe();
+        let semaphore = self.semaphore.clone();
+        let eigenda_preimage_provider = self.eigenda_preimage_provider.clone();
+        tokio::spawn(async move {
+            let _permit = match permit {
+                Some(permit) => permit,
+                None => {
+                    let permit = tokio::select! {
+                        permit = semaphore.acquire_owned() => permit,
+                        _ = receiver.wait_for(|state| *state == PrefetchState::Skipped) => return,
+                    };
+                    let Ok(permit) = permit else {
+                        return;
+                    };
+                    // skipped right before the permit was granted
+                    if !state.send_if_modified(|state| {
+                        let queued = *state == PrefetchState::Queued;
+                        if queued {
+                            *state = PrefetchState::Running;
+                        }
+                        queued
+                    }) {
+                        return;
+                    }
+                    permit
+                }
+            };
+            let stored =
+                match fetch_eigenda_hint(altda_commitment_bytes, &eigenda_preimage_provider, kv)
+                    .await
+                {
+                    Ok(()) => {
+                        debug!(target = "hokulea-host", "prefetched EigenDA cert {key}");
+                        true
+                    }
+                    Err(e) => {
+                        warn!(
+                            target = "hokulea-host",
+                            "failed to prefetch EigenDA cert {key}: {e}"
+                        );
+                        false
+                    }
+                };
+            state.send_replace(PrefetchState::Done(stored));
+        });
+    }
+
+    /// Waits for the prefetch of the cert if it is running, a queued prefetch is skipped for the
+    /// caller to fetch the cert right away. Returns if its data was stored, false if it was not
+    /// prefetched, skipped or the prefetch failed.
+    pub async fn wait(&self, altda_commitment_bytes: &Bytes) -> bool {
+        let mut receiver = {
+            let prefetched = self.prefetched.lock().expect("prefetcher lock poisoned");
+            let Some(state) = prefetched.get(&keccak256(altda_commitment_bytes)) else {
+                return false;
+            };
+            skip_if_queued(state);
+            state.subscribe()
+        };
+        // an error if the prefetch task is gone without an outcome
+        let stored = receiver
+            .wait_for(|state| matches!(state, PrefetchState::Skipped | PrefetchState::Done(_)))
+            .await
+            .is_ok_and(|state| *state == PrefetchState::Done(true));
+        stored
+    }
+}
+
+/// Skips the prefetch if it did not start yet
+fn skip_if_queued(state: &watch::Sender<PrefetchState>) {
+    state.send_if_modified(|state| {
+        let queued = *state == PrefetchState::Queued;
+        if queued {
+            *state = PrefetchState::Skipped;
+        }
+        queued
+    });
+}
+
+/// Map bounded to its `capacity` most recently inserted keys
+#[derive(Debug)]
+struct BoundedMap<V> {
+    entries: HashMap<B256, V>,
+    /// Keys in insertion order, the oldest first
+    order: VecDeque<B256>,
+    capacity: usize,
+}
+
+impl<V> BoundedMap<V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn get(&self, key: &B256) -> Option<&V> {
+        self.entries.get(key)
+    }
+
+    /// Inserts the entry, returns false if the key is already present
+    fn insert(&mut self, key: B256, value: V) -> bool {
+        if self.entries.contains_key(&key) {
+            return false;
+        }
+        self.entries.insert(key, value);
+        self.order.push_back(key);
+        true
+    }
+
+    /// Removes the oldest entry if the map exceeds its capacity
+    fn evict(&mut self) -> Option<V> {
+        if self.order.len() <= self.capacity {
+            return None;
+        }
+        let key = self.order.pop_front()?;
+        self.entries.remove(&key)
+    }
+}
+
+/// Returns the commitment bytes of the EigenDA certs among the calldata of the transactions,
+/// given as recipient and input. Only the transactions to the batcher inbox carrying an altda
+/// commitment are considered, as the EigenDA data source of the client does.
+pub fn eigenda_commitments<'a>(
+    calldata: impl IntoIterator<Item = (Option<Address>, &'a [u8])>,
+    batch_inbox_address: Address,
+) -> Vec<Bytes> {
+    calldata
+        .into_iter()
+        .filter(|(to, _)| *to == Some(batch_inbox_address))
+        .filter_map(|(_, input)| match input.split_first() {
+            Some((&ALTDA_DERIVATION_VERSION, commitment)) => Some(commitment),
+            _ => None,
+        })
+        .filter(|commitment| {
+            matches!(
+                GenericAltDACommitment::try_from(*commitment),
+                Ok(GenericAltDACommitment::EigenDA(_))
+            )
+        })
+        .map(Bytes::copy_from_slice)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eigenda_cert::AltDACommitment;
+
+    #[test]
+    fn test_eigenda_commitments() {
+        let batch_inbox_address = Address::repeat_byte(0xff);
+        let altda_commitment_bytes = AltDACommitment::from_canonical_json(include_str!(
+            "../../../crates/eigenda-cert/testdata/altda_commitment_v3.json"
+        ))
+        .unwrap()
+        .to_rlp_bytes();
+        let with_version = |bytes: &[u8]| [&[ALTDA_DERIVATION_VERSION], bytes].concat();
+        let eigenda_calldata = with_version(&altda_commitment_bytes);
+
+        struct Case {
+            calldata: Vec<(Option<Address>, Vec<u8>)>,
+            commitments: Vec<Bytes>,
+        }
+        let cases = vec![
+            Case {
+                calldata: vec![(Some(batch_inbox_address), eigenda_calldata.clone())],
+                commitments: vec![altda_commitment_bytes.clone().into()],
+            },
+            // not to the batcher inbox
+            Case {
+                calldata: vec![
+                    (Some(Address::ZERO), eigenda_calldata.clone()),
+                    (None, eigenda_calldata.clone()),
+                ],
+                commitments: vec![],
+            },
+            // frames posted as calldata, keccak commitments and a truncated cert
+            Case {
+                calldata: vec![
+                    (Some(batch_inbox_address), vec![0x00, 0xaa]),
+                    (Some(batch_inbox_address), vec![]),
+                    (
+                        Some(batch_inbox_address),
+                        with_version(&GenericAltDACommitment::Keccak(B256::ZERO).to_bytes()),
+                    ),
+                    (
+                        Some(batch_inbox_address),
+                        eigenda_calldata[..eigenda_calldata.len() / 2].to_vec(),
+                    ),
+                    (Some(batch_inbox_address), eigenda_calldata.clone()),
+                ],
+                commitments: vec![altda_commitment_bytes.into()],
+            },
+        ];
+
+        for case in cases {
+            let calldata = case
+                .calldata
+                .iter()
+                .map(|(to, input)| (*to, input.as_slice()));
+            assert_eq!(
+                eigenda_commitments(calldata, batch_inbox_address),
+                case.commitments
+            );
+        }
+    }
+
+    #[test]
+    fn test_bounded_map() {
+        let key = |i: u8| B256::repeat_byte(i);
+        let mut map = BoundedMap::new(2);
+        assert!(map.insert(key(1), 1));
+        assert!(map.evict().is_none());
+        // a key is inserted once
+        assert!(!map.insert(key(1), 2));
+        assert!(map.insert(key(2), 2));
+        assert!(map.evict().is_none());
+
+        // the oldest key is evicted beyond the capacity
+        assert!(map.insert(key(3), 3));
+        assert_eq!(map.evict(), Some(1));
+        assert!(map.evict().is_none());
+        assert_eq!(map.get(&key(1)), None);
+        assert_eq!(map.get(&key(2)), Some(&2));
+        assert_eq!(map.get(&key(3)), Some(&3));
+        // an evicted key can be inserted again
+        assert!(map.insert(key(1), 4));
+        assert_eq!(map.evict(), Some(2));
+    }
+}
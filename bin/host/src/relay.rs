@@ -0,0 +1,350 @@
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use eigenda_cert::relay::{GetBlobReply, GetBlobRequest, GET_BLOB_PATH};
+use eigenda_cert::{AltDACommitment, CertView};
+use hokulea_compute_proof::encoded_payload_from_blob;
+use hokulea_eigenda::BYTES_PER_FIELD_ELEMENT;
+use reqwest::Url;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tonic::codec::ProstCodec;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::Code;
+use tracing::warn;
+
+use crate::cert_verifier::CertValidityChecker;
+use crate::eigenda_preimage::ProxyDerivationStage;
+use crate::errors::HokuleaHostError;
+use crate::kzg::check_kzg_commitment;
+use crate::retry::{CircuitBreaker, ProxyRetryPolicy};
+
+/// A failed `GetBlob` request
+#[derive(Debug, thiserror::Error)]
+pub enum RelayError {
+    /// The relay could not be reached, timed out, or answered with a server error or rate
+    /// limiting. Retrying may help.
+    #[error("relay {relay_key} is unavailable: {msg}")]
+    Unavailable {
+        /// Key of the relay
+        relay_key: u32,
+        /// What went wrong
+        msg: String,
+    },
+    /// The relay is not configured, or answered with an error retrying does not fix, such as an
+    /// unknown blob or a reply longer than the blob of the cert
+    #[error("relay {relay_key} failed: {msg}")]
+    Failed {
+        /// Key of the relay
+        relay_key: u32,
+        /// What went wrong
+        msg: String,
+    },
+    /// The relay returned a blob which cannot be what the cert commits to, as it is longer than
+    /// the blob of the cert or does not match its kzg commitment
+    #[error("relay {relay_key} returned a malformed blob: {msg}")]
+    MalformedBlob {
+        /// Key of the relay
+        relay_key: u32,
+        /// What is wrong with the blob
+        msg: String,
+    },
+}
+
+impl RelayError {
+    /// Returns if the request is worth retrying on the same relay
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, RelayError::Unavailable { .. })
+    }
+}
+
+/// Fetches blobs from the EigenDA relays
+#[async_trait]
+pub trait RelayClient: Debug + Send + Sync {
+    /// Fetch the blob of `blob_key` from the relay of `relay_key`. A blob longer than
+    /// `max_blob_len` bytes is rejected.
+    async fn get_blob(
+        &self,
+        relay_key: u32,
+        blob_key: B256,
+        max_blob_len: usize,
+    ) -> Result<Vec<u8>, RelayError>;
+}
+
+/// [RelayClient] calling the `GetBlob` gRPC method of the relays
+#[derive(Debug, Clone)]
+pub struct GrpcRelayClient {
+    /// Channels to the relays, keyed by relay key. They connect on first use.
+    channels: HashMap<u32, Channel>,
+}
+
+impl GrpcRelayClient {
+    /// Creates a new instance of the [GrpcRelayClient] from the urls of the relays, keyed by relay
+    /// key. A request times out after `timeout`, a blob of 16MiB takes a while to be transferred.
+    pub fn new(relays: &HashMap<u32, Url>, timeout: Duration) -> Result<Self> {
+        let mut channels = HashMap::new();
+        for (relay_key, url) in relays {
+            let mut endpoint = Endpoint::from_shared(url.to_string())
+                .map_err(|e| anyhow!("invalid url {url} of relay {relay_key}: {e}"))?
+                .timeout(timeout);
+            if url.scheme() == "https" {
+                endpoint = endpoint
+                    .tls_config(ClientTlsConfig::new().with_webpki_roots())
+                    .map_err(|e| anyhow!("invalid tls config of relay {relay_key}: {e}"))?;
+            }
+            channels.insert(*relay_key, endpoint.connect_lazy());
+        }
+        Ok(Self { channels })
+    }
+}
+
+#[async_trait]
+impl RelayClient for GrpcRelayClient {
+    async fn get_blob(
+        &self,
+        relay_key: u32,
+        blob_key: B256,
+        max_blob_len: usize,
+    ) -> Result<Vec<u8>, RelayError> {
+        let channel = self
+            .channels
+            .get(&relay_key)
+            .ok_or_else(|| RelayError::Failed {
+                relay_key,
+                msg: "no url configured".into(),
+            })?;
+
+        // a blob is up to 16MiB, beyond the default limit of 4MiB, but a relay must not make the
+        // host buffer more than the blob the cert commits to
+        let mut grpc = tonic::client::Grpc::new(channel.clone())
+            .max_decoding_message_size(GetBlobReply::max_encoded_len(max_blob_len));
+        grpc.ready().await.map_err(|e| RelayError::Unavailable {
+            relay_key,
+            msg: format!("not ready: {e}"),
+        })?;
+        let request = GetBlobRequest {
+            blob_key: blob_key.to_vec(),
+        };
+        let reply: tonic::Response<GetBlobReply> = grpc
+            .unary(
+                tonic::Request::new(request),
+                GET_BLOB_PATH.parse().expect("valid path"),
+                ProstCodec::default(),
+            )
+            .await
+            .map_err(|status| {
+                let msg = format!("failed to return blob {blob_key}: {status}");
+                if is_retryable_code(status.code()) {
+                    RelayError::Unavailable { relay_key, msg }
+                } else {
+                    RelayError::Failed { relay_key, msg }
+                }
+            })?;
+        Ok(reply.into_inner().blob)
+    }
+}
+
+/// The gRPC counterparts of transport errors, server errors and rate limiting, worth retrying
+fn is_retryable_code(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unavailable
+            | Code::DeadlineExceeded
+            | Code::ResourceExhausted
+            | Code::Aborted
+            | Code::Internal
+            | Code::Unknown
+    )
+}
+
+/// Retrieves the data of a cert directly from EigenDA instead of eigenda-proxy, in the same
+/// [ProxyDerivationStage] shape. The validity of the cert is decided by a [CertValidityChecker],
+/// and the blob is fetched from the relays listed in the blob certificate.
+///
+/// The requests to the relays follow the retries, backoff and circuit breaker of the
+/// [ProxyRetryPolicy], with a circuit breaker per relay. Their timeout is the one of the
+/// [RelayClient], a blob takes longer to be transferred than a proxy answer.
+#[derive(Debug, Clone)]
+pub struct RelayRetriever {
+    relay_client: Arc<dyn RelayClient>,
+    cert_validity_checker: Arc<dyn CertValidityChecker>,
+    retry_policy: ProxyRetryPolicy,
+    /// Throttles the requests to a relay once it keeps failing, keyed by relay key. Shared by
+    /// all clones of the retriever.
+    circuit_breakers: Arc<Mutex<HashMap<u32, CircuitBreaker>>>,
+}
+
+impl RelayRetriever {
+    /// Creates a new instance of the [RelayRetriever]
+    pub fn new(
+        relay_client: Arc<dyn RelayClient>,
+        cert_validity_checker: Arc<dyn CertValidityChecker>,
+    ) -> Self {
+        Self {
+            relay_client,
+            cert_validity_checker,
+            retry_policy: ProxyRetryPolicy::default(),
+            circuit_breakers: Arc::default(),
+        }
+    }
+
+    /// Sets the retries, backoff and circuit breaker of the requests to the relays
+    pub fn with_retry_policy(mut self, retry_policy: ProxyRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Fetch data of the altda commitment. The recency of a cert depends on the L1 block
+    /// including it, which the host does not know, hence it is left undecided to the client.
+    /// The blob of a valid cert is fetched from its relays in order, failing over to the next
+    /// relay on errors, including a blob not matching the kzg commitment of the cert. Once every
+    /// relay returned a malformed blob, the encoded payload is deemed malformed.
+    pub async fn fetch_data(
+        &self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<ProxyDerivationStage, HokuleaHostError> {
+        let is_valid_cert = self
+            .is_valid_cert_with_retry(altda_commitment)
+            .await
+            .map_err(|e| HokuleaHostError::CertVerifier(e.to_string()))?;
+        if !is_valid_cert {
+            return Ok(ProxyDerivationStage {
+                is_valid_cert: false,
+                pass_recency_check: None,
+                encoded_payload: vec![],
+            });
+        }
+
+        let versioned_cert = &altda_commitment.versioned_cert;
+        let blob_key = versioned_cert.blob_header().hash();
+        let mut errors = vec![];
+        for relay_key in versioned_cert.relay_keys() {
+            match self
+                .fetch_encoded_payload(*relay_key, blob_key, altda_commitment)
+                .await
+            {
+                Ok(encoded_payload) => {
+                    return Ok(ProxyDerivationStage {
+                        is_valid_cert: true,
+                        pass_recency_check: None,
+                        encoded_payload,
+                    })
+                }
+                Err(e) => {
+                    warn!(target = "hokulea-host", "{e}");
+                    errors.push(e);
+                }
+            }
+        }
+        let malformed = !errors.is_empty()
+            && errors
+                .iter()
+                .all(|e| matches!(e, RelayError::MalformedBlob { .. }));
+        let error = HokuleaHostError::RelayRetrieval { blob_key, errors };
+        if malformed {
+            return Err(HokuleaHostError::MalformedPayload {
+                digest: altda_commitment.to_digest(),
+                reason: error.to_string(),
+            });
+        }
+        Err(error)
+    }
+
+    /// Fetch the blob from a single relay, and convert it into the encoded payload
+    async fn fetch_encoded_payload(
+        &self,
+        relay_key: u32,
+        blob_key: B256,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<Vec<u8>, RelayError> {
+        let malformed = |msg: String| RelayError::MalformedBlob { relay_key, msg };
+        // the trailing zeros of a blob may be trimmed, the length is bound by the cert
+        let blob_len = altda_commitment.get_num_field_element() as usize * BYTES_PER_FIELD_ELEMENT;
+        let mut blob = self
+            .get_blob_with_retry(relay_key, blob_key, blob_len)
+            .await?;
+        if blob.len() > blob_len {
+            return Err(malformed(format!(
+                "blob of {} bytes is longer than the {blob_len} bytes of the cert",
+                blob.len()
+            )));
+        }
+        blob.resize(blob_len, 0);
+
+        let encoded_payload = encoded_payload_from_blob(&blob)
+            .map_err(|e| malformed(format!("invalid blob: {e}")))?;
+        check_kzg_commitment(&encoded_payload, altda_commitment)
+            .map_err(|e| malformed(e.to_string()))?;
+        Ok(encoded_payload)
+    }
+
+    /// Decide the validity of the cert, retrying a failed check with the backoff of the
+    /// [ProxyRetryPolicy]
+    async fn is_valid_cert_with_retry(&self, altda_commitment: &AltDACommitment) -> Result<bool> {
+        let policy = &self.retry_policy;
+        let mut attempt = 0;
+        loop {
+            match self
+                .cert_validity_checker
+                .is_valid_cert(altda_commitment)
+                .await
+            {
+                Err(e) if attempt < policy.max_retries => {
+                    let backoff = policy.backoff(attempt);
+                    warn!(
+                        target = "hokulea-host",
+                        "cert validity check failed: {e}, retrying in {backoff:?}"
+                    );
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Fetch the blob from a single relay following the [ProxyRetryPolicy]. Unavailability is
+    /// retried with backoff, any other error is returned to the caller.
+    async fn get_blob_with_retry(
+        &self,
+        relay_key: u32,
+        blob_key: B256,
+        max_blob_len: usize,
+    ) -> Result<Vec<u8>, RelayError> {
+        let circuit_breaker = self
+            .circuit_breakers
+            .lock()
+            .expect("circuit breakers lock poisoned")
+            .entry(relay_key)
+            .or_default()
+            .clone();
+        circuit_breaker.wait_until_closed().await;
+
+        let policy = &self.retry_policy;
+        let mut attempt = 0;
+        loop {
+            let error = match self
+                .relay_client
+                .get_blob(relay_key, blob_key, max_blob_len)
+                .await
+            {
+                Err(e) if e.is_retryable() => e,
+                result => {
+                    circuit_breaker.record_success();
+                    return result;
+                }
+            };
+
+            if attempt >= policy.max_retries {
+                circuit_breaker.record_failure(policy);
+                return Err(error);
+            }
+            let backoff = policy.backoff(attempt);
+            warn!(target = "hokulea-host", "{error}, retrying in {backoff:?}");
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::warn;
+
+// a 16MiB blob can take a while to be retrieved from the DA network
+pub const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 60_000;
+pub const DEFAULT_MAX_RETRIES: u32 = 5;
+pub const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
+pub const DEFAULT_MAX_BACKOFF_MS: u64 = 30_000;
+pub const DEFAULT_CIRCUIT_BREAKER_THRESHOLD: u32 = 5;
+pub const DEFAULT_CIRCUIT_BREAKER_COOLDOWN_MS: u64 = 30_000;
+
+/// How the host talks to eigenda-proxy when a request fails. Kona retries a failed hint forever
+/// and without any delay, so the policy bounds the retries of a single hint and throttles the
+/// proxy once it keeps failing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyRetryPolicy {
+    /// Timeout of a request, from connecting until the response body is read
+    pub request_timeout: Duration,
+    /// Number of retries of a request after the first attempt
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled on every further retry
+    pub initial_backoff: Duration,
+    /// Upper bound of the backoff
+    pub max_backoff: Duration,
+    /// Number of consecutive failed requests, each after all its retries, that opens the circuit.
+    /// 0 disables the circuit breaker
+    pub circuit_breaker_threshold: u32,
+    /// How long the circuit stays open before a request is let through again
+    pub circuit_breaker_cooldown: Duration,
+}
+
+impl Default for ProxyRetryPolicy {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_millis(DEFAULT_REQUEST_TIMEOUT_MS),
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: Duration::from_millis(DEFAULT_INITIAL_BACKOFF_MS),
+            max_backoff: Duration::from_millis(DEFAULT_MAX_BACKOFF_MS),
+            circuit_breaker_threshold: DEFAULT_CIRCUIT_BREAKER_THRESHOLD,
+            circuit_breaker_cooldown: Duration::from_millis(DEFAULT_CIRCUIT_BREAKER_COOLDOWN_MS),
+        }
+    }
+}
+
+impl ProxyRetryPolicy {
+    /// Exponential backoff before the retry following `attempt`, with equal jitter: a random delay
+    /// between half and all of the exponential one, such that hosts do not retry in lockstep
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+        let half = exponential / 2;
+        half + jitter(exponential - half)
+    }
+}
+
+/// A random duration in `[0, max]`. The randomness comes from the per-process random keys of
+/// [RandomState], which is plenty for spreading retries.
+fn jitter(max: Duration) -> Duration {
+    let nanos = max.as_nanos() as u64;
+    if nanos == 0 {
+        return Duration::ZERO;
+    }
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    Duration::from_nanos(hasher.finish() % (nanos + 1))
+}
+
+/// Stops querying eigenda-proxy for a cooldown once a number of requests failed in a row. It is
+/// shared by all clones of the provider.
+///
+/// Since kona retries a failed hint immediately, failing fast while the circuit is open would only
+/// spin. Instead, a request waits until the cooldown elapses, then goes through as a probe. A
+/// successful probe closes the circuit, a failed one opens it again.
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBreaker {
+    state: Arc<Mutex<CircuitBreakerState>>,
+}
+
+#[derive(Debug, Default)]
+struct CircuitBreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Remaining time the circuit stays open, if open
+    pub fn remaining_open(&self) -> Option<Duration> {
+        let state = self.state.lock().expect("circuit breaker lock poisoned");
+        state
+            .open_until
+            .and_then(|open_until| open_until.checked_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Waits until the circuit is no longer open
+    pub async fn wait_until_closed(&self) {
+        while let Some(remaining) = self.remaining_open() {
+            tokio::time::sleep(remaining).await;
+        }
+    }
+
+    /// Closes the circuit
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().expect("circuit breaker lock poisoned");
+        state.consecutive_failures = 0;
+        state.open_until = None;
+    }
+
+    /// Counts a failed request, and opens the circuit once the threshold of the policy is reached
+    pub fn record_failure(&self, policy: &ProxyRetryPolicy) {
+        let mut state = self.state.lock().expect("circuit breaker lock poisoned");
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if policy.circuit_breaker_threshold != 0
+            && state.consecutive_failures >= policy.circuit_breaker_threshold
+        {
+            warn!(
+                target = "hokulea-host",
+                "{} requests failed in a row, pausing for {:?}",
+                state.consecutive_failures,
+                policy.circuit_breaker_cooldown
+            );
+            state.open_until = Some(Instant::now() + policy.circuit_breaker_cooldown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = ProxyRetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+            ..Default::default()
+        };
+
+        struct Case {
+            attempt: u32,
+            min: Duration,
+            max: Duration,
+        }
+        let cases = [
+            Case {
+                attempt: 0,
+                min: Duration::from_millis(50),
+                max: Duration::from_millis(100),
+            },
+            Case {
+                attempt: 2,
+                min: Duration::from_millis(200),
+                max: Duration::from_millis(400),
+            },
+            // capped by max_backoff
+            Case {
+                attempt: 4,
+                min: Duration::from_millis(500),
+                max: Duration::from_millis(1000),
+            },
+            // no overflow
+            Case {
+                attempt: u32::MAX,
+                min: Duration::from_millis(500),
+                max: Duration::from_millis(1000),
+            },
+        ];
+        for case in cases {
+            for _ in 0..100 {
+                let backoff = policy.backoff(case.attempt);
+                assert!(backoff >= case.min && backoff <= case.max, "{backoff:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let policy = ProxyRetryPolicy {
+            circuit_breaker_threshold: 2,
+            circuit_breaker_cooldown: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let breaker = CircuitBreaker::default();
+
+        breaker.record_failure(&policy);
+        assert!(breaker.remaining_open().is_none());
+        breaker.record_failure(&policy);
+        assert!(breaker.remaining_open().is_some());
+
+        // clones share the state
+        breaker.clone().record_success();
+        assert!(breaker.remaining_open().is_none());
+        breaker.record_failure(&policy);
+        assert!(breaker.remaining_open().is_none());
+
+        // disabled
+        let disabled = ProxyRetryPolicy {
+            circuit_breaker_threshold: 0,
+            ..policy
+        };
+        for _ in 0..10 {
+            breaker.record_failure(&disabled);
+        }
+        assert!(breaker.remaining_open().is_none());
+    }
+}
@@ -0,0 +1,380 @@
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, warn};
+
+use crate::eigenda_preimage::ProxyDerivationStage;
+
+/// Version of the on-disk entry layout, bumped whenever the layout or what an entry guarantees
+/// changes such that entries written by an older host are treated as a miss. Since version 2, the
+/// encoded payload of an entry was checked against the cert before being cached.
+const ENTRY_FORMAT_VERSION: u8 = 2;
+/// Entry header: format version followed by a flag byte
+const ENTRY_HEADER_LEN: usize = 2;
+const FLAG_VALID_CERT: u8 = 0b01;
+const FLAG_PASS_RECENCY_CHECK: u8 = 0b10;
+const FLAG_RECENCY_DECIDED: u8 = 0b100;
+/// Extension of the entry files, anything else in the directory is left alone
+const ENTRY_EXTENSION: &str = "witness";
+const TMP_EXTENSION: &str = "tmp";
+
+/// A content-addressed cache on disk of what eigenda-proxy returned for a cert, keyed by the cert
+/// digest. Re-running the same block range then does not query the proxy again.
+///
+/// Every entry is a file named after the digest, holding the validity, the recency verdict and
+/// the encoded payload. When the total size of the entries exceeds `max_bytes`, the least
+/// recently used entries are evicted. The modification time of an entry file is the value of an
+/// access counter, in seconds since the unix epoch, bumped by every insertion and hit. The order of
+/// the accesses then survives a restart and does not depend on the resolution of the file system
+/// clock.
+#[derive(Debug)]
+pub struct DiskWitnessCache {
+    /// The directory holding the entries
+    dir: PathBuf,
+    /// Upper bound on the total size of the entries in bytes
+    max_bytes: u64,
+    /// Serializes insertion and eviction within the host
+    write_lock: Mutex<()>,
+    /// The last access time given to an entry
+    access_counter: AtomicU64,
+}
+
+impl DiskWitnessCache {
+    /// Opens the cache at `dir`, creating the directory if it does not exist, and evicts entries
+    /// until it fits in `max_bytes`
+    pub fn open(dir: impl Into<PathBuf>, max_bytes: u64) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .map_err(|e| anyhow!("failed to create witness cache dir {}: {e}", dir.display()))?;
+        let cache = Self {
+            dir,
+            max_bytes,
+            write_lock: Mutex::new(()),
+            access_counter: AtomicU64::new(0),
+        };
+        // continue after the most recently used entry
+        let last_access = cache
+            .entries()?
+            .iter()
+            .filter_map(|(_, _, modified)| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|access| access.as_secs())
+            .max()
+            .unwrap_or_default();
+        cache.access_counter.store(last_access, Ordering::Relaxed);
+        cache.evict(0)?;
+        Ok(cache)
+    }
+
+    /// Returns the cached derivation stage of the cert digest if any. An unreadable or corrupted
+    /// entry is removed and reported as a miss.
+    pub fn get(&self, digest: &B256) -> Option<ProxyDerivationStage> {
+        let path = self.entry_path(digest);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!(
+                    target = "hokulea-host",
+                    "failed to read witness cache entry {digest}: {e}"
+                );
+                return None;
+            }
+        };
+
+        let Some(derivation_stage) = decode_entry(&bytes) else {
+            warn!(
+                target = "hokulea-host",
+                "removing corrupted witness cache entry {digest}"
+            );
+            let _ = fs::remove_file(&path);
+            return None;
+        };
+
+        // refresh the entry such that it is evicted last
+        if let Err(e) = File::options()
+            .append(true)
+            .open(&path)
+            .and_then(|f| self.touch(&f))
+        {
+            debug!(
+                target = "hokulea-host",
+                "failed to touch witness cache entry {digest}: {e}"
+            );
+        }
+
+        Some(derivation_stage)
+    }
+
+    /// Inserts the derivation stage of the cert digest, evicting the least recently used entries
+    /// to stay within the size limit. An entry larger than the limit is not cached.
+    pub fn insert(&self, digest: &B256, derivation_stage: &ProxyDerivationStage) -> Result<()> {
+        let entry = encode_entry(derivation_stage);
+        let entry_len = entry.len() as u64;
+        if entry_len > self.max_bytes {
+            debug!(
+                target = "hokulea-host",
+                "witness of {digest} with {entry_len} bytes exceeds the cache limit"
+            );
+            return Ok(());
+        }
+
+        let _guard = self
+            .write_lock
+            .lock()
+            .map_err(|_| anyhow!("witness cache lock poisoned"))?;
+
+        self.evict(entry_len)?;
+
+        // write to a temporary file then rename, such that a crash never leaves a partial entry
+        let path = self.entry_path(digest);
+        let tmp_path = path.with_extension(TMP_EXTENSION);
+        let mut file = File::create(&tmp_path)
+            .map_err(|e| anyhow!("failed to create witness cache entry {digest}: {e}"))?;
+        file.write_all(&entry)
+            .and_then(|_| self.touch(&file))
+            .and_then(|_| file.sync_all())
+            .map_err(|e| anyhow!("failed to write witness cache entry {digest}: {e}"))?;
+        fs::rename(&tmp_path, &path)
+            .map_err(|e| anyhow!("failed to persist witness cache entry {digest}: {e}"))?;
+
+        Ok(())
+    }
+
+    /// Removes the least recently used entries until `reserved` more bytes fit in the limit
+    fn evict(&self, reserved: u64) -> Result<()> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total + reserved <= self.max_bytes {
+            return Ok(());
+        }
+
+        // oldest first
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in entries {
+            if total + reserved <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(&path).map_err(|e| {
+                anyhow!(
+                    "failed to evict witness cache entry {}: {e}",
+                    path.display()
+                )
+            })?;
+            total -= len;
+            debug!(
+                target = "hokulea-host",
+                "evicted witness cache entry {}",
+                path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Lists path, size and modification time of all entries
+    fn entries(&self) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let read_dir = fs::read_dir(&self.dir).map_err(|e| {
+            anyhow!(
+                "failed to list witness cache dir {}: {e}",
+                self.dir.display()
+            )
+        })?;
+
+        let mut entries = vec![];
+        for dir_entry in read_dir.flatten() {
+            let path = dir_entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+            // an entry removed concurrently is simply skipped
+            let Ok(metadata) = dir_entry.metadata() else {
+                continue;
+            };
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((path, metadata.len(), modified));
+        }
+        Ok(entries)
+    }
+
+    /// Sets the modification time of an entry file to the next access time
+    fn touch(&self, file: &File) -> std::io::Result<()> {
+        let access = self.access_counter.fetch_add(1, Ordering::Relaxed) + 1;
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(access))
+    }
+
+    fn entry_path(&self, digest: &B256) -> PathBuf {
+        self.dir.join(format!("{digest:x}.{ENTRY_EXTENSION}"))
+    }
+}
+
+fn encode_entry(derivation_stage: &ProxyDerivationStage) -> Vec<u8> {
+    let mut flags = 0;
+    if derivation_stage.is_valid_cert {
+        flags |= FLAG_VALID_CERT;
+    }
+    if let Some(pass_recency_check) = derivation_stage.pass_recency_check {
+        flags |= FLAG_RECENCY_DECIDED;
+        if pass_recency_check {
+            flags |= FLAG_PASS_RECENCY_CHECK;
+        }
+    }
+
+    let mut entry = Vec::with_capacity(ENTRY_HEADER_LEN + derivation_stage.encoded_payload.len());
+    entry.push(ENTRY_FORMAT_VERSION);
+    entry.push(flags);
+    entry.extend_from_slice(&derivation_stage.encoded_payload);
+    entry
+}
+
+fn decode_entry(entry: &[u8]) -> Option<ProxyDerivationStage> {
+    let [version, flags, encoded_payload @ ..] = entry else {
+        return None;
+    };
+    let known_flags = FLAG_VALID_CERT | FLAG_PASS_RECENCY_CHECK | FLAG_RECENCY_DECIDED;
+    if *version != ENTRY_FORMAT_VERSION || flags & !known_flags != 0 {
+        return None;
+    }
+
+    Some(ProxyDerivationStage {
+        is_valid_cert: flags & FLAG_VALID_CERT != 0,
+        pass_recency_check: (flags & FLAG_RECENCY_DECIDED != 0)
+            .then_some(flags & FLAG_PASS_RECENCY_CHECK != 0),
+        encoded_payload: encoded_payload.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "hokulea-witness-cache-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn stage(
+        is_valid_cert: bool,
+        pass_recency_check: Option<bool>,
+        len: usize,
+    ) -> ProxyDerivationStage {
+        ProxyDerivationStage {
+            is_valid_cert,
+            pass_recency_check,
+            encoded_payload: vec![7u8; len],
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        struct Case {
+            stage: ProxyDerivationStage,
+        }
+        let cases = [
+            // valid cert with payload
+            Case {
+                stage: stage(true, None, 64),
+            },
+            // invalid cert
+            Case {
+                stage: stage(false, None, 0),
+            },
+            // cert failing the recency check
+            Case {
+                stage: stage(true, Some(false), 0),
+            },
+            // cert passing the recency check
+            Case {
+                stage: stage(true, Some(true), 64),
+            },
+        ];
+
+        let dir = test_dir("roundtrip");
+        let cache = DiskWitnessCache::open(&dir, 1 << 20).unwrap();
+        for (i, case) in cases.iter().enumerate() {
+            let digest = B256::with_last_byte(i as u8);
+            assert_eq!(cache.get(&digest), None);
+            cache.insert(&digest, &case.stage).unwrap();
+            assert_eq!(cache.get(&digest), Some(case.stage.clone()));
+        }
+
+        // entries survive a restart of the host
+        let reopened = DiskWitnessCache::open(&dir, 1 << 20).unwrap();
+        for (i, case) in cases.iter().enumerate() {
+            assert_eq!(
+                reopened.get(&B256::with_last_byte(i as u8)),
+                Some(case.stage.clone())
+            );
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_entry_is_a_miss() {
+        let dir = test_dir("corrupted");
+        let cache = DiskWitnessCache::open(&dir, 1 << 20).unwrap();
+        let digest = B256::with_last_byte(1);
+
+        for entry in [
+            vec![],
+            vec![ENTRY_FORMAT_VERSION],
+            vec![0xff, 0, 1],
+            vec![ENTRY_FORMAT_VERSION, 0xff],
+            // written by an older host
+            vec![0, FLAG_VALID_CERT],
+            // its encoded payload was not checked against the cert
+            vec![
+                1,
+                FLAG_VALID_CERT | FLAG_RECENCY_DECIDED | FLAG_PASS_RECENCY_CHECK,
+                0,
+            ],
+        ] {
+            fs::write(cache.entry_path(&digest), entry).unwrap();
+            assert_eq!(cache.get(&digest), None);
+            assert!(!cache.entry_path(&digest).exists());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_eviction() {
+        let dir = test_dir("eviction");
+        // room for two entries of 100 bytes payload
+        let entry_len = (ENTRY_HEADER_LEN + 100) as u64;
+        let cache = DiskWitnessCache::open(&dir, 2 * entry_len).unwrap();
+        let (a, b, c) = (
+            B256::with_last_byte(1),
+            B256::with_last_byte(2),
+            B256::with_last_byte(3),
+        );
+
+        cache.insert(&a, &stage(true, None, 100)).unwrap();
+        cache.insert(&b, &stage(true, None, 100)).unwrap();
+        // a hit makes a the most recently used entry
+        assert!(cache.get(&a).is_some());
+
+        cache.insert(&c, &stage(true, None, 100)).unwrap();
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+
+        // an entry larger than the limit is not cached, nor evicts anything
+        let d = B256::with_last_byte(4);
+        cache.insert(&d, &stage(true, None, 1000)).unwrap();
+        assert!(cache.get(&d).is_none());
+        assert!(cache.get(&a).is_some());
+
+        // the order of the accesses survives a restart, shrinking the limit evicts on open
+        let shrunk = DiskWitnessCache::open(&dir, entry_len).unwrap();
+        assert_eq!(shrunk.entries().unwrap().len(), 1);
+        assert!(shrunk.get(&a).is_some());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+//! Tests the cert validity check of the relay retrieval backend against a scripted mock of the
+//! L1 JSON-RPC.
+
+use alloy_primitives::{Address, B256};
+use alloy_provider::RootProvider;
+use canoe_bindings::StatusCode;
+use eigenda_cert::AltDACommitment;
+use hokulea_host_bin::cert_verifier::{CertValidityChecker, EthCallCertValidityChecker};
+use hokulea_mock_proxy::{MockProxy, ScriptedResponse};
+use reqwest::Url;
+
+fn altda_commitment() -> AltDACommitment {
+    AltDACommitment::from_canonical_json(include_str!(
+        "../../../crates/eigenda-cert/testdata/altda_commitment_v3.json"
+    ))
+    .unwrap()
+}
+
+/// The JSON-RPC answer to the first request of a provider
+fn json_rpc_result(status: u8) -> ScriptedResponse {
+    ScriptedResponse::new(
+        200,
+        format!(r#"{{"jsonrpc":"2.0","id":0,"result":"0x{status:064x}"}}"#).as_bytes(),
+    )
+}
+
+#[tokio::test]
+async fn test_eth_call_cert_validity_checker() {
+    struct Case {
+        response: ScriptedResponse,
+        // None if an error is expected
+        result: Option<bool>,
+    }
+
+    let cases = vec![
+        Case {
+            response: json_rpc_result(StatusCode::SUCCESS as u8),
+            result: Some(true),
+        },
+        Case {
+            response: json_rpc_result(StatusCode::INVALID_CERT as u8),
+            result: Some(false),
+        },
+        Case {
+            response: json_rpc_result(StatusCode::INTERNAL_ERROR as u8),
+            result: Some(false),
+        },
+        // the call reverted
+        Case {
+            response: ScriptedResponse::new(
+                200,
+                br#"{"jsonrpc":"2.0","id":0,"error":{"code":3,"message":"execution reverted"}}"#,
+            ),
+            result: None,
+        },
+        // not a status
+        Case {
+            response: ScriptedResponse::new(200, br#"{"jsonrpc":"2.0","id":0,"result":"0x"}"#),
+            result: None,
+        },
+        Case {
+            response: ScriptedResponse::new(503, b""),
+            result: None,
+        },
+    ];
+
+    let altda_commitment = altda_commitment();
+    for case in cases {
+        let mock = MockProxy::spawn_scripted(vec![case.response], "127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let l1_provider = RootProvider::new_http(Url::parse(&mock.url()).unwrap());
+        let checker = EthCallCertValidityChecker::new(l1_provider, B256::ZERO, 1)
+            .with_cert_verifier_address(Address::repeat_byte(1));
+
+        let result = checker.is_valid_cert(&altda_commitment).await;
+        assert_eq!(result.ok(), case.result);
+        // a single eth_call at the L1 head
+        assert_eq!(mock.requests(), 1);
+    }
+}
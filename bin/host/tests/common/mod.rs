@@ -0,0 +1,132 @@
+//! Helpers shared by the tests of the host against the test cert and local mocks of
+//! eigenda-proxy.
+
+#![allow(dead_code)]
+
+use alloy_primitives::U256;
+use eigenda_cert::{AltDACommitment, EigenDAVersionedCert, G1Point};
+use hokulea_compute_proof::compute_kzg_commitment;
+use hokulea_eigenda::{EigenDAPreimageKey, BYTES_PER_FIELD_ELEMENT};
+use hokulea_host_bin::eigenda_preimage::{OnlineEigenDAPreimageProvider, ProxyDerivationStage};
+use hokulea_host_bin::retry::ProxyRetryPolicy;
+pub use hokulea_mock_proxy::{Fixtures, MockProxy, ScriptedResponse};
+use kona_host::SharedKeyValueStore;
+use kona_preimage::{PreimageKey, PreimageKeyType};
+use reqwest::Url;
+use std::time::Duration;
+
+/// The test cert, committing to `encoded_payload` zero filled up to the blob length
+pub fn altda_commitment(encoded_payload: &[u8]) -> AltDACommitment {
+    altda_commitment_with_blob_length(encoded_payload, None)
+}
+
+/// The test cert, committing to `encoded_payload` zero filled up to `blob_length_fe` field
+/// elements if set, otherwise up to the blob length of the test cert
+pub fn altda_commitment_with_blob_length(
+    encoded_payload: &[u8],
+    blob_length_fe: Option<u32>,
+) -> AltDACommitment {
+    let mut altda_commitment = AltDACommitment::from_canonical_json(include_str!(
+        "../../../../crates/eigenda-cert/testdata/altda_commitment_v3.json"
+    ))
+    .unwrap();
+    let EigenDAVersionedCert::V3(cert) = &mut altda_commitment.versioned_cert else {
+        unreachable!("v3 test cert")
+    };
+    let blob_commitment = &mut cert
+        .blob_inclusion_info
+        .blob_certificate
+        .blob_header
+        .commitment;
+    if let Some(blob_length_fe) = blob_length_fe {
+        blob_commitment.length = blob_length_fe;
+    }
+    let mut padded_payload = encoded_payload.to_vec();
+    padded_payload.resize(blob_commitment.length as usize * BYTES_PER_FIELD_ELEMENT, 0);
+    let commitment = compute_kzg_commitment(&padded_payload).unwrap();
+    blob_commitment.commitment = G1Point {
+        x: U256::from_be_slice(&commitment[..32]),
+        y: U256::from_be_slice(&commitment[32..]),
+    };
+    altda_commitment
+}
+
+/// The preimage stored for `key`, if any
+pub async fn get(kv: &SharedKeyValueStore, key: &EigenDAPreimageKey) -> Option<Vec<u8>> {
+    kv.read()
+        .await
+        .get(PreimageKey::new(*key.hash(), PreimageKeyType::GlobalGeneric).into())
+}
+
+pub fn respond(status: u16, body: &[u8]) -> ScriptedResponse {
+    ScriptedResponse::new(status, body)
+}
+
+pub fn slow(delay: Duration) -> ScriptedResponse {
+    respond(200, b"slow").with_delay(delay)
+}
+
+pub fn derivation_error(status_code: u8) -> ScriptedResponse {
+    ScriptedResponse::derivation_error(status_code)
+}
+
+/// What a mock proxy answers, see [MockProxy::spawn] and [MockProxy::spawn_scripted]
+pub enum MockResponses {
+    Fixtures(Fixtures),
+    Script(Vec<ScriptedResponse>),
+}
+
+impl From<Fixtures> for MockResponses {
+    fn from(fixtures: Fixtures) -> Self {
+        Self::Fixtures(fixtures)
+    }
+}
+
+impl From<Vec<ScriptedResponse>> for MockResponses {
+    fn from(script: Vec<ScriptedResponse>) -> Self {
+        Self::Script(script)
+    }
+}
+
+/// Serves the fixtures or replays the script on a free local port
+pub async fn spawn_mock_proxy(responses: impl Into<MockResponses>) -> (Url, MockProxy) {
+    let addr = "127.0.0.1:0".parse().unwrap();
+    let mock = match responses.into() {
+        MockResponses::Fixtures(fixtures) => MockProxy::spawn(fixtures, addr).await,
+        MockResponses::Script(script) => MockProxy::spawn_scripted(script, addr).await,
+    }
+    .unwrap();
+    (Url::parse(&mock.url()).unwrap(), mock)
+}
+
+/// Short timeout and backoff, without circuit breaker
+pub fn test_policy(max_retries: u32) -> ProxyRetryPolicy {
+    ProxyRetryPolicy {
+        request_timeout: Duration::from_millis(200),
+        max_retries,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+        circuit_breaker_threshold: 0,
+        circuit_breaker_cooldown: Duration::ZERO,
+    }
+}
+
+/// A provider of the mock proxy at `url`, giving up on the first failure
+pub fn no_retry_provider(url: Url) -> OnlineEigenDAPreimageProvider {
+    OnlineEigenDAPreimageProvider::new_http(url).with_retry_policy(ProxyRetryPolicy {
+        max_retries: 0,
+        ..Default::default()
+    })
+}
+
+pub fn stage(
+    is_valid_cert: bool,
+    pass_recency_check: Option<bool>,
+    payload: &[u8],
+) -> ProxyDerivationStage {
+    ProxyDerivationStage {
+        is_valid_cert,
+        pass_recency_check,
+        encoded_payload: payload.to_vec(),
+    }
+}
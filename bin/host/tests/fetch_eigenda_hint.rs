@@ -0,0 +1,348 @@
+//! Exercises the hint handling of the host end to end against the mock eigenda-proxy, from the
+//! http request down to the preimages written in the key-value store.
+
+mod common;
+
+use alloy_primitives::{keccak256, Bytes, B256};
+use common::{
+    altda_commitment, altda_commitment_with_blob_length, get, no_retry_provider, spawn_mock_proxy,
+    Fixtures,
+};
+use eigenda_cert::GenericAltDACommitment;
+use hokulea_eigenda::{
+    EigenDAPreimageKey, EigenDAPreimageProvider, EncodedPayloadFetchMode,
+    BYTES_PER_ENCODED_PAYLOAD_CHUNK, BYTES_PER_FIELD_ELEMENT,
+};
+use hokulea_host_bin::handler::{fetch_eigenda_hint, fetch_keccak_hint};
+use hokulea_mock_proxy::MockProxyResponse;
+use hokulea_proof::eigenda_provider::OracleEigenDAPreimageProvider;
+use kona_host::{MemoryKeyValueStore, OfflineHostBackend, PreimageServer, SharedKeyValueStore};
+use kona_preimage::{
+    BidirectionalChannel, CommsClient, HintReader, HintWriter, OracleReader, OracleServer,
+    PreimageKey, PreimageKeyType,
+};
+use kona_proof::CachingOracle;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+#[tokio::test]
+async fn test_fetch_eigenda_hint() {
+    // two field elements, the host pads the rest of the blob with zeros
+    let mut encoded_payload = vec![0u8; 2 * BYTES_PER_FIELD_ELEMENT];
+    encoded_payload[1..BYTES_PER_FIELD_ELEMENT].fill(0xaa);
+    encoded_payload[BYTES_PER_FIELD_ELEMENT + 1..].fill(0xbb);
+    let altda_commitment = altda_commitment(&encoded_payload);
+    let blob_length_fe = altda_commitment.get_num_field_element();
+    // well formed field elements, not matching the kzg commitment of the cert
+    let mut forged_payload = encoded_payload.clone();
+    forged_payload[1] = 0xcc;
+
+    struct Case {
+        response: Option<MockProxyResponse>,
+        // Err(retryable) if the hint is expected to fail, the recency verdict is empty if the
+        // host did not decide it
+        validity_and_recency: Result<(u8, Option<u8>), bool>,
+        stores_encoded_payload: bool,
+    }
+    let cases = vec![
+        Case {
+            response: Some(MockProxyResponse::Payload(encoded_payload.clone())),
+            validity_and_recency: Ok((1, None)),
+            stores_encoded_payload: true,
+        },
+        Case {
+            response: Some(MockProxyResponse::DerivationError {
+                status_code: 3,
+                msg: "invalid cert".into(),
+            }),
+            validity_and_recency: Ok((0, None)),
+            stores_encoded_payload: false,
+        },
+        Case {
+            response: Some(MockProxyResponse::DerivationError {
+                status_code: 2,
+                msg: "recency check failed".into(),
+            }),
+            validity_and_recency: Ok((1, Some(0))),
+            stores_encoded_payload: false,
+        },
+        // proxy is asked for the encoded payload, it cannot fail decoding
+        Case {
+            response: Some(MockProxyResponse::DerivationError {
+                status_code: 4,
+                msg: "blob decoding error".into(),
+            }),
+            validity_and_recency: Err(false),
+            stores_encoded_payload: false,
+        },
+        // an empty encoded payload is an error, not a crash of the host
+        Case {
+            response: Some(MockProxyResponse::Payload(vec![])),
+            validity_and_recency: Err(false),
+            stores_encoded_payload: false,
+        },
+        // the encoded payload is not made of field elements
+        Case {
+            response: Some(MockProxyResponse::Payload(vec![
+                0xff;
+                BYTES_PER_FIELD_ELEMENT
+            ])),
+            validity_and_recency: Err(false),
+            stores_encoded_payload: false,
+        },
+        Case {
+            response: Some(MockProxyResponse::Payload(forged_payload)),
+            validity_and_recency: Err(false),
+            stores_encoded_payload: false,
+        },
+        // longer than the blob of the cert
+        Case {
+            response: Some(MockProxyResponse::Payload(vec![
+                0u8;
+                (blob_length_fe as usize + 1)
+                    * BYTES_PER_FIELD_ELEMENT
+            ])),
+            validity_and_recency: Err(false),
+            stores_encoded_payload: false,
+        },
+        // unknown to proxy
+        Case {
+            response: None,
+            validity_and_recency: Err(false),
+            stores_encoded_payload: false,
+        },
+    ];
+
+    for case in cases {
+        let mut fixtures = Fixtures::default();
+        if let Some(response) = case.response {
+            fixtures = fixtures.with_response(altda_commitment.to_digest(), response);
+        }
+        let (url, mock_proxy) = spawn_mock_proxy(fixtures).await;
+        let provider = no_retry_provider(url);
+        let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+
+        let result = fetch_eigenda_hint(
+            altda_commitment.to_rlp_bytes().into(),
+            &provider,
+            kv.clone(),
+        )
+        .await;
+        assert_eq!(mock_proxy.requests(), 1);
+
+        let validity_key = EigenDAPreimageKey::validity_key(&altda_commitment);
+        match case.validity_and_recency {
+            Ok((validity, recency)) => {
+                result.unwrap();
+                let recency_key = EigenDAPreimageKey::recency_key(&altda_commitment);
+                assert_eq!(get(&kv, &validity_key).await, Some(vec![validity]));
+                assert_eq!(
+                    get(&kv, &recency_key).await,
+                    Some(recency.into_iter().collect())
+                );
+            }
+            Err(retryable) => {
+                assert_eq!(result.unwrap_err().is_retryable(), retryable);
+                // a malformed encoded payload is rejected before the validity is written
+                assert_eq!(get(&kv, &validity_key).await, None);
+            }
+        }
+
+        let field_elements = [0, 1, blob_length_fe - 1]
+            .map(|index| EigenDAPreimageKey::field_element_key(&altda_commitment, index));
+        let mut stored = vec![];
+        for key in &field_elements {
+            stored.push(get(&kv, key).await);
+        }
+        if case.stores_encoded_payload {
+            assert_eq!(
+                stored,
+                vec![
+                    Some(encoded_payload[..BYTES_PER_FIELD_ELEMENT].to_vec()),
+                    Some(encoded_payload[BYTES_PER_FIELD_ELEMENT..].to_vec()),
+                    Some(vec![0u8; BYTES_PER_FIELD_ELEMENT]),
+                ]
+            );
+        } else {
+            assert_eq!(stored, vec![None, None, None]);
+        }
+    }
+}
+
+/// Serves the key-value store populated by the host to a client through the preimage oracle, as
+/// the preimage server of an offline host does
+fn oracle_client(kv: SharedKeyValueStore) -> Arc<impl CommsClient + Send + Sync> {
+    let hint = BidirectionalChannel::new().unwrap();
+    let preimage = BidirectionalChannel::new().unwrap();
+    tokio::spawn(
+        PreimageServer::new(
+            OracleServer::new(preimage.host),
+            HintReader::new(hint.host),
+            Arc::new(OfflineHostBackend::new(kv)),
+        )
+        .start(),
+    );
+    Arc::new(CachingOracle::new(
+        16,
+        OracleReader::new(preimage.client),
+        HintWriter::new(hint.client),
+    ))
+}
+
+#[tokio::test]
+async fn test_encoded_payload_round_trip() {
+    struct Case {
+        name: &'static str,
+        payload_len: usize,
+        blob_length_fe: u32,
+    }
+    let cases = [
+        // the only chunk is as long as the blob
+        Case {
+            name: "blob under 4KiB",
+            payload_len: 3 * BYTES_PER_FIELD_ELEMENT,
+            blob_length_fe: 8,
+        },
+        // the payload ends in the middle of the last chunk
+        Case {
+            name: "payload ending in the last chunk",
+            payload_len: BYTES_PER_ENCODED_PAYLOAD_CHUNK + 5 * BYTES_PER_FIELD_ELEMENT,
+            blob_length_fe: 256,
+        },
+        // the last chunk only holds the zeros the proxy trimmed
+        Case {
+            name: "payload under 4KiB",
+            payload_len: 40 * BYTES_PER_FIELD_ELEMENT,
+            blob_length_fe: 256,
+        },
+    ];
+
+    for case in cases {
+        let mut encoded_payload = vec![0u8; case.payload_len];
+        for (i, field_element) in encoded_payload
+            .chunks_exact_mut(BYTES_PER_FIELD_ELEMENT)
+            .enumerate()
+        {
+            field_element[1..].fill(i as u8 + 1);
+        }
+        let altda_commitment =
+            altda_commitment_with_blob_length(&encoded_payload, Some(case.blob_length_fe));
+        let mut padded_payload = encoded_payload.clone();
+        padded_payload.resize(case.blob_length_fe as usize * BYTES_PER_FIELD_ELEMENT, 0);
+
+        for fetch_mode in [
+            EncodedPayloadFetchMode::FieldElement,
+            EncodedPayloadFetchMode::Chunk,
+        ] {
+            let fixtures = Fixtures::default().with_response(
+                altda_commitment.to_digest(),
+                MockProxyResponse::Payload(encoded_payload.clone()),
+            );
+            let (url, _mock_proxy) = spawn_mock_proxy(fixtures).await;
+            let provider = no_retry_provider(url).with_fetch_mode(fetch_mode);
+            let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+            fetch_eigenda_hint(
+                altda_commitment.to_rlp_bytes().into(),
+                &provider,
+                kv.clone(),
+            )
+            .await
+            .unwrap();
+
+            // only the layout the client fetches with is stored
+            let field_element_key = EigenDAPreimageKey::field_element_key(&altda_commitment, 0);
+            let chunk_key = EigenDAPreimageKey::encoded_payload_chunk_key(&altda_commitment, 0);
+            assert_eq!(
+                get(&kv, &field_element_key).await.is_some(),
+                fetch_mode == EncodedPayloadFetchMode::FieldElement,
+                "case {} {fetch_mode:?}",
+                case.name
+            );
+            assert_eq!(
+                get(&kv, &chunk_key).await.is_some(),
+                fetch_mode == EncodedPayloadFetchMode::Chunk,
+                "case {} {fetch_mode:?}",
+                case.name
+            );
+
+            let mut client =
+                OracleEigenDAPreimageProvider::new(oracle_client(kv)).with_fetch_mode(fetch_mode);
+            let fetched = client.get_encoded_payload(&altda_commitment).await.unwrap();
+            assert_eq!(
+                fetched.encoded_payload.as_ref(),
+                padded_payload.as_slice(),
+                "case {} {fetch_mode:?}",
+                case.name
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_keccak_hint() {
+    let input_data = b"keccak preimage".to_vec();
+    let commitment = keccak256(&input_data);
+
+    struct Case {
+        payload: Option<Vec<u8>>,
+        // Err(retryable) if the hint is expected to fail
+        result: Result<(), bool>,
+    }
+    let cases = vec![
+        Case {
+            payload: Some(input_data.clone()),
+            result: Ok(()),
+        },
+        // proxy returning data not matching the hash
+        Case {
+            payload: Some(b"forged preimage".to_vec()),
+            result: Err(false),
+        },
+        // unknown to proxy
+        Case {
+            payload: None,
+            result: Err(false),
+        },
+    ];
+
+    for case in cases {
+        let mut fixtures = Fixtures::default();
+        if let Some(payload) = case.payload {
+            fixtures = fixtures.with_response(commitment, MockProxyResponse::Payload(payload));
+        }
+        let (url, _mock_proxy) = spawn_mock_proxy(fixtures).await;
+        let provider = no_retry_provider(url);
+        let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+
+        let result = fetch_keccak_hint(
+            Bytes::copy_from_slice(commitment.as_slice()),
+            &provider,
+            kv.clone(),
+        )
+        .await;
+        let stored = kv
+            .read()
+            .await
+            .get(PreimageKey::new(*commitment, PreimageKeyType::Keccak256).into());
+        match case.result {
+            Ok(()) => {
+                result.unwrap();
+                assert_eq!(stored, Some(input_data.clone()));
+            }
+            Err(retryable) => {
+                assert_eq!(result.unwrap_err().is_retryable(), retryable);
+                assert_eq!(stored, None);
+            }
+        }
+    }
+
+    // the hint carries the hash only, not the commitment type byte
+    let (url, _mock_proxy) = spawn_mock_proxy(Fixtures::default()).await;
+    let provider = no_retry_provider(url);
+    let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+    let hint = Bytes::from(GenericAltDACommitment::Keccak(B256::ZERO).to_bytes());
+    assert!(!fetch_keccak_hint(hint, &provider, kv)
+        .await
+        .unwrap_err()
+        .is_retryable());
+}
@@ -0,0 +1,212 @@
+//! Tests the prefetch of EigenDA certs against the mock eigenda-proxy, and the scan of L1 blocks
+//! against a scripted mock of the L1 JSON-RPC.
+
+mod common;
+
+use alloy_primitives::{hex, Address, Bytes, B256};
+use alloy_provider::RootProvider;
+use common::{
+    altda_commitment, get, no_retry_provider, spawn_mock_proxy, Fixtures, ScriptedResponse,
+};
+use hokulea_eigenda::{EigenDAPreimageKey, ALTDA_DERIVATION_VERSION, BYTES_PER_FIELD_ELEMENT};
+use hokulea_host_bin::eigenda_preimage::OnlineEigenDAPreimageProvider;
+use hokulea_host_bin::prefetch::EigenDAPrefetcher;
+use hokulea_mock_proxy::MockProxyResponse;
+use kona_host::{MemoryKeyValueStore, SharedKeyValueStore};
+use reqwest::Url;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// One field element of data
+fn encoded_payload(byte: u8) -> Vec<u8> {
+    let mut encoded_payload = vec![0u8; BYTES_PER_FIELD_ELEMENT];
+    encoded_payload[1..].fill(byte);
+    encoded_payload
+}
+
+fn prefetcher(proxy_url: Url, concurrency: usize) -> EigenDAPrefetcher {
+    let provider = no_retry_provider(proxy_url);
+    // no block is scanned, the L1 provider is never called
+    let l1_provider = RootProvider::new_http(Url::parse("http://127.0.0.1:1").unwrap());
+    EigenDAPrefetcher::new(l1_provider, provider, Address::ZERO, concurrency)
+}
+
+#[tokio::test]
+async fn test_prefetch() {
+    // well formed, not matching the kzg commitment of the cert
+    let forged_payload = encoded_payload(0xbb);
+    let encoded_payload = encoded_payload(0xaa);
+    let altda_commitment = altda_commitment(&encoded_payload);
+    let altda_commitment_bytes = Bytes::from(altda_commitment.to_rlp_bytes());
+    let validity_key = EigenDAPreimageKey::validity_key(&altda_commitment);
+    let field_element_key = EigenDAPreimageKey::field_element_key(&altda_commitment, 0);
+
+    struct Case {
+        response: Option<MockProxyResponse>,
+        // None if nothing is expected to be stored
+        validity: Option<u8>,
+        stores_encoded_payload: bool,
+    }
+    let cases = vec![
+        Case {
+            response: Some(MockProxyResponse::Payload(encoded_payload.clone())),
+            validity: Some(1),
+            stores_encoded_payload: true,
+        },
+        Case {
+            response: Some(MockProxyResponse::DerivationError {
+                status_code: 3,
+                msg: "invalid cert".into(),
+            }),
+            validity: Some(0),
+            stores_encoded_payload: false,
+        },
+        Case {
+            response: Some(MockProxyResponse::Payload(forged_payload)),
+            validity: None,
+            stores_encoded_payload: false,
+        },
+        // unknown to proxy, the cert is fetched again once hinted
+        Case {
+            response: None,
+            validity: None,
+            stores_encoded_payload: false,
+        },
+    ];
+
+    for case in cases {
+        let mut fixtures = Fixtures::default();
+        if let Some(response) = case.response {
+            fixtures = fixtures.with_response(altda_commitment.to_digest(), response);
+        }
+        let (url, mock_proxy) = spawn_mock_proxy(fixtures).await;
+        let prefetcher = prefetcher(url, 1);
+        let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+
+        // not prefetched yet
+        assert!(!prefetcher.wait(&altda_commitment_bytes).await);
+
+        // a cert is prefetched once
+        prefetcher.prefetch(altda_commitment_bytes.clone(), kv.clone());
+        prefetcher.prefetch(altda_commitment_bytes.clone(), kv.clone());
+        assert_eq!(
+            prefetcher.wait(&altda_commitment_bytes).await,
+            case.validity.is_some()
+        );
+        assert_eq!(mock_proxy.requests(), 1);
+
+        assert_eq!(
+            get(&kv, &validity_key).await,
+            case.validity.map(|validity| vec![validity])
+        );
+        assert_eq!(
+            get(&kv, &field_element_key).await,
+            case.stores_encoded_payload.then(|| encoded_payload.clone())
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_prefetch_skips_queued() {
+    let running = Bytes::from(altda_commitment(&encoded_payload(0xaa)).to_rlp_bytes());
+    let queued = Bytes::from(altda_commitment(&encoded_payload(0xbb)).to_rlp_bytes());
+    let delay = Duration::from_millis(200);
+    let (url, mock_proxy) =
+        spawn_mock_proxy(vec![ScriptedResponse::derivation_error(3).with_delay(delay)]).await;
+    let prefetcher = prefetcher(url, 1);
+    let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+
+    prefetcher.prefetch(running.clone(), kv.clone());
+    prefetcher.prefetch(queued.clone(), kv.clone());
+
+    // the hinted cert is not held up behind the running prefetch, it is fetched by the caller
+    let start = Instant::now();
+    assert!(!prefetcher.wait(&queued).await);
+    assert!(start.elapsed() < delay);
+    // its own prefetch is awaited
+    assert!(prefetcher.wait(&running).await);
+    // the skipped prefetch never reaches the proxy, even once the permit is free
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(mock_proxy.requests(), 1);
+    assert!(!prefetcher.wait(&queued).await);
+}
+
+/// The JSON-RPC answer to `eth_getBlockByHash` with full transactions, given as recipient and
+/// input
+fn block_response(transactions: &[(Address, Vec<u8>)]) -> ScriptedResponse {
+    let zero = B256::ZERO;
+    let transactions = transactions
+        .iter()
+        .enumerate()
+        .map(|(index, (to, input))| {
+            format!(
+                r#"{{"type":"0x0","hash":"{hash}","nonce":"{index:#x}","gasPrice":"0x1","gas":"0x100000","to":"{to}","value":"0x0","input":"0x{input}","v":"0x1b","r":"0x1","s":"0x1","from":"{from}","blockHash":"{zero}","blockNumber":"0x1","transactionIndex":"{index:#x}"}}"#,
+                hash = B256::with_last_byte(index as u8 + 1),
+                input = hex::encode(input),
+                from = Address::ZERO,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    let block = format!(
+        r#"{{"hash":"{zero}","parentHash":"{zero}","sha3Uncles":"{zero}","miner":"{miner}","stateRoot":"{zero}","transactionsRoot":"{zero}","receiptsRoot":"{zero}","logsBloom":"0x{bloom}","difficulty":"0x0","number":"0x1","gasLimit":"0x1c9c380","gasUsed":"0x0","timestamp":"0x1","extraData":"0x","mixHash":"{zero}","nonce":"0x0000000000000000","baseFeePerGas":"0x1","uncles":[],"transactions":[{transactions}]}}"#,
+        miner = Address::ZERO,
+        bloom = "00".repeat(256),
+    );
+    ScriptedResponse::new(
+        200,
+        format!(r#"{{"jsonrpc":"2.0","id":0,"result":{block}}}"#).as_bytes(),
+    )
+}
+
+#[tokio::test]
+async fn test_scan_block() {
+    let batch_inbox_address = Address::repeat_byte(0xff);
+    let altda_commitment_bytes = altda_commitment(&encoded_payload(0xaa)).to_rlp_bytes();
+    let eigenda_calldata = [&[ALTDA_DERIVATION_VERSION], &altda_commitment_bytes[..]].concat();
+
+    struct Case {
+        response: ScriptedResponse,
+        // None if an error is expected
+        commitments: Option<Vec<Bytes>>,
+    }
+    let cases = vec![
+        Case {
+            response: block_response(&[
+                (batch_inbox_address, eigenda_calldata.clone()),
+                // not to the batcher inbox
+                (Address::ZERO, eigenda_calldata.clone()),
+                // frames posted as calldata
+                (batch_inbox_address, vec![0x00, 0xaa]),
+            ]),
+            commitments: Some(vec![altda_commitment_bytes.clone().into()]),
+        },
+        Case {
+            response: block_response(&[]),
+            commitments: Some(vec![]),
+        },
+        // block not found
+        Case {
+            response: ScriptedResponse::new(200, br#"{"jsonrpc":"2.0","id":0,"result":null}"#),
+            commitments: None,
+        },
+        Case {
+            response: ScriptedResponse::new(503, b""),
+            commitments: None,
+        },
+    ];
+
+    for case in cases {
+        let (url, mock) = spawn_mock_proxy(vec![case.response]).await;
+        let l1_provider = RootProvider::new_http(url);
+        // the proxy is never called
+        let provider =
+            OnlineEigenDAPreimageProvider::new_http(Url::parse("http://127.0.0.1:1").unwrap());
+        let prefetcher = EigenDAPrefetcher::new(l1_provider, provider, batch_inbox_address, 1);
+
+        let result = prefetcher.scan_block(B256::repeat_byte(1)).await;
+        assert_eq!(result.ok(), case.commitments);
+        assert_eq!(mock.requests(), 1);
+    }
+}
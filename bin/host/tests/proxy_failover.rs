@@ -0,0 +1,401 @@
+//! Tests the failover and cross-checking of [OnlineEigenDAPreimageProvider] over several local
+//! mocks of eigenda-proxy.
+
+mod common;
+
+use alloy_primitives::{keccak256, Bytes};
+use common::{
+    altda_commitment, derivation_error, respond, spawn_mock_proxy, stage, test_policy, MockProxy,
+    ScriptedResponse,
+};
+use hokulea_eigenda::BYTES_PER_FIELD_ELEMENT;
+use hokulea_host_bin::eigenda_preimage::{OnlineEigenDAPreimageProvider, ProxyDerivationStage};
+use hokulea_host_bin::errors::HokuleaHostError;
+use hokulea_host_bin::retry::ProxyRetryPolicy;
+use hokulea_host_bin::witness_cache::DiskWitnessCache;
+use reqwest::Url;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Two field elements, the trailing zeros of the blob are trimmed
+fn encoded_payload(byte: u8) -> Vec<u8> {
+    let mut encoded_payload = vec![0u8; 2 * BYTES_PER_FIELD_ELEMENT];
+    for field_element in encoded_payload.chunks_mut(BYTES_PER_FIELD_ELEMENT) {
+        field_element[1..].fill(byte);
+    }
+    encoded_payload
+}
+
+/// The same blob as [encoded_payload], with one trailing zero field element left
+fn untrimmed(encoded_payload: &[u8]) -> Vec<u8> {
+    let mut untrimmed = encoded_payload.to_vec();
+    untrimmed.resize(encoded_payload.len() + BYTES_PER_FIELD_ELEMENT, 0);
+    untrimmed
+}
+
+/// Spawns a mock proxy per script, and a provider failing over them in order
+async fn spawn_providers(
+    scripts: Vec<Vec<ScriptedResponse>>,
+    policy: ProxyRetryPolicy,
+) -> (OnlineEigenDAPreimageProvider, Vec<Url>, Vec<MockProxy>) {
+    let mut urls = vec![];
+    let mut mocks = vec![];
+    for script in scripts {
+        let (url, mock) = spawn_mock_proxy(script).await;
+        urls.push(url);
+        mocks.push(mock);
+    }
+
+    let mut provider =
+        OnlineEigenDAPreimageProvider::new_http(urls[0].clone()).with_retry_policy(policy);
+    for url in &urls[1..] {
+        provider = provider.with_failover_endpoint(url.clone());
+    }
+    (provider, urls, mocks)
+}
+
+fn request_counts(mocks: &[MockProxy]) -> Vec<usize> {
+    mocks.iter().map(MockProxy::requests).collect()
+}
+
+#[tokio::test]
+async fn test_failover() {
+    struct Case {
+        scripts: Vec<Vec<ScriptedResponse>>,
+        // Err(retryable) if an error is expected
+        result: Result<ProxyDerivationStage, bool>,
+        requests: Vec<usize>,
+    }
+
+    let cases = vec![
+        Case {
+            scripts: vec![
+                vec![respond(200, b"primary")],
+                vec![respond(200, b"failover")],
+            ],
+            result: Ok(stage(true, None, b"primary")),
+            requests: vec![1, 0],
+        },
+        Case {
+            scripts: vec![vec![respond(500, b"")], vec![respond(200, b"failover")]],
+            result: Ok(stage(true, None, b"failover")),
+            requests: vec![1, 1],
+        },
+        // any error fails over, not only the retryable ones
+        Case {
+            scripts: vec![
+                vec![respond(404, b"")],
+                vec![derivation_error(4)],
+                vec![derivation_error(3)],
+            ],
+            result: Ok(stage(false, None, b"")),
+            requests: vec![1, 1, 1],
+        },
+        Case {
+            scripts: vec![vec![respond(500, b"")], vec![respond(503, b"")]],
+            result: Err(true),
+            requests: vec![1, 1],
+        },
+        // retrying helps if any proxy failed for a transient reason
+        Case {
+            scripts: vec![vec![respond(404, b"")], vec![respond(503, b"")]],
+            result: Err(true),
+            requests: vec![1, 1],
+        },
+        Case {
+            scripts: vec![vec![respond(404, b"")], vec![derivation_error(4)]],
+            result: Err(false),
+            requests: vec![1, 1],
+        },
+    ];
+
+    for case in cases {
+        let (provider, _, mocks) = spawn_providers(case.scripts, test_policy(0)).await;
+        let result = provider
+            .fetch_data_from_proxy(&Bytes::from_static(&[1, 0, 2]))
+            .await;
+        assert_eq!(result.map_err(|e| e.is_retryable()), case.result);
+        assert_eq!(request_counts(&mocks), case.requests);
+    }
+}
+
+#[tokio::test]
+async fn test_failover_keccak_preimage() {
+    let input_data = b"keccak preimage";
+    let commitment = keccak256(input_data);
+    let (provider, _, mocks) = spawn_providers(
+        vec![
+            // a preimage not matching the hash fails over as well
+            vec![respond(200, b"forged preimage")],
+            vec![respond(200, input_data)],
+        ],
+        test_policy(0),
+    )
+    .await;
+
+    let result = provider
+        .fetch_keccak_preimage_from_proxy(commitment)
+        .await
+        .unwrap();
+    assert_eq!(result, Bytes::from_static(input_data));
+    assert_eq!(request_counts(&mocks), vec![1, 1]);
+}
+
+#[tokio::test]
+async fn test_failover_prefers_closed_circuit() {
+    let (provider, _, mocks) = spawn_providers(
+        vec![
+            vec![respond(500, b""), respond(200, b"primary")],
+            vec![respond(200, b"failover"), respond(200, b"failover")],
+        ],
+        ProxyRetryPolicy {
+            circuit_breaker_threshold: 1,
+            circuit_breaker_cooldown: Duration::from_secs(60),
+            ..test_policy(0)
+        },
+    )
+    .await;
+    let commitment = Bytes::from_static(&[1, 0, 2]);
+
+    provider.fetch_data_from_proxy(&commitment).await.unwrap();
+    assert_eq!(request_counts(&mocks), vec![1, 1]);
+
+    // the circuit of the primary is open, the failover is queried without waiting for it
+    let result = provider.fetch_data_from_proxy(&commitment).await.unwrap();
+    assert_eq!(result, stage(true, None, b"failover"));
+    assert_eq!(request_counts(&mocks), vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_cross_check() {
+    struct Case {
+        scripts: Vec<Vec<ScriptedResponse>>,
+        result: Result<ProxyDerivationStage, Option<&'static str>>,
+        requests: Vec<usize>,
+    }
+
+    // Err(Some(field)) is a mismatch on field, Err(None) any other error
+    let payload = encoded_payload(0xaa);
+    let cases = vec![
+        Case {
+            scripts: vec![vec![respond(200, &payload)], vec![respond(200, &payload)]],
+            result: Ok(stage(true, None, &payload)),
+            requests: vec![1, 1],
+        },
+        Case {
+            scripts: vec![vec![derivation_error(3)], vec![derivation_error(3)]],
+            result: Ok(stage(false, None, b"")),
+            requests: vec![1, 1],
+        },
+        // the same blob, trimmed by one proxy only
+        Case {
+            scripts: vec![
+                vec![respond(200, &payload)],
+                vec![respond(200, &untrimmed(&payload))],
+            ],
+            result: Ok(stage(true, None, &payload)),
+            requests: vec![1, 1],
+        },
+        // a payload differing from the one of the first proxy cannot match the cert either
+        Case {
+            scripts: vec![
+                vec![respond(200, &payload)],
+                vec![respond(200, &encoded_payload(0xbb))],
+            ],
+            result: Err(None),
+            requests: vec![1, 1],
+        },
+        Case {
+            scripts: vec![vec![respond(200, &payload)], vec![derivation_error(3)]],
+            result: Err(Some("validity")),
+            requests: vec![1, 1],
+        },
+        Case {
+            scripts: vec![vec![derivation_error(2)], vec![respond(200, &payload)]],
+            result: Err(Some("recency verdict")),
+            requests: vec![1, 1],
+        },
+        // the cross-check fails over as well, the failed primary is tried again first
+        Case {
+            scripts: vec![
+                vec![respond(500, b"")],
+                vec![respond(200, &payload)],
+                vec![respond(200, &payload)],
+            ],
+            result: Ok(stage(true, None, &payload)),
+            requests: vec![2, 1, 1],
+        },
+        // no second proxy to confirm
+        Case {
+            scripts: vec![vec![respond(200, &payload)], vec![respond(500, b"")]],
+            result: Err(None),
+            requests: vec![1, 1],
+        },
+    ];
+
+    let altda_commitment = altda_commitment(&payload);
+    for case in cases {
+        let (provider, urls, mocks) = spawn_providers(case.scripts, test_policy(0)).await;
+        let provider = provider.with_cross_check(true);
+
+        let result = provider.fetch_data_for_commitment(&altda_commitment).await;
+        match (result, case.result) {
+            (Ok(stage), Ok(expected)) => assert_eq!(stage, expected),
+            (Err(HokuleaHostError::ProxyMismatch(e)), Err(Some(field))) => {
+                assert_eq!(e.digest, altda_commitment.to_digest());
+                assert_eq!(e.field, field);
+                assert_eq!((e.first, e.second), (urls[0].clone(), urls[1].clone()));
+            }
+            (Err(e), Err(None)) => assert!(!matches!(e, HokuleaHostError::ProxyMismatch(_))),
+            (result, expected) => panic!("got {result:?}, expected {expected:?}"),
+        }
+        assert_eq!(request_counts(&mocks), case.requests);
+    }
+}
+
+#[tokio::test]
+async fn test_cross_check_witness_cache_hit() {
+    struct Case {
+        cached: ProxyDerivationStage,
+        scripts: Vec<Vec<ScriptedResponse>>,
+        result: Result<ProxyDerivationStage, &'static str>,
+        requests: Vec<usize>,
+    }
+
+    // Err(field) is a mismatch on field
+    let payload = encoded_payload(0xaa);
+    let cases = vec![
+        // a single proxy confirms the cached witness
+        Case {
+            cached: stage(true, None, &payload),
+            scripts: vec![vec![respond(200, &payload)], vec![respond(200, &payload)]],
+            result: Ok(stage(true, None, &payload)),
+            requests: vec![1, 0],
+        },
+        // the same blob, not trimmed by the proxy
+        Case {
+            cached: stage(true, None, &payload),
+            scripts: vec![
+                vec![respond(200, &untrimmed(&payload))],
+                vec![respond(200, &payload)],
+            ],
+            result: Ok(stage(true, None, &payload)),
+            requests: vec![1, 0],
+        },
+        // a stale witness is replaced by what two proxies agree on
+        Case {
+            cached: stage(true, None, &payload),
+            scripts: vec![vec![derivation_error(3)], vec![derivation_error(3)]],
+            result: Ok(stage(false, None, b"")),
+            requests: vec![1, 1],
+        },
+        Case {
+            cached: stage(true, None, &encoded_payload(0xbb)),
+            scripts: vec![vec![respond(200, &payload)], vec![respond(200, &payload)]],
+            result: Ok(stage(true, None, &payload)),
+            requests: vec![1, 1],
+        },
+        Case {
+            cached: stage(true, None, &encoded_payload(0xbb)),
+            scripts: vec![vec![respond(200, &payload)], vec![derivation_error(3)]],
+            result: Err("validity"),
+            requests: vec![1, 1],
+        },
+    ];
+
+    let altda_commitment = altda_commitment(&payload);
+    let digest = altda_commitment.to_digest();
+    let dir = std::env::temp_dir().join(format!(
+        "hokulea-cross-check-witness-cache-{}",
+        std::process::id()
+    ));
+    for case in cases {
+        let _ = std::fs::remove_dir_all(&dir);
+        let witness_cache = Arc::new(DiskWitnessCache::open(&dir, 1 << 20).unwrap());
+        witness_cache.insert(&digest, &case.cached).unwrap();
+
+        let (provider, _, mocks) = spawn_providers(case.scripts, test_policy(0)).await;
+        let provider = provider
+            .with_cross_check(true)
+            .with_witness_cache(witness_cache.clone());
+
+        let result = provider.fetch_data_for_commitment(&altda_commitment).await;
+        match (result, case.result) {
+            (Ok(stage), Ok(expected)) => {
+                assert_eq!(stage, expected);
+                assert_eq!(witness_cache.get(&digest), Some(expected));
+            }
+            (Err(HokuleaHostError::ProxyMismatch(e)), Err(field)) => assert_eq!(e.field, field),
+            (result, expected) => panic!("got {result:?}, expected {expected:?}"),
+        }
+        assert_eq!(request_counts(&mocks), case.requests);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_failover_malformed_payload() {
+    struct Case {
+        scripts: Vec<Vec<ScriptedResponse>>,
+        // Err(retryable) if an error is expected
+        result: Result<ProxyDerivationStage, bool>,
+        requests: Vec<usize>,
+    }
+
+    let payload = encoded_payload(0xaa);
+    // well formed field elements, not committed to by the cert
+    let forged = encoded_payload(0xbb);
+    let cases = vec![
+        Case {
+            scripts: vec![vec![respond(200, &forged)], vec![respond(200, &payload)]],
+            result: Ok(stage(true, None, &payload)),
+            requests: vec![1, 1],
+        },
+        // not made of field elements
+        Case {
+            scripts: vec![
+                vec![respond(200, &[0xff; BYTES_PER_FIELD_ELEMENT])],
+                vec![respond(200, &payload)],
+            ],
+            result: Ok(stage(true, None, &payload)),
+            requests: vec![1, 1],
+        },
+        Case {
+            scripts: vec![vec![respond(200, b"")], vec![respond(200, &payload)]],
+            result: Ok(stage(true, None, &payload)),
+            requests: vec![1, 1],
+        },
+        Case {
+            scripts: vec![vec![respond(200, &forged)], vec![respond(200, &forged)]],
+            result: Err(false),
+            requests: vec![1, 1],
+        },
+        Case {
+            scripts: vec![vec![respond(200, &forged)], vec![respond(503, b"")]],
+            result: Err(true),
+            requests: vec![1, 1],
+        },
+    ];
+
+    let altda_commitment = altda_commitment(&payload);
+    let dir = std::env::temp_dir().join(format!(
+        "hokulea-failover-malformed-payload-{}",
+        std::process::id()
+    ));
+    for case in cases {
+        let _ = std::fs::remove_dir_all(&dir);
+        let witness_cache = Arc::new(DiskWitnessCache::open(&dir, 1 << 20).unwrap());
+        let (provider, _, mocks) = spawn_providers(case.scripts, test_policy(0)).await;
+        let provider = provider.with_witness_cache(witness_cache.clone());
+
+        let result = provider.fetch_data_for_commitment(&altda_commitment).await;
+        // only a payload committed to by the cert is cached
+        assert_eq!(
+            witness_cache.get(&altda_commitment.to_digest()),
+            result.as_ref().ok().cloned()
+        );
+        assert_eq!(result.map_err(|e| e.is_retryable()), case.result);
+        assert_eq!(request_counts(&mocks), case.requests);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,115 @@
+//! Tests the retry policy of [OnlineEigenDAPreimageProvider] against a local mock of
+//! eigenda-proxy.
+
+mod common;
+
+use alloy_primitives::Bytes;
+use common::{
+    derivation_error, respond, slow, spawn_mock_proxy, stage, test_policy, ScriptedResponse,
+};
+use hokulea_host_bin::eigenda_preimage::{OnlineEigenDAPreimageProvider, ProxyDerivationStage};
+use hokulea_host_bin::retry::ProxyRetryPolicy;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn test_fetch_data_from_proxy_with_retry() {
+    struct Case {
+        script: Vec<ScriptedResponse>,
+        // Err(retryable) if an error is expected
+        result: Result<ProxyDerivationStage, bool>,
+        requests: usize,
+    }
+
+    let cases = vec![
+        Case {
+            script: vec![respond(200, b"payload")],
+            result: Ok(stage(true, None, b"payload")),
+            requests: 1,
+        },
+        // server errors are retried
+        Case {
+            script: vec![
+                respond(500, b""),
+                respond(503, b""),
+                respond(200, b"payload"),
+            ],
+            result: Ok(stage(true, None, b"payload")),
+            requests: 3,
+        },
+        // rate limiting is retried
+        Case {
+            script: vec![respond(429, b""), respond(200, b"payload")],
+            result: Ok(stage(true, None, b"payload")),
+            requests: 2,
+        },
+        // retries are bounded
+        Case {
+            script: vec![respond(500, b""); 5],
+            result: Err(true),
+            requests: 3,
+        },
+        // a slow response times out and is retried
+        Case {
+            script: vec![slow(Duration::from_secs(2)), respond(200, b"payload")],
+            result: Ok(stage(true, None, b"payload")),
+            requests: 2,
+        },
+        // derivation errors are answers, not failures
+        Case {
+            script: vec![derivation_error(3)],
+            result: Ok(stage(false, None, b"")),
+            requests: 1,
+        },
+        Case {
+            script: vec![respond(502, b""), derivation_error(2)],
+            result: Ok(stage(true, Some(false), b"")),
+            requests: 2,
+        },
+        // a client error is not retried
+        Case {
+            script: vec![respond(404, b""), respond(200, b"payload")],
+            result: Err(false),
+            requests: 1,
+        },
+        // an undefined derivation error is not retried
+        Case {
+            script: vec![derivation_error(4), respond(200, b"payload")],
+            result: Err(false),
+            requests: 1,
+        },
+    ];
+
+    for case in cases {
+        let (url, mock) = spawn_mock_proxy(case.script).await;
+        let provider =
+            OnlineEigenDAPreimageProvider::new_http(url).with_retry_policy(test_policy(2));
+
+        let result = provider
+            .fetch_data_from_proxy(&Bytes::from_static(&[1, 0, 2]))
+            .await;
+        assert_eq!(result.map_err(|e| e.is_retryable()), case.result);
+        assert_eq!(mock.requests(), case.requests);
+    }
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_pauses_requests() {
+    let cooldown = Duration::from_millis(300);
+    let (url, mock) = spawn_mock_proxy(vec![respond(500, b""), respond(200, b"payload")]).await;
+    let provider =
+        OnlineEigenDAPreimageProvider::new_http(url).with_retry_policy(ProxyRetryPolicy {
+            circuit_breaker_threshold: 1,
+            circuit_breaker_cooldown: cooldown,
+            ..test_policy(0)
+        });
+    let commitment = Bytes::from_static(&[1, 0, 2]);
+
+    assert!(provider.fetch_data_from_proxy(&commitment).await.is_err());
+
+    // the circuit is open, the next request waits for the cooldown then goes through
+    let start = Instant::now();
+    let result = provider.fetch_data_from_proxy(&commitment).await.unwrap();
+    assert!(start.elapsed() >= cooldown / 2);
+    assert_eq!(result, stage(true, None, b"payload"));
+    assert_eq!(mock.requests(), 2);
+}
@@ -0,0 +1,385 @@
+//! Tests the relay retrieval backend of the host against local stand-ins of the EigenDA relays.
+
+mod common;
+
+use alloy_primitives::B256;
+use anyhow::Result;
+use async_trait::async_trait;
+use common::altda_commitment;
+use eigenda_cert::{AltDACommitment, CertView, EigenDAVersionedCert};
+use hokulea_compute_proof::encoded_payload_from_blob;
+use hokulea_eigenda::BYTES_PER_FIELD_ELEMENT;
+use hokulea_host_bin::cert_verifier::CertValidityChecker;
+use hokulea_host_bin::eigenda_preimage::{OnlineEigenDAPreimageProvider, ProxyDerivationStage};
+use hokulea_host_bin::errors::HokuleaHostError;
+use hokulea_host_bin::relay::{GrpcRelayClient, RelayClient, RelayError, RelayRetriever};
+use hokulea_host_bin::retry::ProxyRetryPolicy;
+use hokulea_mock_proxy::MockRelay;
+use reqwest::Url;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Validity decided without calling L1
+#[derive(Debug)]
+struct StaticCertValidity(bool);
+
+#[async_trait]
+impl CertValidityChecker for StaticCertValidity {
+    async fn is_valid_cert(&self, _altda_commitment: &AltDACommitment) -> Result<bool> {
+        Ok(self.0)
+    }
+}
+
+/// Fails the first checks, then decides the cert is valid
+#[derive(Debug)]
+struct FlakyCertValidity(AtomicUsize);
+
+#[async_trait]
+impl CertValidityChecker for FlakyCertValidity {
+    async fn is_valid_cert(&self, _altda_commitment: &AltDACommitment) -> Result<bool> {
+        match self
+            .0
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+        {
+            Ok(_) => Err(anyhow::anyhow!("L1 is unavailable")),
+            Err(_) => Ok(true),
+        }
+    }
+}
+
+/// Replays a script of results, one per `GetBlob` request whatever the relay
+#[derive(Debug)]
+struct ScriptedRelayClient {
+    script: Mutex<VecDeque<Result<Vec<u8>, RelayError>>>,
+    requests: AtomicUsize,
+}
+
+#[async_trait]
+impl RelayClient for ScriptedRelayClient {
+    async fn get_blob(
+        &self,
+        relay_key: u32,
+        _blob_key: B256,
+        _max_blob_len: usize,
+    ) -> Result<Vec<u8>, RelayError> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        self.script.lock().unwrap().pop_front().unwrap_or_else(|| {
+            Err(RelayError::Failed {
+                relay_key,
+                msg: "script exhausted".into(),
+            })
+        })
+    }
+}
+
+fn unavailable() -> Result<Vec<u8>, RelayError> {
+    Err(RelayError::Unavailable {
+        relay_key: 0,
+        msg: "mock".into(),
+    })
+}
+
+fn failed() -> Result<Vec<u8>, RelayError> {
+    Err(RelayError::Failed {
+        relay_key: 0,
+        msg: "mock".into(),
+    })
+}
+
+/// A blob of 8 field elements, whose trailing zeros are trimmed
+fn blob(byte: u8) -> Vec<u8> {
+    let mut blob = vec![0u8; 3 * BYTES_PER_FIELD_ELEMENT];
+    for field_element in blob.chunks_mut(BYTES_PER_FIELD_ELEMENT) {
+        field_element[1..].fill(byte);
+    }
+    blob
+}
+
+fn padded(blob: &[u8]) -> Vec<u8> {
+    let mut padded = blob.to_vec();
+    padded.resize(8 * BYTES_PER_FIELD_ELEMENT, 0);
+    padded
+}
+
+/// The test cert, served by the relays of `relay_keys` and committing to `blob`
+fn relay_altda_commitment(blob: &[u8], relay_keys: Vec<u32>) -> AltDACommitment {
+    let mut altda_commitment = altda_commitment(&encoded_payload_from_blob(&padded(blob)).unwrap());
+    let EigenDAVersionedCert::V3(cert) = &mut altda_commitment.versioned_cert else {
+        unreachable!("v3 test cert")
+    };
+    cert.blob_inclusion_info.blob_certificate.relay_keys = relay_keys;
+    altda_commitment
+}
+
+/// Spawns a mock relay per relay key, each serving its blob for `blob_key` if any
+async fn spawn_relays(
+    blob_key: B256,
+    blobs: Vec<Option<Vec<u8>>>,
+    is_valid_cert: bool,
+) -> (RelayRetriever, Vec<MockRelay>) {
+    let mut relays = vec![];
+    let mut urls = HashMap::new();
+    for (relay_key, blob) in blobs.into_iter().enumerate() {
+        let blobs = blob.map(|blob| (blob_key, blob)).into_iter().collect();
+        let relay = MockRelay::spawn(blobs, "127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        urls.insert(relay_key as u32, Url::parse(&relay.url()).unwrap());
+        relays.push(relay);
+    }
+    let relay_client = GrpcRelayClient::new(&urls, Duration::from_secs(5)).unwrap();
+    let retriever = RelayRetriever::new(
+        Arc::new(relay_client),
+        Arc::new(StaticCertValidity(is_valid_cert)),
+    );
+    (retriever, relays)
+}
+
+#[tokio::test]
+async fn test_relay_retrieval() {
+    let altda_commitment = relay_altda_commitment(&blob(0xaa), vec![0, 1]);
+    let blob_key = altda_commitment.versioned_cert.blob_header().hash();
+    let encoded_payload = encoded_payload_from_blob(&padded(&blob(0xaa))).unwrap();
+
+    struct Case {
+        blobs: Vec<Option<Vec<u8>>>,
+        is_valid_cert: bool,
+        // None if an error is expected
+        result: Option<ProxyDerivationStage>,
+        requests: Vec<usize>,
+    }
+    let valid = ProxyDerivationStage {
+        is_valid_cert: true,
+        pass_recency_check: None,
+        encoded_payload: encoded_payload.clone(),
+    };
+
+    let cases = vec![
+        Case {
+            blobs: vec![Some(blob(0xaa)), Some(blob(0xaa))],
+            is_valid_cert: true,
+            result: Some(valid.clone()),
+            requests: vec![1, 0],
+        },
+        // the blob is served padded as well
+        Case {
+            blobs: vec![Some(padded(&blob(0xaa))), None],
+            is_valid_cert: true,
+            result: Some(valid.clone()),
+            requests: vec![1, 0],
+        },
+        // unknown to the first relay
+        Case {
+            blobs: vec![None, Some(blob(0xaa))],
+            is_valid_cert: true,
+            result: Some(valid.clone()),
+            requests: vec![1, 1],
+        },
+        // a blob not matching the kzg commitment fails over
+        Case {
+            blobs: vec![Some(blob(0xbb)), Some(blob(0xaa))],
+            is_valid_cert: true,
+            result: Some(valid.clone()),
+            requests: vec![1, 1],
+        },
+        // a blob longer than the cert
+        Case {
+            blobs: vec![Some(vec![0u8; 9 * BYTES_PER_FIELD_ELEMENT]), None],
+            is_valid_cert: true,
+            result: None,
+            requests: vec![1, 1],
+        },
+        // the relays are not queried for an invalid cert
+        Case {
+            blobs: vec![Some(blob(0xaa)), Some(blob(0xaa))],
+            is_valid_cert: false,
+            result: Some(ProxyDerivationStage {
+                is_valid_cert: false,
+                pass_recency_check: None,
+                encoded_payload: vec![],
+            }),
+            requests: vec![0, 0],
+        },
+    ];
+
+    for case in cases {
+        let (retriever, relays) = spawn_relays(blob_key, case.blobs, case.is_valid_cert).await;
+        let result = retriever.fetch_data(&altda_commitment).await;
+        assert_eq!(result.ok(), case.result);
+        let requests: Vec<usize> = relays.iter().map(|relay| relay.requests()).collect();
+        assert_eq!(requests, case.requests);
+    }
+}
+
+#[tokio::test]
+async fn test_provider_with_relay_retrieval() {
+    let altda_commitment = relay_altda_commitment(&blob(0xaa), vec![0, 1]);
+    let blob_key = altda_commitment.versioned_cert.blob_header().hash();
+    let (retriever, _relays) = spawn_relays(blob_key, vec![None, Some(blob(0xaa))], true).await;
+    let provider = OnlineEigenDAPreimageProvider::new_relay(retriever);
+
+    let derivation_stage = provider
+        .fetch_data_for_commitment(&altda_commitment)
+        .await
+        .unwrap();
+    assert_eq!(
+        derivation_stage.encoded_payload,
+        encoded_payload_from_blob(&padded(&blob(0xaa))).unwrap()
+    );
+
+    // keccak commitments are only served by eigenda-proxy, retrying does not help
+    assert!(!provider
+        .fetch_keccak_preimage_from_proxy(B256::ZERO)
+        .await
+        .unwrap_err()
+        .is_retryable());
+}
+
+#[tokio::test]
+async fn test_relay_retrieval_with_retry() {
+    let altda_commitment = relay_altda_commitment(&blob(0xaa), vec![0, 1]);
+
+    struct Case {
+        script: Vec<Result<Vec<u8>, RelayError>>,
+        validity_failures: usize,
+        // Err(retryable) if the retrieval is expected to fail
+        result: Result<(), bool>,
+        requests: usize,
+    }
+
+    let cases = vec![
+        Case {
+            script: vec![unavailable(), Ok(blob(0xaa))],
+            validity_failures: 0,
+            result: Ok(()),
+            requests: 2,
+        },
+        // relay 0 is retried until the policy gives up, then relay 1 is queried
+        Case {
+            script: vec![unavailable(), unavailable(), unavailable(), Ok(blob(0xaa))],
+            validity_failures: 0,
+            result: Ok(()),
+            requests: 4,
+        },
+        Case {
+            script: (0..6).map(|_| unavailable()).collect(),
+            validity_failures: 0,
+            result: Err(true),
+            requests: 6,
+        },
+        // a failure not worth retrying fails over right away
+        Case {
+            script: vec![failed(), failed(), Ok(blob(0xaa))],
+            validity_failures: 0,
+            result: Err(false),
+            requests: 2,
+        },
+        // the cert validity check is retried as well
+        Case {
+            script: vec![Ok(blob(0xaa))],
+            validity_failures: 2,
+            result: Ok(()),
+            requests: 1,
+        },
+        Case {
+            script: vec![Ok(blob(0xaa))],
+            validity_failures: 3,
+            result: Err(true),
+            requests: 0,
+        },
+    ];
+
+    for case in cases {
+        let relay_client = Arc::new(ScriptedRelayClient {
+            script: Mutex::new(case.script.into()),
+            requests: AtomicUsize::new(0),
+        });
+        let retriever = RelayRetriever::new(
+            relay_client.clone(),
+            Arc::new(FlakyCertValidity(AtomicUsize::new(case.validity_failures))),
+        )
+        .with_retry_policy(ProxyRetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            circuit_breaker_threshold: 0,
+            ..Default::default()
+        });
+
+        let result = retriever.fetch_data(&altda_commitment).await;
+        assert_eq!(
+            result.map(|_| ()).map_err(|e| e.is_retryable()),
+            case.result
+        );
+        assert_eq!(relay_client.requests.load(Ordering::SeqCst), case.requests);
+    }
+}
+
+#[tokio::test]
+async fn test_relay_retrieval_errors() {
+    struct Case {
+        relay_keys: Vec<u32>,
+        script: Vec<Result<Vec<u8>, RelayError>>,
+        malformed_payload: bool,
+        retryable: bool,
+        requests: usize,
+    }
+
+    let cases = vec![
+        // every relay returns a blob the cert does not commit to
+        Case {
+            relay_keys: vec![0, 1],
+            script: vec![Ok(blob(0xbb)), Ok(blob(0xbb))],
+            malformed_payload: true,
+            retryable: false,
+            requests: 2,
+        },
+        Case {
+            relay_keys: vec![0, 1],
+            script: vec![Ok(blob(0xbb)), failed()],
+            malformed_payload: false,
+            retryable: false,
+            requests: 2,
+        },
+        // the unavailable relay may be back later
+        Case {
+            relay_keys: vec![0, 1],
+            script: vec![Ok(blob(0xbb)), unavailable()],
+            malformed_payload: false,
+            retryable: true,
+            requests: 2,
+        },
+        // no relay to fetch the blob from
+        Case {
+            relay_keys: vec![],
+            script: vec![],
+            malformed_payload: false,
+            retryable: false,
+            requests: 0,
+        },
+    ];
+
+    for case in cases {
+        let altda_commitment = relay_altda_commitment(&blob(0xaa), case.relay_keys);
+        let relay_client = Arc::new(ScriptedRelayClient {
+            script: Mutex::new(case.script.into()),
+            requests: AtomicUsize::new(0),
+        });
+        let retriever =
+            RelayRetriever::new(relay_client.clone(), Arc::new(StaticCertValidity(true)))
+                .with_retry_policy(ProxyRetryPolicy {
+                    max_retries: 0,
+                    ..Default::default()
+                });
+
+        let error = retriever.fetch_data(&altda_commitment).await.unwrap_err();
+        assert_eq!(
+            matches!(error, HokuleaHostError::MalformedPayload { .. }),
+            case.malformed_payload
+        );
+        assert_eq!(error.is_retryable(), case.retryable);
+        assert_eq!(relay_client.requests.load(Ordering::SeqCst), case.requests);
+    }
+}
@@ -0,0 +1,584 @@
+//! A stand-in for eigenda-proxy serving encoded payloads and derivation errors from fixtures.
+//! It only implements the `GET /get/{hex}` route the hokulea host relies on. The [relay] module
+//! holds a stand-in for the EigenDA relays, serving the blob fixtures. Tests of the host failure
+//! handling rather replay a script of [ScriptedResponse], whatever the request.
+
+pub mod relay;
+pub use relay::MockRelay;
+
+use alloy_primitives::{hex, B256};
+use anyhow::{anyhow, Result};
+use eigenda_cert::GenericAltDACommitment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Status code eigenda-proxy answers a derivation error with
+pub const HTTP_RESPONSE_STATUS_CODE_TEAPOT: u16 = 418;
+
+/// Extension of the fixture files holding a payload
+pub const PAYLOAD_FIXTURE_EXTENSION: &str = "bin";
+/// Extension of the fixture files holding a derivation error
+pub const DERIVATION_ERROR_FIXTURE_EXTENSION: &str = "json";
+/// Extension of the fixture files holding a blob served by the mock relay
+pub const BLOB_FIXTURE_EXTENSION: &str = "blob";
+
+/// Upper bound on the size of a request head, the route carries at most a few KiB of hex
+const MAX_REQUEST_HEAD_LEN: usize = 64 * 1024;
+
+/// What the mock answers for a commitment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockProxyResponse {
+    /// 200 with the encoded payload of a cert, or the input data of a keccak commitment
+    Payload(Vec<u8>),
+    /// 418 carrying a derivation error
+    DerivationError {
+        /// Status code of the derivation error, see `status_code.rs` of the host
+        status_code: u8,
+        /// Message of the derivation error
+        msg: String,
+    },
+}
+
+/// The json body of a 418, as serialized by eigenda-proxy
+#[derive(Debug, Serialize, Deserialize)]
+struct DerivationErrorBody {
+    #[serde(rename = "StatusCode")]
+    status_code: u8,
+    #[serde(rename = "Msg")]
+    msg: String,
+}
+
+/// The responses of the mock, keyed by the digest of the commitment, and the blobs of the mock
+/// relay, keyed by blob key
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    responses: HashMap<B256, MockProxyResponse>,
+    blobs: HashMap<B256, Vec<u8>>,
+}
+
+impl Fixtures {
+    /// Loads the fixtures from a directory, see the README for the layout. Files with any other
+    /// extension are ignored.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut fixtures = Self::default();
+        let read_dir = std::fs::read_dir(dir)
+            .map_err(|e| anyhow!("failed to read fixture dir {}: {e}", dir.display()))?;
+        for entry in read_dir {
+            let path = entry?.path();
+            let (Some(stem), Some(extension)) = (
+                path.file_stem().and_then(|s| s.to_str()),
+                path.extension().and_then(|e| e.to_str()),
+            ) else {
+                continue;
+            };
+
+            let response = match extension {
+                BLOB_FIXTURE_EXTENSION => {
+                    let blob_key = parse_fixture_key(stem, &path)?;
+                    if fixtures
+                        .blobs
+                        .insert(blob_key, std::fs::read(&path)?)
+                        .is_some()
+                    {
+                        return Err(anyhow!("more than one blob for key {blob_key}"));
+                    }
+                    continue;
+                }
+                PAYLOAD_FIXTURE_EXTENSION => MockProxyResponse::Payload(std::fs::read(&path)?),
+                DERIVATION_ERROR_FIXTURE_EXTENSION => {
+                    let body: DerivationErrorBody = serde_json::from_slice(&std::fs::read(&path)?)
+                        .map_err(|e| {
+                            anyhow!("invalid derivation error fixture {}: {e}", path.display())
+                        })?;
+                    MockProxyResponse::DerivationError {
+                        status_code: body.status_code,
+                        msg: body.msg,
+                    }
+                }
+                _ => continue,
+            };
+            let digest = parse_fixture_key(stem, &path)?;
+            if fixtures.responses.insert(digest, response).is_some() {
+                return Err(anyhow!("more than one fixture for digest {digest}"));
+            }
+        }
+        Ok(fixtures)
+    }
+
+    /// Adds the response for the commitment digest
+    pub fn with_response(mut self, digest: B256, response: MockProxyResponse) -> Self {
+        self.responses.insert(digest, response);
+        self
+    }
+
+    /// Adds the blob served by the mock relay for the blob key
+    pub fn with_blob(mut self, blob_key: B256, blob: Vec<u8>) -> Self {
+        self.blobs.insert(blob_key, blob);
+        self
+    }
+
+    /// The blobs served by the mock relay, keyed by blob key
+    pub fn blobs(&self) -> &HashMap<B256, Vec<u8>> {
+        &self.blobs
+    }
+
+    /// Number of commitments with a response
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Returns true if there is no fixture
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// Routes a request target, e.g. `/get/0100..?commitment_mode=optimism_generic`, to the status
+    /// code and body of the response
+    pub fn respond(&self, target: &str) -> (u16, Vec<u8>) {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let Some(commitment_hex) = path.strip_prefix("/get/") else {
+            return (404, b"unknown route".to_vec());
+        };
+        let Ok(commitment_bytes) = hex::decode(commitment_hex) else {
+            return (400, b"commitment is not hex".to_vec());
+        };
+        let commitment = match GenericAltDACommitment::try_from(commitment_bytes.as_slice()) {
+            Ok(commitment) => commitment,
+            Err(e) => return (400, format!("invalid commitment: {e}").into_bytes()),
+        };
+
+        match &commitment {
+            GenericAltDACommitment::Keccak(_) => {}
+            // the host derives from the encoded payload, a decoded payload would be a bug
+            GenericAltDACommitment::EigenDA(_) => {
+                if !query
+                    .split('&')
+                    .any(|param| param == "return_encoded_payload=true")
+                {
+                    return (400, b"only encoded payloads are served".to_vec());
+                }
+            }
+            GenericAltDACommitment::DaService { da_layer_byte, .. } => {
+                return (
+                    400,
+                    format!("unsupported da layer {da_layer_byte}").into_bytes(),
+                );
+            }
+        }
+
+        match self.responses.get(&commitment.to_digest()) {
+            Some(MockProxyResponse::Payload(payload)) => (200, payload.clone()),
+            Some(MockProxyResponse::DerivationError { status_code, msg }) => {
+                let body = DerivationErrorBody {
+                    status_code: *status_code,
+                    msg: msg.clone(),
+                };
+                (
+                    HTTP_RESPONSE_STATUS_CODE_TEAPOT,
+                    serde_json::to_vec(&body).expect("derivation error serializes"),
+                )
+            }
+            None => (404, b"no fixture for commitment".to_vec()),
+        }
+    }
+}
+
+/// A response replayed by a scripted [MockProxy]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptedResponse {
+    /// Status code of the response
+    pub status: u16,
+    /// Body of the response
+    pub body: Vec<u8>,
+    /// How long the response is held back, to exercise the timeouts of the host
+    pub delay: Duration,
+}
+
+impl ScriptedResponse {
+    /// Answers the status code and body right away
+    pub fn new(status: u16, body: &[u8]) -> Self {
+        Self {
+            status,
+            body: body.to_vec(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Answers a 418 carrying a derivation error with the status code
+    pub fn derivation_error(status_code: u8) -> Self {
+        let body = DerivationErrorBody {
+            status_code,
+            msg: "mock".into(),
+        };
+        Self::new(
+            HTTP_RESPONSE_STATUS_CODE_TEAPOT,
+            &serde_json::to_vec(&body).expect("derivation error serializes"),
+        )
+    }
+
+    /// Holds the response back for `delay`
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// How a [MockProxy] answers requests
+#[derive(Debug)]
+enum Responder {
+    /// Routes the request to the fixture of its commitment
+    Fixtures(Fixtures),
+    /// Replays the script in order, one response per request. Requests beyond it get a 500.
+    Script(Vec<ScriptedResponse>),
+}
+
+impl Responder {
+    /// The response to the `index`-th request, with its request line
+    fn respond(&self, index: usize, request_line: &str) -> ScriptedResponse {
+        match self {
+            Self::Fixtures(fixtures) => {
+                let (status, body) = match request_line.split_whitespace().collect::<Vec<_>>()[..] {
+                    ["GET", target, _version] => fixtures.respond(target),
+                    _ => (405, b"only GET is supported".to_vec()),
+                };
+                ScriptedResponse {
+                    status,
+                    body,
+                    delay: Duration::ZERO,
+                }
+            }
+            Self::Script(script) => script
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| ScriptedResponse::new(500, b"script exhausted")),
+        }
+    }
+}
+
+/// Fixture files are named after the digest of a commitment, or the key of a blob
+fn parse_fixture_key(stem: &str, path: &Path) -> Result<B256> {
+    stem.parse().map_err(|e| {
+        anyhow!(
+            "fixture {} is not named after a digest: {e}",
+            path.display()
+        )
+    })
+}
+
+/// A running mock proxy, stopped when dropped
+#[derive(Debug)]
+pub struct MockProxy {
+    addr: SocketAddr,
+    requests: Arc<AtomicUsize>,
+    task: JoinHandle<()>,
+}
+
+impl MockProxy {
+    /// Serves the fixtures on `addr`, port 0 picks a free port
+    pub async fn spawn(fixtures: Fixtures, addr: SocketAddr) -> Result<Self> {
+        Self::spawn_responder(Responder::Fixtures(fixtures), addr).await
+    }
+
+    /// Replays the script on `addr`, one response per request in order, port 0 picks a free port
+    pub async fn spawn_scripted(script: Vec<ScriptedResponse>, addr: SocketAddr) -> Result<Self> {
+        Self::spawn_responder(Responder::Script(script), addr).await
+    }
+
+    async fn spawn_responder(responder: Responder, addr: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let responder = Arc::new(responder);
+        let counter = requests.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        warn!(target = "mock-proxy", "failed to accept connection: {e}");
+                        continue;
+                    }
+                };
+                let index = counter.fetch_add(1, Ordering::SeqCst);
+                let responder = responder.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_connection(stream, &responder, index).await {
+                        warn!(target = "mock-proxy", "failed to serve request: {e}");
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            addr,
+            requests,
+            task,
+        })
+    }
+
+    /// The address the mock listens on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The base url to pass to the host as eigenda-proxy address
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    /// Number of requests served so far
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    /// Serves forever, unless the serving task panics
+    pub async fn join(mut self) -> Result<()> {
+        (&mut self.task)
+            .await
+            .map_err(|e| anyhow!("mock proxy stopped: {e}"))
+    }
+}
+
+impl Drop for MockProxy {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Reads a single request, one per connection, and writes the response
+async fn serve_connection(
+    mut stream: TcpStream,
+    responder: &Responder,
+    index: usize,
+) -> Result<()> {
+    let mut head = vec![];
+    let mut buf = [0u8; 4096];
+    while !head.ends_with(b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Err(anyhow!(
+                "connection closed before the end of the request head"
+            ));
+        }
+        head.extend_from_slice(&buf[..n]);
+        if head.len() > MAX_REQUEST_HEAD_LEN {
+            return Err(anyhow!("request head too large"));
+        }
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let request_line = head.lines().next().unwrap_or_default();
+    let ScriptedResponse {
+        status,
+        body,
+        delay,
+    } = responder.respond(index, request_line);
+    debug!(target = "mock-proxy", "{request_line} -> {status}");
+    tokio::time::sleep(delay).await;
+
+    let header = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        reason_phrase(status),
+        body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        500 => "Internal Server Error",
+        HTTP_RESPONSE_STATUS_CODE_TEAPOT => "I'm a teapot",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::keccak256;
+    use eigenda_cert::AltDACommitment;
+
+    fn altda_commitment() -> AltDACommitment {
+        AltDACommitment::from_canonical_json(include_str!(
+            "../../../crates/eigenda-cert/testdata/altda_commitment_v3.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_respond() {
+        let altda_commitment = altda_commitment();
+        let cert_hex = hex::encode(altda_commitment.to_rlp_bytes());
+        let input_data = b"keccak preimage";
+        let keccak_hex =
+            hex::encode(GenericAltDACommitment::Keccak(keccak256(input_data)).to_bytes());
+
+        let fixtures = Fixtures::default()
+            .with_response(
+                altda_commitment.to_digest(),
+                MockProxyResponse::Payload(vec![0, 1, 2]),
+            )
+            .with_response(
+                keccak256(input_data),
+                MockProxyResponse::Payload(input_data.to_vec()),
+            );
+        let invalid_cert = Fixtures::default().with_response(
+            altda_commitment.to_digest(),
+            MockProxyResponse::DerivationError {
+                status_code: 3,
+                msg: "invalid cert".into(),
+            },
+        );
+
+        struct Case {
+            fixtures: Fixtures,
+            target: String,
+            status: u16,
+            body: Vec<u8>,
+        }
+        let cases = vec![
+            Case {
+                fixtures: fixtures.clone(),
+                target: format!(
+                    "/get/{cert_hex}?commitment_mode=optimism_generic&return_encoded_payload=true"
+                ),
+                status: 200,
+                body: vec![0, 1, 2],
+            },
+            Case {
+                fixtures: invalid_cert,
+                target: format!("/get/{cert_hex}?return_encoded_payload=true"),
+                status: HTTP_RESPONSE_STATUS_CODE_TEAPOT,
+                body: br#"{"StatusCode":3,"Msg":"invalid cert"}"#.to_vec(),
+            },
+            Case {
+                fixtures: fixtures.clone(),
+                target: format!("/get/{keccak_hex}"),
+                status: 200,
+                body: input_data.to_vec(),
+            },
+            // decoded payloads are not served
+            Case {
+                fixtures: fixtures.clone(),
+                target: format!("/get/{cert_hex}?commitment_mode=optimism_generic"),
+                status: 400,
+                body: b"only encoded payloads are served".to_vec(),
+            },
+            Case {
+                fixtures: Fixtures::default(),
+                target: format!("/get/{cert_hex}?return_encoded_payload=true"),
+                status: 404,
+                body: b"no fixture for commitment".to_vec(),
+            },
+            Case {
+                fixtures: fixtures.clone(),
+                target: "/get/zz".into(),
+                status: 400,
+                body: b"commitment is not hex".to_vec(),
+            },
+            Case {
+                fixtures: fixtures.clone(),
+                target: "/put/00".into(),
+                status: 404,
+                body: b"unknown route".to_vec(),
+            },
+        ];
+
+        for case in cases {
+            let (status, body) = case.fixtures.respond(&case.target);
+            assert_eq!(status, case.status);
+            assert_eq!(body, case.body);
+        }
+    }
+
+    #[test]
+    fn test_respond_scripted() {
+        let responder = Responder::Script(vec![
+            ScriptedResponse::new(503, b""),
+            ScriptedResponse::derivation_error(2).with_delay(Duration::from_millis(10)),
+        ]);
+        let request_line = "GET /get/00 HTTP/1.1";
+
+        assert_eq!(
+            responder.respond(0, request_line),
+            ScriptedResponse::new(503, b"")
+        );
+        let response = responder.respond(1, request_line);
+        assert_eq!(response.status, HTTP_RESPONSE_STATUS_CODE_TEAPOT);
+        assert_eq!(response.body, br#"{"StatusCode":2,"Msg":"mock"}"#);
+        assert_eq!(response.delay, Duration::from_millis(10));
+        // whatever the request, beyond the script
+        assert_eq!(
+            responder.respond(2, "POST /put HTTP/1.1"),
+            ScriptedResponse::new(500, b"script exhausted")
+        );
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir().join(format!("hokulea-mock-proxy-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let (a, b, c) = (
+            B256::with_last_byte(1),
+            B256::with_last_byte(2),
+            B256::with_last_byte(3),
+        );
+        std::fs::write(dir.join(format!("{a:x}.bin")), [7u8; 32]).unwrap();
+        std::fs::write(
+            dir.join(format!("{b:x}.json")),
+            r#"{"StatusCode": 2, "Msg": "stale cert"}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join(format!("{c:x}.blob")), [0u8; 64]).unwrap();
+        std::fs::write(dir.join("README.md"), "ignored").unwrap();
+
+        let fixtures = Fixtures::load(&dir).unwrap();
+        assert_eq!(fixtures.len(), 2);
+        assert_eq!(
+            fixtures.responses[&a],
+            MockProxyResponse::Payload(vec![7u8; 32])
+        );
+        assert_eq!(
+            fixtures.responses[&b],
+            MockProxyResponse::DerivationError {
+                status_code: 2,
+                msg: "stale cert".into()
+            }
+        );
+        assert_eq!(fixtures.blobs().len(), 1);
+        assert_eq!(fixtures.blobs()[&c], vec![0u8; 64]);
+
+        // a payload and a derivation error for the same digest
+        std::fs::write(
+            dir.join(format!("{a:x}.json")),
+            r#"{"StatusCode": 3, "Msg": ""}"#,
+        )
+        .unwrap();
+        assert!(Fixtures::load(&dir).is_err());
+        std::fs::remove_file(dir.join(format!("{a:x}.json"))).unwrap();
+
+        std::fs::write(dir.join("not-a-digest.bin"), [0u8]).unwrap();
+        assert!(Fixtures::load(&dir).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
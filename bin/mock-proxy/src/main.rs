@@ -0,0 +1,50 @@
+//! Main entrypoint for the mock eigenda-proxy.
+
+use clap::Parser;
+use hokulea_mock_proxy::{Fixtures, MockProxy, MockRelay};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tracing::info;
+
+/// The mock eigenda-proxy CLI application arguments.
+#[derive(Parser, Debug)]
+struct MockProxyArgs {
+    /// Directory holding the fixtures, see the README for the layout.
+    #[clap(long, env)]
+    fixtures: PathBuf,
+
+    /// Address to listen on.
+    #[clap(long, default_value = "127.0.0.1:3100", env)]
+    addr: SocketAddr,
+
+    /// Address the mock relay serving the blob fixtures listens on. Not started if not set.
+    #[clap(long, env)]
+    relay_addr: Option<SocketAddr>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = MockProxyArgs::parse();
+    tracing_subscriber::fmt::init();
+
+    let fixtures = Fixtures::load(&args.fixtures)?;
+    let mock_proxy = MockProxy::spawn(fixtures.clone(), args.addr).await?;
+    info!(
+        "serving {} fixtures from {} on {}",
+        fixtures.len(),
+        args.fixtures.display(),
+        mock_proxy.url()
+    );
+
+    let Some(relay_addr) = args.relay_addr else {
+        return mock_proxy.join().await;
+    };
+    let mock_relay = MockRelay::spawn(fixtures.blobs().clone(), relay_addr).await?;
+    info!(
+        "serving {} blobs on {}",
+        fixtures.blobs().len(),
+        mock_relay.url()
+    );
+    tokio::try_join!(mock_proxy.join(), mock_relay.join())?;
+    Ok(())
+}
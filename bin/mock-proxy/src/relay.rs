@@ -0,0 +1,149 @@
+//! A stand-in for an EigenDA relay serving blobs by blob key. It only implements the `GetBlob`
+//! method of the relay gRPC service the hokulea host relies on.
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use eigenda_cert::relay::{GetBlobReply, GetBlobRequest, GET_BLOB_PATH};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tonic::body::BoxBody;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{http, BoxFuture, Context, Poll, Service};
+use tonic::server::{Grpc, NamedService, UnaryService};
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+use tracing::{debug, warn};
+
+/// A running mock relay, stopped when dropped
+#[derive(Debug)]
+pub struct MockRelay {
+    addr: SocketAddr,
+    requests: Arc<AtomicUsize>,
+    task: JoinHandle<()>,
+}
+
+impl MockRelay {
+    /// Serves the blobs, keyed by blob key, on `addr`, port 0 picks a free port
+    pub async fn spawn(blobs: HashMap<B256, Vec<u8>>, addr: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let incoming = TcpIncoming::from_listener(listener, true, None)
+            .map_err(|e| anyhow!("failed to listen on {addr}: {e}"))?;
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let service = RelayService {
+            blobs: Arc::new(blobs),
+            requests: requests.clone(),
+        };
+        let task = tokio::spawn(async move {
+            if let Err(e) = Server::builder()
+                .add_service(service)
+                .serve_with_incoming(incoming)
+                .await
+            {
+                warn!(target = "mock-relay", "stopped serving: {e}");
+            }
+        });
+
+        Ok(Self {
+            addr,
+            requests,
+            task,
+        })
+    }
+
+    /// The address the mock listens on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The url to pass to the host as relay address
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Number of `GetBlob` requests served so far
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    /// Serves forever, unless the serving task panics
+    pub async fn join(mut self) -> Result<()> {
+        (&mut self.task)
+            .await
+            .map_err(|e| anyhow!("mock relay stopped: {e}"))
+    }
+}
+
+impl Drop for MockRelay {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// The relay gRPC service, routing requests by method path
+#[derive(Debug, Clone)]
+struct RelayService {
+    blobs: Arc<HashMap<B256, Vec<u8>>>,
+    requests: Arc<AtomicUsize>,
+}
+
+impl NamedService for RelayService {
+    const NAME: &'static str = "relay.Relay";
+}
+
+impl Service<http::Request<BoxBody>> for RelayService {
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<BoxBody>) -> Self::Future {
+        let service = self.clone();
+        Box::pin(async move {
+            if req.uri().path() != GET_BLOB_PATH {
+                return Ok(
+                    Status::unimplemented(format!("unknown method {}", req.uri())).into_http(),
+                );
+            }
+            let mut grpc = Grpc::new(ProstCodec::default());
+            Ok(grpc.unary(GetBlobMethod(service), req).await)
+        })
+    }
+}
+
+/// The `GetBlob` method of the [RelayService]
+struct GetBlobMethod(RelayService);
+
+impl UnaryService<GetBlobRequest> for GetBlobMethod {
+    type Response = GetBlobReply;
+    type Future = BoxFuture<Response<Self::Response>, Status>;
+
+    fn call(&mut self, request: Request<GetBlobRequest>) -> Self::Future {
+        let service = self.0.clone();
+        Box::pin(async move {
+            service.requests.fetch_add(1, Ordering::SeqCst);
+            let blob_key = B256::try_from(request.get_ref().blob_key.as_slice())
+                .map_err(|_| Status::invalid_argument("blob key must be 32 bytes"))?;
+            let blob = service.blobs.get(&blob_key).cloned();
+            debug!(
+                target = "mock-relay",
+                "GetBlob {blob_key} -> {}",
+                blob.is_some()
+            );
+            match blob {
+                Some(blob) => Ok(Response::new(GetBlobReply { blob })),
+                None => Err(Status::not_found(format!("no blob for key {blob_key}"))),
+            }
+        })
+    }
+}
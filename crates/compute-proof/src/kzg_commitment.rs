@@ -0,0 +1,48 @@
+//! Host side checks of the data returned for an eigenda blob against the kzg commitment carried
+//! by the cert, such that forged data is rejected before it reaches the zkVM.
+use crate::kzg_proof::convert_biguint_to_be_32_bytes;
+use crate::G1_SRS;
+use alloy_primitives::Bytes;
+use num::BigUint;
+use rust_kzg_bn254_primitives::blob::Blob;
+use rust_kzg_bn254_primitives::errors::KzgError;
+use rust_kzg_bn254_prover::kzg::KZG;
+use rust_kzg_bn254_prover::srs::SRS;
+
+/// This function computes the kzg commitment of an eigenDA blob from its encoded payload. The blob
+/// is the inverse Fourier Transform of the encoded payload, hence the commitment is computed on the
+/// evaluation form. The returned 64 bytes are the big endian x and y coordinates, the same layout as
+/// [crate::compute_kzg_proof].
+pub fn compute_kzg_commitment(encoded_payload: &[u8]) -> Result<Bytes, KzgError> {
+    compute_kzg_commitment_with_srs(encoded_payload, &G1_SRS)
+}
+
+/// This function computes the kzg commitment of an eigenDA blob from its encoded payload, see
+/// [compute_kzg_commitment]
+pub fn compute_kzg_commitment_with_srs<'s>(
+    encoded_payload: &[u8],
+    srs: &SRS<'s>,
+) -> Result<Bytes, KzgError> {
+    let mut kzg = KZG::new();
+    kzg.calculate_and_store_roots_of_unity(encoded_payload.len() as u64)?;
+
+    let blob = Blob::new(encoded_payload)?;
+    let input_poly = blob.to_polynomial_eval_form()?;
+    let commitment = kzg.commit_eval_form(&input_poly, srs)?;
+
+    let commitment_x_bigint: BigUint = commitment.x.into();
+    let commitment_y_bigint: BigUint = commitment.y.into();
+
+    let mut commitment_bytes = convert_biguint_to_be_32_bytes(&commitment_x_bigint).to_vec();
+    commitment_bytes.extend_from_slice(&convert_biguint_to_be_32_bytes(&commitment_y_bigint));
+    Ok(commitment_bytes.into())
+}
+
+/// This function converts an eigenDA blob, as served by the relays in its coefficient form, into
+/// the encoded payload, its evaluation form. The blob must already be padded to the number of
+/// field elements of the cert.
+pub fn encoded_payload_from_blob(blob: &[u8]) -> Result<Vec<u8>, KzgError> {
+    let blob = Blob::new(blob)?;
+    let coeff_poly = blob.to_polynomial_coeff_form()?;
+    Ok(coeff_poly.to_eval_form()?.to_bytes_be())
+}
@@ -9,11 +9,15 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+pub mod kzg_commitment;
 pub mod kzg_proof;
 use alloy_primitives::FixedBytes;
 use ark_bn254::G1Affine;
 use ark_serialize::CanonicalDeserialize;
 use hokulea_proof::EigenDAPreimage;
+pub use kzg_commitment::{
+    compute_kzg_commitment, compute_kzg_commitment_with_srs, encoded_payload_from_blob,
+};
 pub use kzg_proof::{
     compute_kzg_proof, compute_kzg_proof_with_srs, convert_biguint_to_be_32_bytes,
 };
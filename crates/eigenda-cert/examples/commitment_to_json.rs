@@ -0,0 +1,25 @@
+//! Converts an AltDA commitment hex to its canonical JSON form, and back.
+//!
+//! ```sh
+//! echo "010002f908f8..." | cargo run --example commitment_to_json -p eigenda-cert --features json
+//! cargo run --example commitment_to_json -p eigenda-cert --features json < cert.json
+//! ```
+
+use alloy_primitives::Bytes;
+use eigenda_cert::AltDACommitment;
+use std::{io::Read, str::FromStr};
+
+fn main() {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).unwrap();
+    let input = input.trim();
+
+    if input.starts_with('{') {
+        let comm = AltDACommitment::from_canonical_json(input).expect("failed to parse json");
+        println!("{}", Bytes::from(comm.to_rlp_bytes()));
+    } else {
+        let bytes = Bytes::from_str(input).expect("invalid hex input");
+        let comm = AltDACommitment::try_from(&bytes[..]).expect("failed to parse AltDA commitment");
+        println!("{}", comm.to_canonical_json());
+    }
+}
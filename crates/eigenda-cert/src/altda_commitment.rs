@@ -1,7 +1,7 @@
-use crate::{EigenDACertV2, EigenDACertV3, EigenDACertV4, G1Point};
+use crate::{CertView, EigenDACertV2, EigenDACertV3, EigenDACertV4, G1Point};
 use alloc::vec::Vec;
 use alloy_primitives::keccak256;
-use alloy_primitives::B256;
+use alloy_primitives::{Bytes, B256};
 use alloy_rlp::Decodable;
 use alloy_rlp::Encodable;
 use alloy_rlp::Error;
@@ -32,6 +32,8 @@ pub enum AltDACommitmentParseError {
     UnsupportedCertVersionType(u8),
     #[error("Unable to decode rlp cert: {0}")]
     InvalidRlpCert(Error),
+    #[error("Keccak commitment must be 33 bytes, got {0}")]
+    InvalidKeccakCommitmentLength(usize),
 }
 
 /// AltDACommitment contains EigenDA cert, and is used as a part of key to uniquely
@@ -59,13 +61,13 @@ impl TryFrom<&[u8]> for AltDACommitment {
         // <https://specs.optimism.io/experimental/alt-da.html#input-commitment-submission>
         // 0 for keccak, 1 for da-service
         let commitment_type = value[0];
-        if commitment_type != 1 {
+        if commitment_type != GENERIC_COMMITMENT_TYPE {
             return Err(AltDACommitmentParseError::UnsupportedCommitmentType);
         }
 
         // da_layer_byte, eigenda is 0
         let da_layer_byte = value[1];
-        if da_layer_byte != 0 {
+        if da_layer_byte != EIGENDA_DA_LAYER_BYTE {
             return Err(AltDACommitmentParseError::UnsupportedDaLayerType);
         }
 
@@ -103,6 +105,124 @@ impl TryFrom<&[u8]> for AltDACommitment {
     }
 }
 
+/// <https://specs.optimism.io/experimental/alt-da.html#input-commitment-submission>
+/// commitment_type of keccak commitment
+pub const KECCAK_COMMITMENT_TYPE: u8 = 0;
+/// commitment_type of generic commitment, i.e. da-service
+pub const GENERIC_COMMITMENT_TYPE: u8 = 1;
+/// da_layer_byte of generic commitment for eigenda
+pub const EIGENDA_DA_LAYER_BYTE: u8 = 0;
+
+/// GenericAltDACommitment is an altda commitment of any commitment type supported by the
+/// derivation. Chains migrated from keccak based altda still carry historical batches with
+/// keccak commitments, whose input data is addressed by its keccak256 hash
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)] //
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)] //
+pub enum GenericAltDACommitment {
+    /// commitment_type 0, keccak256 hash of the input data
+    Keccak(B256),
+    /// commitment_type 1, with eigenda da_layer_byte
+    EigenDA(AltDACommitment),
+    /// commitment_type 1, with the da_layer_byte of any other DA service
+    DaService {
+        /// da_layer_byte of the DA service
+        da_layer_byte: u8,
+        /// opaque commitment following the da_layer_byte, interpreted by the DA service
+        commitment: Bytes,
+    },
+}
+
+impl TryFrom<&[u8]> for GenericAltDACommitment {
+    type Error = AltDACommitmentParseError;
+    fn try_from(value: &[u8]) -> Result<GenericAltDACommitment, Self::Error> {
+        match value.first() {
+            // keccak commitment is exactly the commitment type followed by a 32 bytes hash
+            Some(&KECCAK_COMMITMENT_TYPE) => {
+                if value.len() != 1 + B256::len_bytes() {
+                    return Err(AltDACommitmentParseError::InvalidKeccakCommitmentLength(
+                        value.len(),
+                    ));
+                }
+                Ok(GenericAltDACommitment::Keccak(B256::from_slice(
+                    &value[1..],
+                )))
+            }
+            // generic commitment of other DA service is at least commitment type, da_layer_byte
+            // and one byte of commitment
+            Some(&GENERIC_COMMITMENT_TYPE)
+                if value.len() >= 3 && value[1] != EIGENDA_DA_LAYER_BYTE =>
+            {
+                Ok(GenericAltDACommitment::DaService {
+                    da_layer_byte: value[1],
+                    commitment: Bytes::copy_from_slice(&value[2..]),
+                })
+            }
+            _ => AltDACommitment::try_from(value).map(GenericAltDACommitment::EigenDA),
+        }
+    }
+}
+
+impl From<AltDACommitment> for GenericAltDACommitment {
+    fn from(altda_commitment: AltDACommitment) -> Self {
+        GenericAltDACommitment::EigenDA(altda_commitment)
+    }
+}
+
+impl GenericAltDACommitment {
+    /// Get commitment type, 0 for keccak, 1 for da-service
+    pub fn commitment_type(&self) -> u8 {
+        match self {
+            GenericAltDACommitment::Keccak(_) => KECCAK_COMMITMENT_TYPE,
+            GenericAltDACommitment::EigenDA(c) => c.commitment_type,
+            GenericAltDACommitment::DaService { .. } => GENERIC_COMMITMENT_TYPE,
+        }
+    }
+
+    /// Get the da_layer_byte of a generic commitment, None for a keccak commitment
+    pub fn da_layer_byte(&self) -> Option<u8> {
+        match self {
+            GenericAltDACommitment::Keccak(_) => None,
+            GenericAltDACommitment::EigenDA(_) => Some(EIGENDA_DA_LAYER_BYTE),
+            GenericAltDACommitment::DaService { da_layer_byte, .. } => Some(*da_layer_byte),
+        }
+    }
+
+    /// Convert GenericAltDACommitment into bytes in the same form downloaded from
+    /// Ethereum block
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            GenericAltDACommitment::Keccak(hash) => {
+                let mut bytes = Vec::with_capacity(1 + B256::len_bytes());
+                bytes.push(KECCAK_COMMITMENT_TYPE);
+                bytes.extend_from_slice(hash.as_slice());
+                bytes
+            }
+            GenericAltDACommitment::EigenDA(c) => c.to_rlp_bytes(),
+            GenericAltDACommitment::DaService {
+                da_layer_byte,
+                commitment,
+            } => {
+                let mut bytes = Vec::with_capacity(2 + commitment.len());
+                bytes.push(GENERIC_COMMITMENT_TYPE);
+                bytes.push(*da_layer_byte);
+                bytes.extend_from_slice(commitment);
+                bytes
+            }
+        }
+    }
+
+    /// Convert GenericAltDACommitment into hash digest. The digest of a keccak commitment
+    /// is the keccak256 hash it carries
+    pub fn to_digest(&self) -> B256 {
+        match self {
+            GenericAltDACommitment::Keccak(hash) => *hash,
+            GenericAltDACommitment::EigenDA(c) => c.to_digest(),
+            GenericAltDACommitment::DaService { .. } => keccak256(self.to_bytes()),
+        }
+    }
+}
+
 impl AltDACommitment {
     /// This function preprare a holder for a key used to fetch field elements for
     /// eigenda encoded payload. The analogous code for eth blob can be found
@@ -130,86 +250,17 @@ impl AltDACommitment {
 
     /// get number of field element for a cert
     pub fn get_num_field_element(&self) -> u64 {
-        match &self.versioned_cert {
-            EigenDAVersionedCert::V2(c) => {
-                c.blob_inclusion_info
-                    .blob_certificate
-                    .blob_header
-                    .commitment
-                    .length as u64
-            }
-            EigenDAVersionedCert::V3(c) => {
-                c.blob_inclusion_info
-                    .blob_certificate
-                    .blob_header
-                    .commitment
-                    .length as u64
-            }
-            EigenDAVersionedCert::V4(c) => {
-                c.blob_inclusion_info
-                    .blob_certificate
-                    .blob_header
-                    .commitment
-                    .length as u64
-            }
-        }
+        self.versioned_cert.num_field_element()
     }
 
     /// get reference block number
     pub fn get_rbn(&self) -> u64 {
-        match &self.versioned_cert {
-            EigenDAVersionedCert::V2(c) => c.batch_header_v2.reference_block_number as u64,
-            EigenDAVersionedCert::V3(c) => c.batch_header_v2.reference_block_number as u64,
-            EigenDAVersionedCert::V4(c) => c.batch_header_v2.reference_block_number as u64,
-        }
+        self.versioned_cert.reference_block_number()
     }
 
     /// get kzg commitment g1 point, first U256 is x coordinate, second is y
     pub fn get_kzg_commitment(&self) -> G1Point {
-        match &self.versioned_cert {
-            EigenDAVersionedCert::V2(c) => G1Point {
-                x: c.blob_inclusion_info
-                    .blob_certificate
-                    .blob_header
-                    .commitment
-                    .commitment
-                    .x,
-                y: c.blob_inclusion_info
-                    .blob_certificate
-                    .blob_header
-                    .commitment
-                    .commitment
-                    .y,
-            },
-            EigenDAVersionedCert::V3(c) => G1Point {
-                x: c.blob_inclusion_info
-                    .blob_certificate
-                    .blob_header
-                    .commitment
-                    .commitment
-                    .x,
-                y: c.blob_inclusion_info
-                    .blob_certificate
-                    .blob_header
-                    .commitment
-                    .commitment
-                    .y,
-            },
-            EigenDAVersionedCert::V4(c) => G1Point {
-                x: c.blob_inclusion_info
-                    .blob_certificate
-                    .blob_header
-                    .commitment
-                    .commitment
-                    .x,
-                y: c.blob_inclusion_info
-                    .blob_certificate
-                    .blob_header
-                    .commitment
-                    .commitment
-                    .y,
-            },
-        }
+        self.versioned_cert.kzg_commitment()
     }
 
     /// Convert AltdaCommitment into bytes in the same form downloaded from
@@ -260,12 +311,15 @@ impl AltDACommitment {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::ALTDA_COMMITMENT_V3;
     use alloc::vec;
     use alloy_primitives::Bytes;
 
     #[test]
     fn test_try_into_altda_commitment_and_to_rlp_bytes_ok() {
-        let calldata: Bytes = alloy_primitives::hex::decode("0x010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001").unwrap().into();
+        let calldata: Bytes = alloy_primitives::hex::decode(ALTDA_COMMITMENT_V3)
+            .unwrap()
+            .into();
         let altda_commitment: AltDACommitment = calldata[..].try_into().unwrap();
         let calldata_serialized = altda_commitment.to_rlp_bytes();
         assert_eq!(calldata, calldata_serialized);
@@ -273,7 +327,9 @@ mod tests {
 
     #[test]
     fn test_try_into_altda_commitment() {
-        let calldata: Bytes = alloy_primitives::hex::decode("0x010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001").unwrap().into();
+        let calldata: Bytes = alloy_primitives::hex::decode(ALTDA_COMMITMENT_V3)
+            .unwrap()
+            .into();
         let altda_commitment: AltDACommitment = calldata[..].try_into().unwrap();
 
         struct Case {
@@ -324,4 +380,105 @@ mod tests {
             assert_eq!(result, case.result);
         }
     }
+
+    #[test]
+    fn test_try_into_generic_altda_commitment() {
+        let calldata: Bytes = alloy_primitives::hex::decode(ALTDA_COMMITMENT_V3)
+            .unwrap()
+            .into();
+        let altda_commitment: AltDACommitment = calldata[..].try_into().unwrap();
+        let keccak_hash = keccak256(b"hokulea");
+
+        struct Case {
+            input: Bytes,
+            result: Result<GenericAltDACommitment, AltDACommitmentParseError>,
+        }
+
+        let cases = vec![
+            Case {
+                input: Bytes::new(),
+                result: Err(AltDACommitmentParseError::InsufficientData),
+            },
+            Case {
+                input: [&[KECCAK_COMMITMENT_TYPE], keccak_hash.as_slice()]
+                    .concat()
+                    .into(),
+                result: Ok(GenericAltDACommitment::Keccak(keccak_hash)),
+            },
+            Case {
+                input: [&[KECCAK_COMMITMENT_TYPE], &keccak_hash[..31]]
+                    .concat()
+                    .into(),
+                result: Err(AltDACommitmentParseError::InvalidKeccakCommitmentLength(32)),
+            },
+            Case {
+                input: [&[KECCAK_COMMITMENT_TYPE], keccak_hash.as_slice(), &[0]]
+                    .concat()
+                    .into(),
+                result: Err(AltDACommitmentParseError::InvalidKeccakCommitmentLength(34)),
+            },
+            Case {
+                input: calldata.clone(),
+                result: Ok(GenericAltDACommitment::EigenDA(altda_commitment.clone())),
+            },
+            Case {
+                input: alloy_primitives::hex::decode("0x01070a0b").unwrap().into(),
+                result: Ok(GenericAltDACommitment::DaService {
+                    da_layer_byte: 7,
+                    commitment: alloy_primitives::hex::decode("0x0a0b").unwrap().into(),
+                }),
+            },
+            // generic commitment without commitment bytes
+            Case {
+                input: alloy_primitives::hex::decode("0x0107").unwrap().into(),
+                result: Err(AltDACommitmentParseError::InsufficientData),
+            },
+            Case {
+                input: {
+                    let mut alt = altda_commitment.clone();
+                    alt.commitment_type = 2;
+                    alt.to_rlp_bytes().into()
+                },
+                result: Err(AltDACommitmentParseError::UnsupportedCommitmentType),
+            },
+        ];
+
+        for case in cases {
+            let result: Result<GenericAltDACommitment, AltDACommitmentParseError> =
+                case.input.as_ref().try_into();
+            assert_eq!(result, case.result);
+            // serialization round trips
+            if let Ok(commitment) = result {
+                assert_eq!(commitment.to_bytes(), case.input);
+            }
+        }
+
+        let keccak_commitment = GenericAltDACommitment::Keccak(keccak_hash);
+        assert_eq!(keccak_commitment.commitment_type(), KECCAK_COMMITMENT_TYPE);
+        assert_eq!(keccak_commitment.to_digest(), keccak_hash);
+        assert_eq!(keccak_commitment.da_layer_byte(), None);
+        let eigenda_commitment: GenericAltDACommitment = altda_commitment.clone().into();
+        assert_eq!(
+            eigenda_commitment.commitment_type(),
+            GENERIC_COMMITMENT_TYPE
+        );
+        assert_eq!(eigenda_commitment.to_digest(), altda_commitment.to_digest());
+        assert_eq!(
+            eigenda_commitment.da_layer_byte(),
+            Some(EIGENDA_DA_LAYER_BYTE)
+        );
+        let da_service_commitment = GenericAltDACommitment::DaService {
+            da_layer_byte: 7,
+            commitment: Bytes::from_static(&[10, 11]),
+        };
+        assert_eq!(
+            da_service_commitment.commitment_type(),
+            GENERIC_COMMITMENT_TYPE
+        );
+        assert_eq!(
+            da_service_commitment.to_digest(),
+            keccak256([GENERIC_COMMITMENT_TYPE, 7, 10, 11])
+        );
+        assert_eq!(da_service_commitment.da_layer_byte(), Some(7));
+    }
 }
@@ -0,0 +1,257 @@
+//! Contains the [CertView] trait, which exposes the fields shared by all cert versions, such
+//! that certs can be inspected without matching over every [EigenDAVersionedCert].
+
+#[cfg(feature = "structure")]
+use crate::structure::{self, CertStructureError};
+use crate::{
+    BatchHeaderV2, BlobCertificate, BlobCommitment, BlobHeaderV2, BlobInclusionInfo, EigenDACertV2,
+    EigenDACertV3, EigenDACertV4, EigenDAVersionedCert, G1Point, NonSignerStakesAndSignature,
+};
+use alloy_primitives::Bytes;
+
+/// Read-only view over the fields common to EigenDA certs. Implementors only provide the
+/// top level fields, the nested fields are derived from them.
+pub trait CertView {
+    /// batch header signed by the operators
+    fn batch_header(&self) -> &BatchHeaderV2;
+
+    /// inclusion info of the blob within the batch
+    fn blob_inclusion_info(&self) -> &BlobInclusionInfo;
+
+    /// non-signers, their stakes and the aggregate signature
+    fn nonsigner_stake_and_signature(&self) -> &NonSignerStakesAndSignature;
+
+    /// quorums that signed the batch
+    fn signed_quorum_numbers(&self) -> &Bytes;
+
+    /// offchain derivation version, only carried by V4 cert and onward
+    fn offchain_derivation_version(&self) -> Option<u16> {
+        None
+    }
+
+    /// reference block number of the batch
+    fn reference_block_number(&self) -> u64 {
+        self.batch_header().reference_block_number as u64
+    }
+
+    /// blob certificate of the blob
+    fn blob_certificate(&self) -> &BlobCertificate {
+        &self.blob_inclusion_info().blob_certificate
+    }
+
+    /// blob header of the blob
+    fn blob_header(&self) -> &BlobHeaderV2 {
+        &self.blob_certificate().blob_header
+    }
+
+    /// blob version of the blob header
+    fn blob_version(&self) -> u16 {
+        self.blob_header().version
+    }
+
+    /// quorums that the blob is dispersed to
+    fn quorum_numbers(&self) -> &Bytes {
+        &self.blob_header().quorum_numbers
+    }
+
+    /// hash of the payment header of the blob
+    fn payment_header_hash(&self) -> &[u8; 32] {
+        &self.blob_header().payment_header_hash
+    }
+
+    /// keys of the relays serving the blob
+    fn relay_keys(&self) -> &[u32] {
+        &self.blob_certificate().relay_keys
+    }
+
+    /// blob commitment, including the kzg commitment and the length of the blob
+    fn blob_commitment(&self) -> &BlobCommitment {
+        &self.blob_header().commitment
+    }
+
+    /// kzg commitment of the blob
+    fn kzg_commitment(&self) -> G1Point {
+        self.blob_commitment().commitment
+    }
+
+    /// number of field elements of the blob
+    fn num_field_element(&self) -> u64 {
+        self.blob_commitment().length as u64
+    }
+
+    /// Checks the structure of the cert offline: quorum numbers are sorted and unique, signed
+    /// quorums are quorums of the blob header, the non-signer data is length consistent with
+    /// the signed quorums, all G1 and G2 points are on the curve and in the subgroup, and the
+    /// blob length is a power of two.
+    #[cfg(feature = "structure")]
+    fn validate_structure(&self) -> Result<(), CertStructureError> {
+        structure::validate_structure(self)
+    }
+}
+
+impl CertView for EigenDACertV2 {
+    fn batch_header(&self) -> &BatchHeaderV2 {
+        &self.batch_header_v2
+    }
+
+    fn blob_inclusion_info(&self) -> &BlobInclusionInfo {
+        &self.blob_inclusion_info
+    }
+
+    fn nonsigner_stake_and_signature(&self) -> &NonSignerStakesAndSignature {
+        &self.nonsigner_stake_and_signature
+    }
+
+    fn signed_quorum_numbers(&self) -> &Bytes {
+        &self.signed_quorum_numbers
+    }
+}
+
+impl CertView for EigenDACertV3 {
+    fn batch_header(&self) -> &BatchHeaderV2 {
+        &self.batch_header_v2
+    }
+
+    fn blob_inclusion_info(&self) -> &BlobInclusionInfo {
+        &self.blob_inclusion_info
+    }
+
+    fn nonsigner_stake_and_signature(&self) -> &NonSignerStakesAndSignature {
+        &self.nonsigner_stake_and_signature
+    }
+
+    fn signed_quorum_numbers(&self) -> &Bytes {
+        &self.signed_quorum_numbers
+    }
+}
+
+impl CertView for EigenDACertV4 {
+    fn batch_header(&self) -> &BatchHeaderV2 {
+        &self.batch_header_v2
+    }
+
+    fn blob_inclusion_info(&self) -> &BlobInclusionInfo {
+        &self.blob_inclusion_info
+    }
+
+    fn nonsigner_stake_and_signature(&self) -> &NonSignerStakesAndSignature {
+        &self.nonsigner_stake_and_signature
+    }
+
+    fn signed_quorum_numbers(&self) -> &Bytes {
+        &self.signed_quorum_numbers
+    }
+
+    fn offchain_derivation_version(&self) -> Option<u16> {
+        Some(self.offchain_derivation_version)
+    }
+}
+
+impl EigenDAVersionedCert {
+    /// Returns the cert of any version as a [CertView]
+    pub fn as_view(&self) -> &dyn CertView {
+        match self {
+            EigenDAVersionedCert::V2(c) => c,
+            EigenDAVersionedCert::V3(c) => c,
+            EigenDAVersionedCert::V4(c) => c,
+        }
+    }
+}
+
+impl CertView for EigenDAVersionedCert {
+    fn batch_header(&self) -> &BatchHeaderV2 {
+        self.as_view().batch_header()
+    }
+
+    fn blob_inclusion_info(&self) -> &BlobInclusionInfo {
+        self.as_view().blob_inclusion_info()
+    }
+
+    fn nonsigner_stake_and_signature(&self) -> &NonSignerStakesAndSignature {
+        self.as_view().nonsigner_stake_and_signature()
+    }
+
+    fn signed_quorum_numbers(&self) -> &Bytes {
+        self.as_view().signed_quorum_numbers()
+    }
+
+    fn offchain_derivation_version(&self) -> Option<u16> {
+        self.as_view().offchain_derivation_version()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{versioned_certs, ALTDA_COMMITMENT_V3};
+    use crate::AltDACommitment;
+    use alloc::vec;
+
+    #[test]
+    fn test_cert_view() {
+        let calldata = alloy_primitives::hex::decode(ALTDA_COMMITMENT_V3).unwrap();
+        let altda_commitment: AltDACommitment = calldata[..].try_into().unwrap();
+        let EigenDAVersionedCert::V3(cert) = altda_commitment.versioned_cert else {
+            panic!("expect V3 cert");
+        };
+        let [v2, v3, v4] = versioned_certs(&EigenDAVersionedCert::V3(cert.clone()), 7);
+
+        struct Case {
+            cert: EigenDAVersionedCert,
+            offchain_derivation_version: Option<u16>,
+        }
+
+        let cases = vec![
+            Case {
+                cert: v2,
+                offchain_derivation_version: None,
+            },
+            Case {
+                cert: v3,
+                offchain_derivation_version: None,
+            },
+            Case {
+                cert: v4,
+                offchain_derivation_version: Some(7),
+            },
+        ];
+
+        let blob_header = &cert.blob_inclusion_info.blob_certificate.blob_header;
+        for case in cases {
+            let view = case.cert.as_view();
+            assert_eq!(view.batch_header(), &cert.batch_header_v2);
+            assert_eq!(view.blob_inclusion_info(), &cert.blob_inclusion_info);
+            assert_eq!(
+                view.nonsigner_stake_and_signature(),
+                &cert.nonsigner_stake_and_signature
+            );
+            assert_eq!(view.signed_quorum_numbers(), &cert.signed_quorum_numbers);
+            assert_eq!(
+                view.offchain_derivation_version(),
+                case.offchain_derivation_version
+            );
+            assert_eq!(
+                view.reference_block_number(),
+                cert.batch_header_v2.reference_block_number as u64
+            );
+            assert_eq!(view.blob_version(), blob_header.version);
+            assert_eq!(view.quorum_numbers(), &blob_header.quorum_numbers);
+            assert_eq!(view.payment_header_hash(), &blob_header.payment_header_hash);
+            assert_eq!(
+                view.relay_keys(),
+                &cert.blob_inclusion_info.blob_certificate.relay_keys[..]
+            );
+            assert_eq!(view.kzg_commitment(), blob_header.commitment.commitment);
+            assert_eq!(
+                view.num_field_element(),
+                blob_header.commitment.length as u64
+            );
+            // the versioned cert delegates to the cert of its version
+            assert_eq!(
+                case.cert.offchain_derivation_version(),
+                case.offchain_derivation_version
+            );
+            assert_eq!(case.cert.blob_header(), blob_header);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Inclusion of a blob certificate in the batch signed by the EigenDA operators. Port of
+//! `EigenDACertVerificationLib.checkBlobInclusion`, the blob certificate is hashed as in
+//! `EigenDAHasher`, and its hash is a leaf of the keccak Merkle tree whose root is the
+//! batch_root of the batch header.
+
+use crate::{BatchHeaderV2, BlobCertificate, BlobHeaderV2, BlobInclusionInfo};
+use alloy_primitives::{keccak256, FixedBytes, B256};
+use alloy_sol_types::SolValue;
+
+/// Errors of [BlobInclusionInfo::verify_inclusion]
+#[derive(Debug, thiserror::Error, Clone, Copy, PartialEq, Eq)]
+pub enum BlobInclusionError {
+    /// the inclusion proof must be a sequence of 32 bytes siblings
+    #[error("inclusion proof length {0} is not a multiple of 32")]
+    InvalidProofLength(usize),
+    /// the Merkle path does not lead to the batch root
+    #[error("computed root {computed} does not match batch root {batch_root}")]
+    RootMismatch { computed: B256, batch_root: B256 },
+}
+
+impl BlobHeaderV2 {
+    /// keccak256(abi.encode(keccak256(abi.encode(version, quorumNumbers, commitment)),
+    /// paymentHeaderHash)), as `EigenDAHasher.hashBlobHeaderV2`
+    pub fn hash(&self) -> B256 {
+        let header_hash = keccak256(
+            (
+                self.version,
+                self.quorum_numbers.clone(),
+                self.commitment.to_sol(),
+            )
+                .abi_encode_params(),
+        );
+        keccak256((header_hash, FixedBytes(self.payment_header_hash)).abi_encode_params())
+    }
+}
+
+impl BlobCertificate {
+    /// keccak256(abi.encode(hashBlobHeaderV2(blobHeader), signature, relayKeys)), as
+    /// `EigenDAHasher.hashBlobCertificate`
+    pub fn hash(&self) -> B256 {
+        keccak256(
+            (
+                self.blob_header.hash(),
+                self.signature.clone(),
+                self.relay_keys.clone(),
+            )
+                .abi_encode_params(),
+        )
+    }
+}
+
+impl BlobInclusionInfo {
+    /// Walk the Merkle path of the inclusion proof from the leaf of the blob certificate, and
+    /// check it ends at the batch_root of the batch header. A batch of a single blob has an empty
+    /// proof, its root is the leaf itself. At every level, the blob_index tells
+    /// if the running hash is the left (even) or the right (odd) child, as in
+    /// `Merkle.verifyInclusionKeccak` of eigenlayer-contracts.
+    pub fn verify_inclusion(&self, batch_header: &BatchHeaderV2) -> Result<(), BlobInclusionError> {
+        let proof = &self.inclusion_proof;
+        if !proof.len().is_multiple_of(32) {
+            return Err(BlobInclusionError::InvalidProofLength(proof.len()));
+        }
+
+        // the leaf is keccak256(abi.encodePacked(blobCertHash))
+        let mut computed = keccak256(self.blob_certificate.hash());
+        let mut index = self.blob_index;
+        for sibling in proof.chunks_exact(32) {
+            computed = if index.is_multiple_of(2) {
+                keccak256([computed.as_slice(), sibling].concat())
+            } else {
+                keccak256([sibling, computed.as_slice()].concat())
+            };
+            index /= 2;
+        }
+
+        let batch_root = B256::from(batch_header.batch_root);
+        if computed != batch_root {
+            return Err(BlobInclusionError::RootMismatch {
+                computed,
+                batch_root,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::ALTDA_COMMITMENT_V3;
+    use crate::{AltDACommitment, CertView};
+    use alloc::vec;
+    use alloy_primitives::Bytes;
+
+    // certs of sepolia transactions, see the tests of hokulea-eigenda
+    const CALLDATA_1: &str = ALTDA_COMMITMENT_V3;
+    const CALLDATA_2: &str = "0x010002f90360e5a079b1d7d15095ff732f8443d96b6d293494b003e9b4377ac1c51375908afb2e9e83916347f901cef901c9f9018180820001f90159f842a008d79f9a46a4183f1d64c45fdedc36be33efc4cd27ffc9f85ac02987c2a5dddca004df46272276ced58d65a56b9a6dfa24102f066d3f0bba07e35992ede22aa4bcf888f842a0018fe73cb24305b7df1fc808f65c15d0f7921758cef87d9f38d31db4236eb9f4a01125fd3aa13adb27759b01c954dd641ad2174374f8e4cb3333970eda9fa011ecf842a00a0dd6223eed09f283a20429a7e03449c7d38b4fe674913c67e0f02d16949a41a00f52bab320eeb037ad223e361512350e35e373c79cf7f2ba1ec2aa036b49adbef888f842a02ecf0e30ff9ce9696c6c1ce5c341bfdfb56f589d3da7925fe54ee43a6667a09aa023a35883de2716167810bfca0c14af9530321908c4b78b572e9308161bb2d402f842a01fd1844a0e0f37f3c9edc39d0e14c8a4214afa7b017843dba1f467c8765df23fa004a01126fe1d6b57d7b985c61a3cb9a57f6c2e86d4dd0da956ad613bef0bcda708a000feeb6796b97a3f0179ef436770791068b9fdc15866f53af08f6d649083952eb841af27b4b47fd17adb76d6bb38831a19df88ee027ceaf22c88d846daff41ce2c7e5a814c8d0aa8034d1626e00d1c711087f412d4ccb35cacb0d490fbaa582493cb01c1808080f90163c0c0f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a027b90b5da16ef02417ad5820223e680d2c2d19a3f1d30566cfbb7b9aa30abf6da022432d9b57d271b8dd84bfb4ccd9df36b84e422cb471b35d50d55ae83a03f16ef842a0018ed79d6c0707cc6f4ec81bcea6c4cc0096f0e3635961caf3271c3c9a36a9dfa0179360dc4646a7c49bf730e1789c00622facd7836faa3c747be0f2d824cb1412f842a029273db955f9532f7b1ffe0eead7b85ac277534c892f73f0d9cf4403be36b5c6a022895e02ab90d618987ee5bf2818c93b9c5fac931d2af2b42a2d207c9d3d4966c20705c20805c2c0c0820001";
+    const CALLDATA_3: &str = "0x010002f903eee5a098eb692e5d190ff4458583187f335be454e47df2912152fe1200c9c3505ac4208391631bf901cef901c9f9018180820001f90159f842a00b24de07075954f38be4b14a6321d98cbbc07fc19737263774c763fb900cc7e4a01a76894605331379abaf50fdff37828c6cbe638ebe5376eaa4c250e053328fa2f888f842a009f7e88880c8e646cd234a162fc07dd5dd298092a714e9b5c8f2f473ca1afa56a0176c98504ed52e2dbc8fec1d1cd2c19f1f7d4c4a0d5584e479ce1034a571305df842a018135e9b7e4a1821ad2607a3cdc801b2a4cb1c9d641767428e8bc96f4a9c77bea00eba497b2195b825b817876f24c08d11ae30f6712c9d19255ec8d2a10b970c68f888f842a008e8f0db2324bdfef9af18c94a7aab5d68e5a485728281a5276b6519b5e99e83a00d1d6a357bb7baa967608929c192da0e15086b44e8557c672bd0f82763d0a43af842a002c20947d2e8628096d5ac30f0a48200f43960789064fe2f5c7c0b0e0c867a64a022b1240ea86dec625bb4a6db9c31769b7d7a894c4d2db565d5f215afaa8de11008a05f5f8a015a8ea873f35b68b5c829b2d8cb966785e50fb77b89da4dc177008f68b8419dadf3f532d1ed8a986d5091476945242710d9ee38ed6aa91472ad8c37170d8820fb9b0d59b3ee08b1a5a9ed8c3c4728b5a9dbf74104cec747e7ff43ae42be8600c1808080f901f1c28001f887f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a02b1528a6792412f62e605d184a86c5831f5eb62fe8b8a55ab734379af46ecd10a01c99445cf70539613357bf7770d2e9780abf080531bfdc8cc1e74171f7c43eb5f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a021a96430d1ee4b86b3dd912911a5a0128793f5d17242b49af0963126281656b2a02c138443b35d1038b341db4d3e3883efa7b335c91768c2796b852d3e747a2f3ff842a02517000c28dda7a87164dccc0cd1829bdd6014f5a020297f3cbed3993ce2107ca022591d582daa491dba1642862e37218d71d3492fa9ba22fec5347f22d72ae389f842a0102d793353afc14a8c4faf4df2d013db0c8c03d0f00028a5f142182b8b13359ca029bb1669d1a25dbbd48b5de886200d3e8e01d40c1405c51f79d952f9cb540833c20705c20805c6c28001c28080820001";
+
+    fn parse(calldata: &str) -> AltDACommitment {
+        let calldata = alloy_primitives::hex::decode(calldata).unwrap();
+        calldata[..].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_verify_inclusion() {
+        struct Case {
+            name: &'static str,
+            calldata: &'static str,
+            mutate: fn(&mut BlobInclusionInfo, &mut BatchHeaderV2),
+            expected: Result<(), BlobInclusionError>,
+        }
+
+        let cases = [
+            Case {
+                name: "sepolia cert 1",
+                calldata: CALLDATA_1,
+                mutate: |_, _| {},
+                expected: Ok(()),
+            },
+            Case {
+                name: "sepolia cert 2",
+                calldata: CALLDATA_2,
+                mutate: |_, _| {},
+                expected: Ok(()),
+            },
+            Case {
+                name: "sepolia cert 3",
+                calldata: CALLDATA_3,
+                mutate: |_, _| {},
+                expected: Ok(()),
+            },
+            Case {
+                name: "truncated proof",
+                calldata: CALLDATA_1,
+                mutate: |info, _| info.inclusion_proof = Bytes::from(vec![0u8; 31]),
+                expected: Err(BlobInclusionError::InvalidProofLength(31)),
+            },
+        ];
+
+        for case in cases {
+            let altda_commitment = parse(case.calldata);
+            let mut info = altda_commitment
+                .versioned_cert
+                .blob_inclusion_info()
+                .clone();
+            let mut batch_header = altda_commitment.versioned_cert.batch_header().clone();
+            (case.mutate)(&mut info, &mut batch_header);
+            assert_eq!(
+                info.verify_inclusion(&batch_header),
+                case.expected,
+                "case {}",
+                case.name
+            );
+        }
+    }
+
+    /// The sepolia certs are in batches of a single blob, whose root is the leaf. Place the leaf
+    /// at index 2 of a tree of 4 leaves instead.
+    fn two_level_tree() -> (BlobInclusionInfo, BatchHeaderV2) {
+        let altda_commitment = parse(CALLDATA_1);
+        let mut info = altda_commitment
+            .versioned_cert
+            .blob_inclusion_info()
+            .clone();
+        let mut batch_header = altda_commitment.versioned_cert.batch_header().clone();
+        let leaf = keccak256(info.blob_certificate.hash());
+        assert_eq!(leaf, B256::from(batch_header.batch_root));
+
+        let siblings = [B256::repeat_byte(1), B256::repeat_byte(2)];
+        let parent = keccak256([leaf, siblings[0]].concat());
+        let root = keccak256([siblings[1], parent].concat());
+        info.blob_index = 2;
+        info.inclusion_proof = siblings.concat().into();
+        batch_header.batch_root = root.0;
+        (info, batch_header)
+    }
+
+    #[test]
+    fn test_verify_inclusion_merkle_path() {
+        struct Case {
+            name: &'static str,
+            mutate: fn(&mut BlobInclusionInfo, &mut BatchHeaderV2),
+            expected_ok: bool,
+        }
+
+        let cases = [
+            Case {
+                name: "valid",
+                mutate: |_, _| {},
+                expected_ok: true,
+            },
+            Case {
+                name: "other blob index",
+                mutate: |info, _| info.blob_index = 3,
+                expected_ok: false,
+            },
+            Case {
+                name: "swapped siblings",
+                mutate: |info, _| {
+                    let proof = info.inclusion_proof.clone();
+                    info.inclusion_proof = [&proof[32..], &proof[..32]].concat().into();
+                },
+                expected_ok: false,
+            },
+            Case {
+                name: "other batch root",
+                mutate: |_, header| header.batch_root[0] ^= 1,
+                expected_ok: false,
+            },
+            Case {
+                name: "other relay keys",
+                mutate: |info, _| info.blob_certificate.relay_keys.push(1),
+                expected_ok: false,
+            },
+            Case {
+                name: "other signature",
+                mutate: |info, _| info.blob_certificate.signature = Bytes::from(vec![1u8; 65]),
+                expected_ok: false,
+            },
+            Case {
+                name: "other payment header hash",
+                mutate: |info, _| info.blob_certificate.blob_header.payment_header_hash[31] ^= 1,
+                expected_ok: false,
+            },
+            Case {
+                name: "other blob length",
+                mutate: |info, _| info.blob_certificate.blob_header.commitment.length *= 2,
+                expected_ok: false,
+            },
+        ];
+
+        for case in cases {
+            let (mut info, mut batch_header) = two_level_tree();
+            (case.mutate)(&mut info, &mut batch_header);
+            let result = info.verify_inclusion(&batch_header);
+            if case.expected_ok {
+                assert_eq!(result, Ok(()), "case {}", case.name);
+            } else {
+                assert!(
+                    matches!(result, Err(BlobInclusionError::RootMismatch { .. })),
+                    "case {}",
+                    case.name
+                );
+            }
+        }
+    }
+}
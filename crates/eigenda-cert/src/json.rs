@@ -0,0 +1,556 @@
+//! Canonical JSON representation of an [AltDACommitment], for explorers and incident tooling
+//! storing and diffing certs. The binary form of a commitment is [AltDACommitment::to_rlp_bytes],
+//! the JSON form is a readable view of the same data:
+//!
+//! - fields are named after the rust structs, in declaration order
+//! - byte strings, hashes and curve coordinates are 0x-prefixed lowercase hex. Coordinates are
+//!   always 32 bytes wide, such that the same point is always written the same way
+//! - block numbers, indices, lengths and versions are decimal numbers
+//! - the cert version is named as in [AltDACommitment::cert_version_str], and only V4 certs carry
+//!   an offchain_derivation_version
+//!
+//! The output is pretty-printed with 2-space indentation. Parsing accepts any whitespace, but
+//! rejects unknown fields, and commitments that [AltDACommitment::try_from] would reject, such
+//! that `from_canonical_json(to_canonical_json(c)) == c` and both sides have the same rlp bytes.
+//!
+//! Golden files of the canonical form are under `testdata/`.
+
+use crate::{
+    AltDACommitment, AltDACommitmentParseError, BatchHeaderV2, BlobCertificate, BlobCommitment,
+    BlobHeaderV2, BlobInclusionInfo, EigenDACertV2, EigenDACertV3, EigenDACertV4,
+    EigenDAVersionedCert, Fp2, G1Point, G2Point, NonSignerStakesAndSignature,
+    EIGENDA_DA_LAYER_BYTE, GENERIC_COMMITMENT_TYPE,
+};
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloy_primitives::{Bytes, B256, U256};
+use serde::{Deserialize, Serialize};
+
+/// Errors of [AltDACommitment::from_canonical_json]
+#[derive(Debug, thiserror::Error)]
+pub enum CanonicalJsonError {
+    /// not a json document of the canonical form
+    #[error("invalid canonical json: {0}")]
+    Json(#[from] serde_json::Error),
+    /// cert version other than V2, V3 or V4
+    #[error("unsupported cert version {0}")]
+    UnsupportedCertVersion(String),
+    /// offchain_derivation_version is present on a cert other than V4, or missing on a V4 cert
+    #[error("offchain_derivation_version is only and always carried by V4 certs, got {0} cert")]
+    OffchainDerivationVersion(String),
+    /// the commitment is not one of an EigenDA cert
+    #[error("not an eigenda altda commitment: {0}")]
+    Commitment(AltDACommitmentParseError),
+}
+
+impl AltDACommitment {
+    /// Serialize into the canonical JSON form, see the [module documentation](crate::json)
+    pub fn to_canonical_json(&self) -> String {
+        serde_json::to_string_pretty(&AltDACommitmentJson::from(self))
+            .expect("canonical json contains only strings, numbers and arrays")
+    }
+
+    /// Parse the canonical JSON form, see the [module documentation](crate::json)
+    pub fn from_canonical_json(json: &str) -> Result<Self, CanonicalJsonError> {
+        serde_json::from_str::<AltDACommitmentJson>(json)?.try_into()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AltDACommitmentJson {
+    commitment_type: u8,
+    da_layer_byte: u8,
+    cert_version: String,
+    cert: CertJson,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CertJson {
+    batch_header_v2: BatchHeaderJson,
+    blob_inclusion_info: BlobInclusionInfoJson,
+    nonsigner_stake_and_signature: NonSignerStakesAndSignatureJson,
+    signed_quorum_numbers: Bytes,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    offchain_derivation_version: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchHeaderJson {
+    batch_root: B256,
+    reference_block_number: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlobInclusionInfoJson {
+    blob_certificate: BlobCertificateJson,
+    blob_index: u32,
+    inclusion_proof: Bytes,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlobCertificateJson {
+    blob_header: BlobHeaderJson,
+    signature: Bytes,
+    relay_keys: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlobHeaderJson {
+    version: u16,
+    quorum_numbers: Bytes,
+    commitment: BlobCommitmentJson,
+    payment_header_hash: B256,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlobCommitmentJson {
+    commitment: G1PointJson,
+    length_commitment: G2PointJson,
+    length_proof: G2PointJson,
+    length: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NonSignerStakesAndSignatureJson {
+    non_signer_quorum_bitmap_indices: Vec<u32>,
+    non_signer_pubkeys: Vec<G1PointJson>,
+    quorum_apks: Vec<G1PointJson>,
+    apk_g2: G2PointJson,
+    sigma: G1PointJson,
+    quorum_apk_indices: Vec<u32>,
+    total_stake_indices: Vec<u32>,
+    non_signer_stake_indices: Vec<Vec<u32>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct G1PointJson {
+    x: B256,
+    y: B256,
+}
+
+/// Fp2 elements are written in the order of [Fp2], i.e. of the solidity `uint256[2]`
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct G2PointJson {
+    x: [B256; 2],
+    y: [B256; 2],
+}
+
+impl From<&AltDACommitment> for AltDACommitmentJson {
+    fn from(c: &AltDACommitment) -> Self {
+        let (
+            batch_header_v2,
+            blob_inclusion_info,
+            nonsigner_stake_and_signature,
+            signed_quorum_numbers,
+            offchain_derivation_version,
+        ) = match &c.versioned_cert {
+            EigenDAVersionedCert::V2(c) => (
+                &c.batch_header_v2,
+                &c.blob_inclusion_info,
+                &c.nonsigner_stake_and_signature,
+                &c.signed_quorum_numbers,
+                None,
+            ),
+            EigenDAVersionedCert::V3(c) => (
+                &c.batch_header_v2,
+                &c.blob_inclusion_info,
+                &c.nonsigner_stake_and_signature,
+                &c.signed_quorum_numbers,
+                None,
+            ),
+            EigenDAVersionedCert::V4(c) => (
+                &c.batch_header_v2,
+                &c.blob_inclusion_info,
+                &c.nonsigner_stake_and_signature,
+                &c.signed_quorum_numbers,
+                Some(c.offchain_derivation_version),
+            ),
+        };
+        Self {
+            commitment_type: c.commitment_type,
+            da_layer_byte: c.da_layer_byte,
+            cert_version: c.cert_version_str().into(),
+            cert: CertJson {
+                batch_header_v2: batch_header_v2.into(),
+                blob_inclusion_info: blob_inclusion_info.into(),
+                nonsigner_stake_and_signature: nonsigner_stake_and_signature.into(),
+                signed_quorum_numbers: signed_quorum_numbers.clone(),
+                offchain_derivation_version,
+            },
+        }
+    }
+}
+
+impl TryFrom<AltDACommitmentJson> for AltDACommitment {
+    type Error = CanonicalJsonError;
+
+    fn try_from(c: AltDACommitmentJson) -> Result<Self, Self::Error> {
+        // same checks as parsing the binary form
+        if c.commitment_type != GENERIC_COMMITMENT_TYPE {
+            return Err(CanonicalJsonError::Commitment(
+                AltDACommitmentParseError::UnsupportedCommitmentType,
+            ));
+        }
+        if c.da_layer_byte != EIGENDA_DA_LAYER_BYTE {
+            return Err(CanonicalJsonError::Commitment(
+                AltDACommitmentParseError::UnsupportedDaLayerType,
+            ));
+        }
+
+        let cert = c.cert;
+        let batch_header_v2 = cert.batch_header_v2.into();
+        let blob_inclusion_info = cert.blob_inclusion_info.into();
+        let nonsigner_stake_and_signature = cert.nonsigner_stake_and_signature.into();
+        let signed_quorum_numbers = cert.signed_quorum_numbers;
+        let versioned_cert = match (c.cert_version.as_str(), cert.offchain_derivation_version) {
+            ("V2", None) => EigenDAVersionedCert::V2(EigenDACertV2 {
+                batch_header_v2,
+                blob_inclusion_info,
+                nonsigner_stake_and_signature,
+                signed_quorum_numbers,
+            }),
+            ("V3", None) => EigenDAVersionedCert::V3(EigenDACertV3 {
+                batch_header_v2,
+                blob_inclusion_info,
+                nonsigner_stake_and_signature,
+                signed_quorum_numbers,
+            }),
+            ("V4", Some(offchain_derivation_version)) => EigenDAVersionedCert::V4(EigenDACertV4 {
+                batch_header_v2,
+                blob_inclusion_info,
+                nonsigner_stake_and_signature,
+                signed_quorum_numbers,
+                offchain_derivation_version,
+            }),
+            ("V2" | "V3" | "V4", _) => {
+                return Err(CanonicalJsonError::OffchainDerivationVersion(
+                    c.cert_version,
+                ))
+            }
+            _ => return Err(CanonicalJsonError::UnsupportedCertVersion(c.cert_version)),
+        };
+
+        Ok(AltDACommitment {
+            commitment_type: c.commitment_type,
+            da_layer_byte: c.da_layer_byte,
+            versioned_cert,
+        })
+    }
+}
+
+impl From<&BatchHeaderV2> for BatchHeaderJson {
+    fn from(h: &BatchHeaderV2) -> Self {
+        Self {
+            batch_root: B256::from(h.batch_root),
+            reference_block_number: h.reference_block_number,
+        }
+    }
+}
+
+impl From<BatchHeaderJson> for BatchHeaderV2 {
+    fn from(h: BatchHeaderJson) -> Self {
+        Self {
+            batch_root: h.batch_root.0,
+            reference_block_number: h.reference_block_number,
+        }
+    }
+}
+
+impl From<&BlobInclusionInfo> for BlobInclusionInfoJson {
+    fn from(i: &BlobInclusionInfo) -> Self {
+        Self {
+            blob_certificate: (&i.blob_certificate).into(),
+            blob_index: i.blob_index,
+            inclusion_proof: i.inclusion_proof.clone(),
+        }
+    }
+}
+
+impl From<BlobInclusionInfoJson> for BlobInclusionInfo {
+    fn from(i: BlobInclusionInfoJson) -> Self {
+        Self {
+            blob_certificate: i.blob_certificate.into(),
+            blob_index: i.blob_index,
+            inclusion_proof: i.inclusion_proof,
+        }
+    }
+}
+
+impl From<&BlobCertificate> for BlobCertificateJson {
+    fn from(c: &BlobCertificate) -> Self {
+        Self {
+            blob_header: (&c.blob_header).into(),
+            signature: c.signature.clone(),
+            relay_keys: c.relay_keys.clone(),
+        }
+    }
+}
+
+impl From<BlobCertificateJson> for BlobCertificate {
+    fn from(c: BlobCertificateJson) -> Self {
+        Self {
+            blob_header: c.blob_header.into(),
+            signature: c.signature,
+            relay_keys: c.relay_keys,
+        }
+    }
+}
+
+impl From<&BlobHeaderV2> for BlobHeaderJson {
+    fn from(h: &BlobHeaderV2) -> Self {
+        Self {
+            version: h.version,
+            quorum_numbers: h.quorum_numbers.clone(),
+            commitment: (&h.commitment).into(),
+            payment_header_hash: B256::from(h.payment_header_hash),
+        }
+    }
+}
+
+impl From<BlobHeaderJson> for BlobHeaderV2 {
+    fn from(h: BlobHeaderJson) -> Self {
+        Self {
+            version: h.version,
+            quorum_numbers: h.quorum_numbers,
+            commitment: h.commitment.into(),
+            payment_header_hash: h.payment_header_hash.0,
+        }
+    }
+}
+
+impl From<&BlobCommitment> for BlobCommitmentJson {
+    fn from(c: &BlobCommitment) -> Self {
+        Self {
+            commitment: (&c.commitment).into(),
+            length_commitment: (&c.length_commitment).into(),
+            length_proof: (&c.length_proof).into(),
+            length: c.length,
+        }
+    }
+}
+
+impl From<BlobCommitmentJson> for BlobCommitment {
+    fn from(c: BlobCommitmentJson) -> Self {
+        Self {
+            commitment: c.commitment.into(),
+            length_commitment: c.length_commitment.into(),
+            length_proof: c.length_proof.into(),
+            length: c.length,
+        }
+    }
+}
+
+impl From<&NonSignerStakesAndSignature> for NonSignerStakesAndSignatureJson {
+    fn from(n: &NonSignerStakesAndSignature) -> Self {
+        Self {
+            non_signer_quorum_bitmap_indices: n.non_signer_quorum_bitmap_indices.clone(),
+            non_signer_pubkeys: n.non_signer_pubkeys.iter().map(Into::into).collect(),
+            quorum_apks: n.quorum_apks.iter().map(Into::into).collect(),
+            apk_g2: (&n.apk_g2).into(),
+            sigma: (&n.sigma).into(),
+            quorum_apk_indices: n.quorum_apk_indices.clone(),
+            total_stake_indices: n.total_stake_indices.clone(),
+            non_signer_stake_indices: n.non_signer_stake_indices.clone(),
+        }
+    }
+}
+
+impl From<NonSignerStakesAndSignatureJson> for NonSignerStakesAndSignature {
+    fn from(n: NonSignerStakesAndSignatureJson) -> Self {
+        Self {
+            non_signer_quorum_bitmap_indices: n.non_signer_quorum_bitmap_indices,
+            non_signer_pubkeys: n.non_signer_pubkeys.into_iter().map(Into::into).collect(),
+            quorum_apks: n.quorum_apks.into_iter().map(Into::into).collect(),
+            apk_g2: n.apk_g2.into(),
+            sigma: n.sigma.into(),
+            quorum_apk_indices: n.quorum_apk_indices,
+            total_stake_indices: n.total_stake_indices,
+            non_signer_stake_indices: n.non_signer_stake_indices,
+        }
+    }
+}
+
+impl From<&G1Point> for G1PointJson {
+    fn from(p: &G1Point) -> Self {
+        Self {
+            x: to_b256(p.x),
+            y: to_b256(p.y),
+        }
+    }
+}
+
+impl From<G1PointJson> for G1Point {
+    fn from(p: G1PointJson) -> Self {
+        Self {
+            x: to_u256(p.x),
+            y: to_u256(p.y),
+        }
+    }
+}
+
+impl From<&G2Point> for G2PointJson {
+    fn from(p: &G2Point) -> Self {
+        Self {
+            x: [to_b256(p.x.0), to_b256(p.x.1)],
+            y: [to_b256(p.y.0), to_b256(p.y.1)],
+        }
+    }
+}
+
+impl From<G2PointJson> for G2Point {
+    fn from(p: G2PointJson) -> Self {
+        Self {
+            x: Fp2(to_u256(p.x[0]), to_u256(p.x[1])),
+            y: Fp2(to_u256(p.y[0]), to_u256(p.y[1])),
+        }
+    }
+}
+
+fn to_b256(value: U256) -> B256 {
+    B256::from(value.to_be_bytes::<32>())
+}
+
+fn to_u256(value: B256) -> U256 {
+    U256::from_be_bytes(value.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{versioned_certs, ALTDA_COMMITMENT_V3};
+    use alloc::string::ToString;
+
+    const GOLDEN_V3: &str = include_str!("../testdata/altda_commitment_v3.json");
+    const GOLDEN_V4: &str = include_str!("../testdata/altda_commitment_v4.json");
+
+    fn altda_commitment_v3() -> AltDACommitment {
+        let calldata = alloy_primitives::hex::decode(ALTDA_COMMITMENT_V3).unwrap();
+        calldata[..].try_into().unwrap()
+    }
+
+    fn altda_commitment_v4() -> AltDACommitment {
+        let altda_commitment = altda_commitment_v3();
+        let [_, _, v4] = versioned_certs(&altda_commitment.versioned_cert, 0);
+        AltDACommitment {
+            versioned_cert: v4,
+            ..altda_commitment
+        }
+    }
+
+    #[test]
+    fn test_canonical_json_golden() {
+        struct Case {
+            name: &'static str,
+            altda_commitment: AltDACommitment,
+            golden: &'static str,
+        }
+
+        let cases = [
+            Case {
+                name: "V3",
+                altda_commitment: altda_commitment_v3(),
+                golden: GOLDEN_V3,
+            },
+            Case {
+                name: "V4",
+                altda_commitment: altda_commitment_v4(),
+                golden: GOLDEN_V4,
+            },
+        ];
+
+        for case in cases {
+            let json = case.altda_commitment.to_canonical_json();
+            assert_eq!(json, case.golden.trim_end(), "case {}", case.name);
+
+            let parsed = AltDACommitment::from_canonical_json(case.golden).unwrap();
+            assert_eq!(parsed, case.altda_commitment, "case {}", case.name);
+            // round trip through the binary form
+            let rlp_bytes = parsed.to_rlp_bytes();
+            assert_eq!(
+                rlp_bytes,
+                case.altda_commitment.to_rlp_bytes(),
+                "case {}",
+                case.name
+            );
+            let reparsed: AltDACommitment = rlp_bytes[..].try_into().unwrap();
+            assert_eq!(reparsed.to_canonical_json(), json, "case {}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_from_canonical_json_invalid() {
+        struct Case {
+            name: &'static str,
+            from: &'static str,
+            to: &'static str,
+            expected: &'static str,
+        }
+
+        let cases = [
+            Case {
+                name: "keccak commitment type",
+                from: "\"commitment_type\": 1",
+                to: "\"commitment_type\": 0",
+                expected: "not an eigenda altda commitment: Unsupported commitment type",
+            },
+            Case {
+                name: "other da layer",
+                from: "\"da_layer_byte\": 0",
+                to: "\"da_layer_byte\": 1",
+                expected: "not an eigenda altda commitment: Unsupported da layer type",
+            },
+            Case {
+                name: "unknown cert version",
+                from: "\"V3\"",
+                to: "\"V5\"",
+                expected: "unsupported cert version V5",
+            },
+            Case {
+                name: "V4 without offchain derivation version",
+                from: "\"V3\"",
+                to: "\"V4\"",
+                expected: "offchain_derivation_version is only and always carried by V4 certs, got V4 cert",
+            },
+            Case {
+                name: "unknown field",
+                from: "\"blob_index\": 0",
+                to: "\"blob_index\": 0, \"blob_idx\": 0",
+                expected: "invalid canonical json: unknown field `blob_idx`",
+            },
+            Case {
+                name: "short coordinate",
+                from: "0x2f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9",
+                to: "0x2f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69",
+                expected: "invalid canonical json: ",
+            },
+        ];
+
+        for case in cases {
+            assert!(GOLDEN_V3.contains(case.from), "case {}", case.name);
+            let json = GOLDEN_V3.replacen(case.from, case.to, 1);
+            let err = AltDACommitment::from_canonical_json(&json).unwrap_err();
+            assert!(
+                err.to_string().starts_with(case.expected),
+                "case {}: {err}",
+                case.name
+            );
+        }
+
+        // an offchain_derivation_version on a cert other than V4
+        let json = GOLDEN_V4.replace("\"V4\"", "\"V2\"");
+        assert!(matches!(
+            AltDACommitment::from_canonical_json(&json),
+            Err(CanonicalJsonError::OffchainDerivationVersion(_))
+        ));
+    }
+}
@@ -9,10 +9,29 @@ extern crate alloc;
 use alloc::vec::Vec;
 
 pub mod altda_commitment;
+pub mod cert_view;
+pub mod inclusion;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "relay")]
+pub mod relay;
+#[cfg(feature = "structure")]
+pub mod structure;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 pub mod v2_cert;
 pub mod v3_cert;
 pub mod v4_cert;
-pub use altda_commitment::{AltDACommitment, AltDACommitmentParseError, EigenDAVersionedCert};
+pub use altda_commitment::{
+    AltDACommitment, AltDACommitmentParseError, EigenDAVersionedCert, GenericAltDACommitment,
+    EIGENDA_DA_LAYER_BYTE, GENERIC_COMMITMENT_TYPE, KECCAK_COMMITMENT_TYPE,
+};
+pub use cert_view::CertView;
+pub use inclusion::BlobInclusionError;
+#[cfg(feature = "json")]
+pub use json::CanonicalJsonError;
+#[cfg(feature = "structure")]
+pub use structure::CertStructureError;
 pub use v2_cert::EigenDACertV2;
 pub use v3_cert::EigenDACertV3;
 pub use v4_cert::EigenDACertV4;
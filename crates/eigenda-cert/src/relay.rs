@@ -0,0 +1,53 @@
+//! Messages of the `GetBlob` method of the EigenDA relay gRPC service, see
+//! `api/proto/relay/relay.proto` of eigenda. Shared by the host fetching blobs from the relays
+//! and the mock relay standing in for them in tests.
+
+use alloc::vec::Vec;
+
+/// Path of the `GetBlob` method of the relay gRPC service
+pub const GET_BLOB_PATH: &str = "/relay.Relay/GetBlob";
+
+/// Request of the `GetBlob` method
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetBlobRequest {
+    /// The key of the blob, the hash of its blob header
+    #[prost(bytes = "vec", tag = "1")]
+    pub blob_key: Vec<u8>,
+}
+
+/// Reply of the `GetBlob` method
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetBlobReply {
+    /// The blob in its coefficient form
+    #[prost(bytes = "vec", tag = "1")]
+    pub blob: Vec<u8>,
+}
+
+impl GetBlobReply {
+    /// Upper bound of the length of an encoded reply carrying a blob of at most `blob_len` bytes:
+    /// the key of the field, the length delimiter and the blob
+    pub fn max_encoded_len(blob_len: usize) -> usize {
+        1 + prost::length_delimiter_len(blob_len) + blob_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use prost::Message;
+
+    #[test]
+    fn test_max_encoded_len() {
+        for blob_len in [1, 127, 128, 32 * 4096, 16 * 1024 * 1024] {
+            let reply = GetBlobReply {
+                blob: vec![1u8; blob_len],
+            };
+            assert_eq!(reply.encoded_len(), GetBlobReply::max_encoded_len(blob_len));
+            let shorter = GetBlobReply {
+                blob: vec![1u8; blob_len - 1],
+            };
+            assert!(shorter.encoded_len() < GetBlobReply::max_encoded_len(blob_len));
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Offline structural validation of EigenDA certs. It catches malformed certs locally, before
+//! the cert is checked onchain by the cert verifier, see [CertView::validate_structure].
+//! Passing the structural validation does not imply the cert is valid, since the signature
+//! and the inclusion of the blob are not verified.
+
+use crate::{BlobHeaderV2, CertView, G1Point, G2Point};
+use alloy_primitives::U256;
+use substrate_bn::{AffineG1, AffineG2, Fq, Fq2};
+
+/// Structural defect of an EigenDA cert
+#[derive(Debug, thiserror::Error, Clone, Copy, PartialEq, Eq)]
+pub enum CertStructureError {
+    #[error("Quorum numbers of the blob header are not sorted and unique")]
+    UnsortedQuorumNumbers,
+    #[error("Signed quorum numbers are not sorted and unique")]
+    UnsortedSignedQuorumNumbers,
+    #[error("Signed quorum {0} is not a quorum of the blob header")]
+    SignedQuorumNotInBlobHeader(u8),
+    #[error("{field} has length {len}, expected {expected}")]
+    LengthMismatch {
+        field: &'static str,
+        len: usize,
+        expected: usize,
+    },
+    #[error("Non-signer stake indices of quorum index {index} has length {len}, more than the {non_signers} non-signers")]
+    TooManyNonSignerStakeIndices {
+        index: usize,
+        len: usize,
+        non_signers: usize,
+    },
+    #[error("{0} is not a BN254 G1 point")]
+    InvalidG1Point(&'static str),
+    #[error("{0} is not a BN254 G2 point in the prime order subgroup")]
+    InvalidG2Point(&'static str),
+    #[error("Blob length {0} is not a power of two")]
+    BlobLengthNotPowerOfTwo(u32),
+}
+
+/// Validates the structure of the cert, see [CertView::validate_structure]
+pub fn validate_structure<C: CertView + ?Sized>(cert: &C) -> Result<(), CertStructureError> {
+    let blob_header = cert.blob_header();
+    validate_quorum_numbers(blob_header, cert.signed_quorum_numbers())?;
+    validate_non_signer_lengths(cert)?;
+    validate_points(cert)?;
+
+    let length = blob_header.commitment.length;
+    if !length.is_power_of_two() {
+        return Err(CertStructureError::BlobLengthNotPowerOfTwo(length));
+    }
+    Ok(())
+}
+
+fn validate_quorum_numbers(
+    blob_header: &BlobHeaderV2,
+    signed_quorum_numbers: &[u8],
+) -> Result<(), CertStructureError> {
+    if !is_strictly_ascending(&blob_header.quorum_numbers) {
+        return Err(CertStructureError::UnsortedQuorumNumbers);
+    }
+    if !is_strictly_ascending(signed_quorum_numbers) {
+        return Err(CertStructureError::UnsortedSignedQuorumNumbers);
+    }
+    if let Some(quorum) = signed_quorum_numbers
+        .iter()
+        .find(|quorum| !blob_header.quorum_numbers.contains(*quorum))
+    {
+        return Err(CertStructureError::SignedQuorumNotInBlobHeader(*quorum));
+    }
+    Ok(())
+}
+
+/// The per quorum vectors of the non-signer data follow the signed quorums, and the per
+/// non-signer vectors follow the non-signer pubkeys
+fn validate_non_signer_lengths<C: CertView + ?Sized>(cert: &C) -> Result<(), CertStructureError> {
+    let data = cert.nonsigner_stake_and_signature();
+    let num_quorums = cert.signed_quorum_numbers().len();
+    let num_non_signers = data.non_signer_pubkeys.len();
+
+    let lengths = [
+        ("quorum_apks", data.quorum_apks.len(), num_quorums),
+        (
+            "quorum_apk_indices",
+            data.quorum_apk_indices.len(),
+            num_quorums,
+        ),
+        (
+            "total_stake_indices",
+            data.total_stake_indices.len(),
+            num_quorums,
+        ),
+        (
+            "non_signer_stake_indices",
+            data.non_signer_stake_indices.len(),
+            num_quorums,
+        ),
+        (
+            "non_signer_quorum_bitmap_indices",
+            data.non_signer_quorum_bitmap_indices.len(),
+            num_non_signers,
+        ),
+    ];
+    for (field, len, expected) in lengths {
+        if len != expected {
+            return Err(CertStructureError::LengthMismatch {
+                field,
+                len,
+                expected,
+            });
+        }
+    }
+
+    for (index, stake_indices) in data.non_signer_stake_indices.iter().enumerate() {
+        if stake_indices.len() > num_non_signers {
+            return Err(CertStructureError::TooManyNonSignerStakeIndices {
+                index,
+                len: stake_indices.len(),
+                non_signers: num_non_signers,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn validate_points<C: CertView + ?Sized>(cert: &C) -> Result<(), CertStructureError> {
+    let commitment = cert.blob_commitment();
+    let data = cert.nonsigner_stake_and_signature();
+
+    validate_g1_point(&commitment.commitment, "commitment")?;
+    validate_g2_point(&commitment.length_commitment, "length_commitment")?;
+    validate_g2_point(&commitment.length_proof, "length_proof")?;
+    for point in &data.non_signer_pubkeys {
+        validate_g1_point(point, "non_signer_pubkeys")?;
+    }
+    for point in &data.quorum_apks {
+        validate_g1_point(point, "quorum_apks")?;
+    }
+    validate_g2_point(&data.apk_g2, "apk_g2")?;
+    validate_g1_point(&data.sigma, "sigma")?;
+    Ok(())
+}
+
+fn is_strictly_ascending(numbers: &[u8]) -> bool {
+    numbers.windows(2).all(|pair| pair[0] < pair[1])
+}
+
+fn to_fq(value: U256) -> Option<Fq> {
+    Fq::from_slice(&value.to_be_bytes::<32>()).ok()
+}
+
+/// (0, 0) encodes the point at infinity, as in the EigenDA contracts. G1 has cofactor 1, any
+/// point on the curve is in the subgroup.
+fn validate_g1_point(point: &G1Point, field: &'static str) -> Result<(), CertStructureError> {
+    if point.x.is_zero() && point.y.is_zero() {
+        return Ok(());
+    }
+    let invalid = CertStructureError::InvalidG1Point(field);
+    let x = to_fq(point.x).ok_or(invalid)?;
+    let y = to_fq(point.y).ok_or(invalid)?;
+    AffineG1::new(x, y).map(|_| ()).map_err(|_| invalid)
+}
+
+/// An Fp2 element is encoded as X[0] * i + X[1], as in the EigenDA contracts. The construction
+/// of the affine point checks the subgroup membership.
+fn validate_g2_point(point: &G2Point, field: &'static str) -> Result<(), CertStructureError> {
+    let coordinates = [point.x.0, point.x.1, point.y.0, point.y.1];
+    if coordinates.iter().all(|c| c.is_zero()) {
+        return Ok(());
+    }
+    let invalid = CertStructureError::InvalidG2Point(field);
+    let x = Fq2::new(
+        to_fq(point.x.1).ok_or(invalid)?,
+        to_fq(point.x.0).ok_or(invalid)?,
+    );
+    let y = Fq2::new(
+        to_fq(point.y.1).ok_or(invalid)?,
+        to_fq(point.y.0).ok_or(invalid)?,
+    );
+    AffineG2::new(x, y).map(|_| ()).map_err(|_| invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::ALTDA_COMMITMENT_V3;
+    use crate::{AltDACommitment, EigenDACertV3, EigenDAVersionedCert, Fp2};
+    use alloc::vec;
+
+    fn valid_cert() -> EigenDACertV3 {
+        let calldata = alloy_primitives::hex::decode(ALTDA_COMMITMENT_V3).unwrap();
+        let altda_commitment: AltDACommitment = calldata[..].try_into().unwrap();
+        let EigenDAVersionedCert::V3(cert) = altda_commitment.versioned_cert else {
+            panic!("expect V3 cert");
+        };
+        cert
+    }
+
+    #[test]
+    fn test_validate_structure() {
+        struct Case {
+            mutate: fn(&mut EigenDACertV3),
+            result: Result<(), CertStructureError>,
+        }
+
+        let cases = vec![
+            Case {
+                mutate: |_| {},
+                result: Ok(()),
+            },
+            Case {
+                mutate: |c| {
+                    c.blob_inclusion_info
+                        .blob_certificate
+                        .blob_header
+                        .quorum_numbers = vec![1, 0].into()
+                },
+                result: Err(CertStructureError::UnsortedQuorumNumbers),
+            },
+            Case {
+                mutate: |c| {
+                    c.blob_inclusion_info
+                        .blob_certificate
+                        .blob_header
+                        .quorum_numbers = vec![0, 0, 1].into()
+                },
+                result: Err(CertStructureError::UnsortedQuorumNumbers),
+            },
+            Case {
+                mutate: |c| c.signed_quorum_numbers = vec![1, 0].into(),
+                result: Err(CertStructureError::UnsortedSignedQuorumNumbers),
+            },
+            Case {
+                mutate: |c| c.signed_quorum_numbers = vec![0, 2].into(),
+                result: Err(CertStructureError::SignedQuorumNotInBlobHeader(2)),
+            },
+            Case {
+                mutate: |c| {
+                    c.nonsigner_stake_and_signature.quorum_apks.pop();
+                },
+                result: Err(CertStructureError::LengthMismatch {
+                    field: "quorum_apks",
+                    len: 1,
+                    expected: 2,
+                }),
+            },
+            Case {
+                mutate: |c| {
+                    c.nonsigner_stake_and_signature
+                        .non_signer_quorum_bitmap_indices
+                        .push(0);
+                },
+                result: Err(CertStructureError::LengthMismatch {
+                    field: "non_signer_quorum_bitmap_indices",
+                    len: 5,
+                    expected: 4,
+                }),
+            },
+            Case {
+                mutate: |c| {
+                    c.nonsigner_stake_and_signature.non_signer_stake_indices[1].push(0);
+                },
+                result: Err(CertStructureError::TooManyNonSignerStakeIndices {
+                    index: 1,
+                    len: 5,
+                    non_signers: 4,
+                }),
+            },
+            Case {
+                mutate: |c| {
+                    c.nonsigner_stake_and_signature.sigma.y += U256::from(1);
+                },
+                result: Err(CertStructureError::InvalidG1Point("sigma")),
+            },
+            Case {
+                // coordinate exceeds the field modulus
+                mutate: |c| {
+                    c.nonsigner_stake_and_signature.quorum_apks[0].x = U256::MAX;
+                },
+                result: Err(CertStructureError::InvalidG1Point("quorum_apks")),
+            },
+            Case {
+                // point at infinity
+                mutate: |c| {
+                    c.nonsigner_stake_and_signature.sigma = G1Point {
+                        x: U256::ZERO,
+                        y: U256::ZERO,
+                    };
+                },
+                result: Ok(()),
+            },
+            Case {
+                // real and imaginary parts are swapped
+                mutate: |c| {
+                    let apk_g2 = &mut c.nonsigner_stake_and_signature.apk_g2;
+                    apk_g2.x = Fp2(apk_g2.x.1, apk_g2.x.0);
+                    apk_g2.y = Fp2(apk_g2.y.1, apk_g2.y.0);
+                },
+                result: Err(CertStructureError::InvalidG2Point("apk_g2")),
+            },
+            Case {
+                mutate: |c| {
+                    c.blob_inclusion_info
+                        .blob_certificate
+                        .blob_header
+                        .commitment
+                        .length = 6
+                },
+                result: Err(CertStructureError::BlobLengthNotPowerOfTwo(6)),
+            },
+            Case {
+                mutate: |c| {
+                    c.blob_inclusion_info
+                        .blob_certificate
+                        .blob_header
+                        .commitment
+                        .length = 0
+                },
+                result: Err(CertStructureError::BlobLengthNotPowerOfTwo(0)),
+            },
+        ];
+
+        for case in cases {
+            let mut cert = valid_cert();
+            (case.mutate)(&mut cert);
+            assert_eq!(cert.validate_structure(), case.result);
+            assert_eq!(
+                EigenDAVersionedCert::V3(cert).validate_structure(),
+                case.result
+            );
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Helpers shared by the tests of the crates handling EigenDA certs
+
+use crate::{CertView, EigenDACertV2, EigenDACertV3, EigenDACertV4, EigenDAVersionedCert};
+
+/// Hex encoded altda commitment of a V3 cert attested on Sepolia, as posted by the batcher. It is
+/// the commitment of `testdata/altda_commitment_v3.json`.
+pub const ALTDA_COMMITMENT_V3: &str = "0x010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001";
+
+/// Returns the V2, V3 and V4 certs carrying the same content as `cert`, whatever its version.
+/// The V4 cert carries `offchain_derivation_version`.
+pub fn versioned_certs(
+    cert: &EigenDAVersionedCert,
+    offchain_derivation_version: u16,
+) -> [EigenDAVersionedCert; 3] {
+    let v2 = EigenDACertV2 {
+        batch_header_v2: cert.batch_header().clone(),
+        blob_inclusion_info: cert.blob_inclusion_info().clone(),
+        nonsigner_stake_and_signature: cert.nonsigner_stake_and_signature().clone(),
+        signed_quorum_numbers: cert.signed_quorum_numbers().clone(),
+    };
+    let v3 = EigenDACertV3 {
+        batch_header_v2: v2.batch_header_v2.clone(),
+        blob_inclusion_info: v2.blob_inclusion_info.clone(),
+        nonsigner_stake_and_signature: v2.nonsigner_stake_and_signature.clone(),
+        signed_quorum_numbers: v2.signed_quorum_numbers.clone(),
+    };
+    let v4 = EigenDACertV4 {
+        batch_header_v2: v2.batch_header_v2.clone(),
+        blob_inclusion_info: v2.blob_inclusion_info.clone(),
+        nonsigner_stake_and_signature: v2.nonsigner_stake_and_signature.clone(),
+        signed_quorum_numbers: v2.signed_quorum_numbers.clone(),
+        offchain_derivation_version,
+    };
+    [
+        EigenDAVersionedCert::V2(v2),
+        EigenDAVersionedCert::V3(v3),
+        EigenDAVersionedCert::V4(v4),
+    ]
+}
@@ -0,0 +1,129 @@
+//! Compares the CPU cycles spent by [EncodedPayload::decode] against the streaming
+//! [EncodedPayload::decode_into] on large encoded payloads. Run with
+//! `cargo bench -p hokulea-eigenda --bench decode`.
+//!
+//! Cycles are read from the time stamp counter on x86_64, other targets fall back to wall-clock
+//! time. These are the cycles of the host CPU, not the ones of a zkVM guest.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hokulea_eigenda::{EncodedPayload, PAYLOAD_ENCODING_VERSION_0};
+use std::hint::black_box;
+
+#[cfg(target_arch = "x86_64")]
+mod cycles {
+    use criterion::measurement::{Measurement, ValueFormatter};
+    use criterion::Throughput;
+
+    /// Measures the number of elapsed CPU cycles, read from the time stamp counter
+    pub struct Cycles;
+
+    impl Measurement for Cycles {
+        type Intermediate = u64;
+        type Value = u64;
+
+        fn start(&self) -> Self::Intermediate {
+            // SAFETY: rdtsc is available on every x86_64 CPU
+            unsafe { core::arch::x86_64::_rdtsc() }
+        }
+
+        fn end(&self, start: Self::Intermediate) -> Self::Value {
+            // SAFETY: rdtsc is available on every x86_64 CPU
+            unsafe { core::arch::x86_64::_rdtsc() }.saturating_sub(start)
+        }
+
+        fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+            v1 + v2
+        }
+
+        fn zero(&self) -> Self::Value {
+            0
+        }
+
+        fn to_f64(&self, value: &Self::Value) -> f64 {
+            *value as f64
+        }
+
+        fn formatter(&self) -> &dyn ValueFormatter {
+            &CyclesFormatter
+        }
+    }
+
+    struct CyclesFormatter;
+
+    impl ValueFormatter for CyclesFormatter {
+        fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+            "cycles"
+        }
+
+        fn scale_throughputs(
+            &self,
+            _typical_value: f64,
+            throughput: &Throughput,
+            values: &mut [f64],
+        ) -> &'static str {
+            let (count, unit) = match throughput {
+                Throughput::Bytes(n) | Throughput::BytesDecimal(n) => (*n, "cycles/byte"),
+                Throughput::Elements(n) => (*n, "cycles/element"),
+            };
+            for value in values {
+                *value /= count as f64;
+            }
+            unit
+        }
+
+        fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+            "cycles"
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+type BenchMeasurement = cycles::Cycles;
+#[cfg(not(target_arch = "x86_64"))]
+type BenchMeasurement = criterion::measurement::WallTime;
+
+fn config() -> Criterion<BenchMeasurement> {
+    #[cfg(target_arch = "x86_64")]
+    return Criterion::default().with_measurement(cycles::Cycles);
+    #[cfg(not(target_arch = "x86_64"))]
+    return Criterion::default();
+}
+
+fn bench_decode(c: &mut Criterion<BenchMeasurement>) {
+    let mut group = c.benchmark_group("decode");
+    // payload sizes close to the largest blob of 16 MiB
+    for payload_len in [128 * 1024, 1024 * 1024, 15 * 1024 * 1024] {
+        let payload: Vec<u8> = (0..payload_len).map(|i| i as u8).collect();
+        let encoded_payload = EncodedPayload::encode(&payload, PAYLOAD_ENCODING_VERSION_0).unwrap();
+        group.throughput(Throughput::Bytes(
+            encoded_payload.encoded_payload.len() as u64
+        ));
+
+        group.bench_with_input(
+            BenchmarkId::new("decode", payload_len),
+            &encoded_payload,
+            |b, encoded_payload| b.iter(|| black_box(encoded_payload.decode().unwrap())),
+        );
+        // reuse the output buffer across iterations, as a caller streaming into a reader would
+        let mut out = Vec::with_capacity(payload_len);
+        group.bench_with_input(
+            BenchmarkId::new("decode_into", payload_len),
+            &encoded_payload,
+            |b, encoded_payload| {
+                b.iter(|| {
+                    out.clear();
+                    encoded_payload.decode_into(&mut out).unwrap();
+                    black_box(&out);
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = config();
+    targets = bench_decode
+}
+criterion_main!(benches);
@@ -4,6 +4,9 @@
 pub const PAYLOAD_ENCODING_VERSION_0: u8 = 0x0;
 /// 32 bytes per field element on bn254 curve
 pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// Size of an encoded payload chunk in the preimage oracle, 128 field elements. The last chunk of
+/// an encoded payload smaller than a chunk is as long as the encoded payload
+pub const BYTES_PER_ENCODED_PAYLOAD_CHUNK: usize = 4096;
 /// Encoded payload header length in bytes (first field element)
 pub const ENCODED_PAYLOAD_HEADER_LEN_BYTES: usize = 32;
 /// EigenDA Version in OP Derivation Version Byte
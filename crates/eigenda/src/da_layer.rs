@@ -0,0 +1,176 @@
+//! Contains the [DaLayerHandler] trait and the [DaLayerRegistry]. A handler retrieves the rollup
+//! payload of generic altda commitments of one DA layer, identified by the da_layer_byte.
+//! See spec <https://specs.optimism.io/experimental/alt-da.html#input-commitment-submission>
+
+use crate::errors::{DaLayerRegistryError, HokuleaErrorKind};
+use crate::events::DiscardReason;
+use crate::traits::EigenDAPreimageProvider;
+use crate::{EigenDAPreimageSource, PayloadCodecRegistry};
+use alloc::{boxed::Box, collections::BTreeMap};
+use alloy_primitives::Bytes;
+use async_trait::async_trait;
+use core::fmt::Debug;
+use eigenda_cert::{GenericAltDACommitment, EIGENDA_DA_LAYER_BYTE};
+
+/// Retrieves the rollup payload of generic altda commitments of a DA layer. The EigenDA layer,
+/// da_layer_byte 0, is the first handler of every data source, see [EigenDADaLayer].
+#[async_trait]
+pub trait DaLayerHandler: Debug + Send + Sync {
+    /// The da_layer_byte of the DA layer
+    fn da_layer_byte(&self) -> u8;
+
+    /// Retrieves the rollup payload of the generic altda commitment, which carries the
+    /// da_layer_byte of the handler. Returns the [DiscardReason] if the commitment is discarded,
+    /// the derivation then moves on to the next altda commitment. Temporary errors are retried
+    /// with the same commitment.
+    async fn next(
+        &mut self,
+        altda_commitment: &GenericAltDACommitment,
+        l1_inclusion_bn: u64,
+    ) -> Result<Result<Bytes, DiscardReason>, HokuleaErrorKind>;
+}
+
+/// A [DaLayerHandler] stored in the [DaLayerRegistry]. It is implemented for every handler which
+/// is [Clone], such that the registry is cloned along with the data source.
+pub trait RegisteredDaLayerHandler: DaLayerHandler {
+    /// Clones the handler into a new box
+    fn clone_box(&self) -> Box<dyn RegisteredDaLayerHandler>;
+}
+
+impl<H: DaLayerHandler + Clone + 'static> RegisteredDaLayerHandler for H {
+    fn clone_box(&self) -> Box<dyn RegisteredDaLayerHandler> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn RegisteredDaLayerHandler> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// A set of [DaLayerHandler] keyed by their da_layer_byte, for the DA layers other than EigenDA.
+/// The default registry is empty, such that generic commitments of any other DA layer are
+/// discarded.
+#[derive(Debug, Clone, Default)]
+pub struct DaLayerRegistry {
+    handlers: BTreeMap<u8, Box<dyn RegisteredDaLayerHandler>>,
+}
+
+impl DaLayerRegistry {
+    /// Registers a handler under its da_layer_byte. Returns the handler previously registered
+    /// under the same da_layer_byte, if any.
+    ///
+    /// Fails if the handler claims the da_layer_byte of EigenDA, which is always served by the
+    /// [EigenDADaLayer] of the data source.
+    pub fn register<H: DaLayerHandler + Clone + 'static>(
+        &mut self,
+        handler: H,
+    ) -> Result<Option<Box<dyn RegisteredDaLayerHandler>>, DaLayerRegistryError> {
+        let da_layer_byte = handler.da_layer_byte();
+        if da_layer_byte == EIGENDA_DA_LAYER_BYTE {
+            return Err(DaLayerRegistryError::ReservedDaLayerByte(da_layer_byte));
+        }
+        Ok(self.handlers.insert(da_layer_byte, Box::new(handler)))
+    }
+
+    /// Returns the handler registered under the da_layer_byte
+    pub fn get(&self, da_layer_byte: u8) -> Option<&dyn DaLayerHandler> {
+        self.handlers
+            .get(&da_layer_byte)
+            .map(|handler| handler.as_ref() as &dyn DaLayerHandler)
+    }
+
+    /// Returns the handler registered under the da_layer_byte, to retrieve a rollup payload
+    pub fn get_mut(&mut self, da_layer_byte: u8) -> Option<&mut dyn DaLayerHandler> {
+        self.handlers
+            .get_mut(&da_layer_byte)
+            .map(|handler| handler.as_mut() as &mut dyn DaLayerHandler)
+    }
+}
+
+/// The [DaLayerHandler] of EigenDA, the first DA layer of every data source. It fetches the
+/// encoded payload of the DA cert through the [EigenDAPreimageSource], and decodes it with the
+/// codecs of the data source.
+#[derive(Debug)]
+pub struct EigenDADaLayer<'a, B>
+where
+    B: EigenDAPreimageProvider + Send,
+{
+    /// fetches the encoded payload of the DA cert
+    pub eigenda_source: &'a mut EigenDAPreimageSource<B>,
+    /// decodes the encoded payload
+    pub payload_codecs: &'a PayloadCodecRegistry,
+}
+
+#[async_trait]
+impl<B> DaLayerHandler for EigenDADaLayer<'_, B>
+where
+    B: EigenDAPreimageProvider + Send + Sync + Debug,
+{
+    fn da_layer_byte(&self) -> u8 {
+        EIGENDA_DA_LAYER_BYTE
+    }
+
+    async fn next(
+        &mut self,
+        altda_commitment: &GenericAltDACommitment,
+        l1_inclusion_bn: u64,
+    ) -> Result<Result<Bytes, DiscardReason>, HokuleaErrorKind> {
+        let GenericAltDACommitment::EigenDA(altda_commitment) = altda_commitment else {
+            return Ok(Err(DiscardReason::UnsupportedDaLayer(
+                altda_commitment.da_layer_byte().unwrap_or_default(),
+            )));
+        };
+        let encoded_payload = match self
+            .eigenda_source
+            .try_next(altda_commitment, l1_inclusion_bn)
+            .await?
+        {
+            Ok(encoded_payload) => encoded_payload,
+            Err(reason) => return Ok(Err(reason)),
+        };
+        Ok(encoded_payload
+            .decode_with(self.payload_codecs)
+            .map_err(DiscardReason::Decoding))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDaLayerHandler;
+
+    #[test]
+    fn test_register_da_layer_handler() {
+        let mut registry = DaLayerRegistry::default();
+        assert!(registry.get(7).is_none());
+
+        let handler = TestDaLayerHandler::new(7);
+        assert!(registry.register(handler).unwrap().is_none());
+        assert_eq!(registry.get(7).map(|h| h.da_layer_byte()), Some(7));
+        assert_eq!(registry.get_mut(7).map(|h| h.da_layer_byte()), Some(7));
+        assert!(registry.get(8).is_none());
+
+        // a second handler replaces the first one
+        let previous = registry.register(TestDaLayerHandler::new(7)).unwrap();
+        assert_eq!(previous.map(|h| h.da_layer_byte()), Some(7));
+
+        // the registry is cloned with its handlers
+        assert_eq!(registry.clone().get(7).map(|h| h.da_layer_byte()), Some(7));
+    }
+
+    #[test]
+    fn test_register_eigenda_da_layer_handler() {
+        let mut registry = DaLayerRegistry::default();
+        assert_eq!(
+            registry
+                .register(TestDaLayerHandler::new(EIGENDA_DA_LAYER_BYTE))
+                .err(),
+            Some(DaLayerRegistryError::ReservedDaLayerByte(
+                EIGENDA_DA_LAYER_BYTE
+            ))
+        );
+        assert!(registry.get(EIGENDA_DA_LAYER_BYTE).is_none());
+    }
+}
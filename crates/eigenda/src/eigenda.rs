@@ -1,11 +1,15 @@
 //! Contains the [EigenDAPreimageSource] and EigenDA blob derivation, which is a concrete
 //! implementation of the [DataAvailabilityProvider] trait for the EigenDA protocol.
 use crate::traits::EigenDAPreimageProvider;
-use crate::{eigenda_preimage::EigenDAPreimageSource, HokuleaErrorKind, ALTDA_DERIVATION_VERSION};
-use eigenda_cert::AltDACommitment;
+use crate::{
+    eigenda_preimage::EigenDAPreimageSource, DaLayerHandler, DaLayerRegistry, DerivationEvent,
+    DerivationEventSink, DerivedFrame, DiscardReason, EigenDADaLayer, HokuleaErrorKind,
+    NoopDerivationEventSink, PayloadCodecRegistry, ALTDA_DERIVATION_VERSION,
+};
+use eigenda_cert::GenericAltDACommitment;
 use kona_derive::PipelineErrorKind;
 
-use alloc::{boxed::Box, fmt::Debug};
+use alloc::{boxed::Box, fmt::Debug, sync::Arc};
 use alloy_primitives::{Address, Bytes};
 use async_trait::async_trait;
 use kona_derive::{
@@ -32,7 +36,16 @@ where
     pub eigenda_source: EigenDAPreimageSource<A>,
     /// altda commitment, if we step in by calling next and it is Some, it means previous
     /// call has a temporary error
-    pub altda_commitment: Option<AltDACommitment>,
+    pub altda_commitment: Option<GenericAltDACommitment>,
+    /// codecs used to decode the encoded payload, keyed by payload encoding version
+    pub payload_codecs: PayloadCodecRegistry,
+    /// observes the derivation events of every altda commitment
+    pub event_sink: Arc<dyn DerivationEventSink>,
+    /// handlers of generic altda commitments of the DA layers other than EigenDA, keyed by
+    /// da_layer_byte
+    pub da_layers: DaLayerRegistry,
+    /// whether keccak altda commitments are resolved, otherwise they are discarded
+    pub keccak_commitments: bool,
 }
 
 impl<C, B, A> EigenDADataSource<C, B, A>
@@ -41,8 +54,9 @@ where
     B: BlobProvider + Send + Clone + Debug,
     A: EigenDAPreimageProvider + Send + Clone + Debug,
 {
-    /// Instantiates a new [EigenDADataSource].
-    pub const fn new(
+    /// Instantiates a new [EigenDADataSource], which decodes encoded payloads with the
+    /// built-in payload encoding versions.
+    pub fn new(
         ethereum_source: EthereumDataSource<C, B>,
         eigenda_source: EigenDAPreimageSource<A>,
     ) -> Self {
@@ -50,116 +64,266 @@ where
             ethereum_source,
             eigenda_source,
             altda_commitment: None,
+            payload_codecs: PayloadCodecRegistry::default(),
+            event_sink: Arc::new(NoopDerivationEventSink),
+            da_layers: DaLayerRegistry::default(),
+            keccak_commitments: false,
+        }
+    }
+
+    /// Replaces the codecs used to decode the encoded payload.
+    pub fn with_payload_codecs(mut self, payload_codecs: PayloadCodecRegistry) -> Self {
+        self.payload_codecs = payload_codecs;
+        self
+    }
+
+    /// Replaces the sink observing the derivation events.
+    pub fn with_event_sink(mut self, event_sink: Arc<dyn DerivationEventSink>) -> Self {
+        self.event_sink = event_sink;
+        self
+    }
+
+    /// Replaces the handlers of the DA layers other than EigenDA. Generic altda commitments of
+    /// a da_layer_byte without handler are discarded.
+    pub fn with_da_layers(mut self, da_layers: DaLayerRegistry) -> Self {
+        self.da_layers = da_layers;
+        self
+    }
+
+    /// Resolves keccak altda commitments (commitment_type 0) through the preimage oracle, for
+    /// chains migrated from OP's keccak based altda. They are discarded by default, as op-node
+    /// skips commitments of a type other than the one it is configured for.
+    pub fn with_keccak_commitments(mut self, keccak_commitments: bool) -> Self {
+        self.keccak_commitments = keccak_commitments;
+        self
+    }
+
+    /// Builds and emits the event if the event sink is enabled.
+    fn emit(&self, event: impl FnOnce() -> DerivationEvent) {
+        if self.event_sink.enabled() {
+            self.event_sink.on_event(&event());
+        }
+    }
+
+    /// Emits the [DerivationEvent::CertDiscarded] event, and drops the altda commitment such
+    /// that the next one is fetched.
+    fn discard(&mut self, reason: DiscardReason, block_ref: &BlockInfo, batcher_addr: Address) {
+        if let Some(altda_commitment) = self.altda_commitment.take() {
+            self.emit(|| DerivationEvent::CertDiscarded {
+                reason,
+                digest: altda_commitment.to_digest(),
+                l1_block: block_ref.number,
+                batcher_addr,
+            });
         }
     }
 }
 
-#[async_trait]
-impl<C, B, A> DataAvailabilityProvider for EigenDADataSource<C, B, A>
+impl<C, B, A> EigenDADataSource<C, B, A>
 where
     C: ChainProvider + Send + Sync + Clone + Debug,
     B: BlobProvider + Send + Sync + Clone + Debug,
     A: EigenDAPreimageProvider + Send + Sync + Clone + Debug,
 {
-    type Item = Bytes;
+    /// Same as [DataAvailabilityProvider::next], but tags the derived data with the DA path
+    /// producing it. Data which is not an altda commitment is tagged [DerivedFrame::Ethereum],
+    /// the ethereum source does not tell calldata from blob.
+    pub async fn next_frame(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_addr: Address,
+    ) -> PipelineResult<DerivedFrame> {
+        let (data, altda_commitment) = self.next_data(block_ref, batcher_addr).await?;
+        Ok(match altda_commitment {
+            Some(GenericAltDACommitment::EigenDA(altda_commitment)) => DerivedFrame::EigenDA {
+                digest: altda_commitment.to_digest(),
+                payload: data,
+            },
+            Some(GenericAltDACommitment::Keccak(commitment)) => {
+                DerivedFrame::AltDAKeccak { commitment, data }
+            }
+            Some(GenericAltDACommitment::DaService { da_layer_byte, .. }) => {
+                DerivedFrame::DaLayer {
+                    da_layer_byte,
+                    payload: data,
+                }
+            }
+            None => DerivedFrame::Ethereum(data),
+        })
+    }
 
-    async fn next(
+    /// Derives the next data, together with the altda commitment it is derived from, if any.
+    async fn next_data(
         &mut self,
         block_ref: &BlockInfo,
         batcher_addr: Address,
-    ) -> PipelineResult<Self::Item> {
+    ) -> PipelineResult<(Bytes, Option<GenericAltDACommitment>)> {
         debug!("Data Available Source next {} {}", block_ref, batcher_addr);
         // if there is no data, fetch one from ethereum source.
         // the fetched data can either be an altda commitment which can be used to retrieve from eigenda source
         // or a ethereum blob or calldata
-        if self.altda_commitment.is_none() {
-            let local_data = match self.ethereum_source.next(block_ref, batcher_addr).await {
-                Ok(d) => d,
-                Err(e) => {
-                    // if ethereum source for that block exhausted, reset the ethereum source and itself
-                    // before returning error
-                    if let PipelineErrorKind::Temporary(PipelineError::Eof) = e {
-                        self.clear();
+        // a discarded altda commitment moves on to the next one in the loop, instead of recursing. A
+        // block with many discarded altda commitments would otherwise build a deep chain of futures
+        loop {
+            if self.altda_commitment.is_none() {
+                let local_data = match self.ethereum_source.next(block_ref, batcher_addr).await {
+                    Ok(d) => d,
+                    Err(e) => {
+                        // if ethereum source for that block exhausted, reset the ethereum source and itself
+                        // before returning error
+                        if let PipelineErrorKind::Temporary(PipelineError::Eof) = e {
+                            self.clear();
+                        }
+                        return Err(e);
                     }
-                    return Err(e);
+                };
+
+                // if data length is 0, return early. It implies the data is skipped
+                if local_data.is_empty() {
+                    // see handling in go implementation
+                    // OP develop branch https://github.com/ethereum-optimism/optimism/blob/4317c093fbe951c57c0e36037a9aa281e8e0795c/op-node/rollup/derive/altda_data_source.go#L56
+                    // EigenLabs  branch https://github.com/Layr-Labs/optimism/blob/34e5ce8416de529b8a57b0c55e1635ebe89805dc/op-node/rollup/derive/altda_data_source.go#L58
+                    return Err(PipelineErrorKind::Temporary(PipelineError::NotEnoughData));
                 }
-            };
-
-            // if data length is 0, return early. It implies the data is skipped
-            if local_data.is_empty() {
-                // see handling in go implementation
-                // OP develop branch https://github.com/ethereum-optimism/optimism/blob/4317c093fbe951c57c0e36037a9aa281e8e0795c/op-node/rollup/derive/altda_data_source.go#L56
-                // EigenLabs  branch https://github.com/Layr-Labs/optimism/blob/34e5ce8416de529b8a57b0c55e1635ebe89805dc/op-node/rollup/derive/altda_data_source.go#L58
-                return Err(PipelineErrorKind::Temporary(PipelineError::NotEnoughData));
-            }
 
-            // it is not intended for altDA
-            if local_data[0] != ALTDA_DERIVATION_VERSION {
-                return Ok(local_data);
-            }
+                // it is not intended for altDA
+                if local_data[0] != ALTDA_DERIVATION_VERSION {
+                    return Ok((local_data, None));
+                }
 
-            match self.eigenda_source.parse(&local_data) {
-                // set the state only we has parsed an altda commitment
-                Ok(altda_commitment) => self.altda_commitment = Some(altda_commitment),
-                // OP develop branch https://github.com/ethereum-optimism/optimism/blob/4317c093fbe951c57c0e36037a9aa281e8e0795c/op-node/rollup/derive/altda_data_source.go#L69
-                // EigenLabs  branch https://github.com/Layr-Labs/optimism/blob/34e5ce8416de529b8a57b0c55e1635ebe89805dc/op-node/rollup/derive/altda_data_source.go#L72
-                Err(_) => return Err(PipelineErrorKind::Temporary(PipelineError::NotEnoughData)),
+                match self.eigenda_source.parse_generic(&local_data) {
+                    // set the state only we has parsed an altda commitment
+                    Ok(altda_commitment) => {
+                        self.emit(|| DerivationEvent::CertParsed {
+                            digest: altda_commitment.to_digest(),
+                            l1_block: block_ref.number,
+                            batcher_addr,
+                        });
+                        self.altda_commitment = Some(altda_commitment)
+                    }
+                    // OP develop branch https://github.com/ethereum-optimism/optimism/blob/4317c093fbe951c57c0e36037a9aa281e8e0795c/op-node/rollup/derive/altda_data_source.go#L69
+                    // EigenLabs  branch https://github.com/Layr-Labs/optimism/blob/34e5ce8416de529b8a57b0c55e1635ebe89805dc/op-node/rollup/derive/altda_data_source.go#L72
+                    Err(_) => {
+                        return Err(PipelineErrorKind::Temporary(PipelineError::NotEnoughData))
+                    }
+                }
             }
-        }
 
-        // keep the altda_commitment, in case eigenda source encounters a temporary error. When next function is called
-        // again, we can retry this altda commiment without losing it, because ethereum no longer keeps this data after
-        // popping it
-        let local_altda_commitment = self
-            .altda_commitment
-            .clone()
-            .expect("should have altda commitment");
-
-        match self
-            .eigenda_source
-            .next(&local_altda_commitment, block_ref.number)
-            .await
-        {
-            Err(e) => match e {
-                HokuleaErrorKind::Temporary(e) => {
-                    warn!("Hokulea derivation may encounter temporary errors caused by either the preimage provider
-                        or the communication channel between preimage providers. All temporary errors will result in
-                        retrying the same derivation step: {}", e);
-                    Err(PipelineError::Provider(e).temp())
+            // keep the altda_commitment, in case eigenda source encounters a temporary error. When next function is called
+            // again, we can retry this altda commiment without losing it, because ethereum no longer keeps this data after
+            // popping it
+            let local_altda_commitment = self
+                .altda_commitment
+                .clone()
+                .expect("should have altda commitment");
+
+            let result = match &local_altda_commitment {
+                // the input data of a keccak commitment is returned as it is
+                GenericAltDACommitment::Keccak(commitment) => {
+                    if self.keccak_commitments {
+                        self.eigenda_source.next_keccak(*commitment).await.map(Ok)
+                    } else {
+                        Ok(Err(DiscardReason::KeccakCommitmentDisabled))
+                    }
+                }
+                // generic commitments are served by the handler of their DA layer, EigenDA first
+                GenericAltDACommitment::EigenDA(_) => {
+                    EigenDADaLayer {
+                        eigenda_source: &mut self.eigenda_source,
+                        payload_codecs: &self.payload_codecs,
+                    }
+                    .next(&local_altda_commitment, block_ref.number)
+                    .await
+                }
+                GenericAltDACommitment::DaService { da_layer_byte, .. } => {
+                    match self.da_layers.get_mut(*da_layer_byte) {
+                        Some(handler) => {
+                            handler
+                                .next(&local_altda_commitment, block_ref.number)
+                                .await
+                        }
+                        None => Ok(Err(DiscardReason::UnsupportedDaLayer(*da_layer_byte))),
+                    }
                 }
-                HokuleaErrorKind::Discard(e) => {
+            };
+
+            match result {
+                Ok(Err(reason)) => {
                     // altda commitment is discarded and try next one
                     // EigenLabs branch https://github.com/Layr-Labs/optimism/blob/34e5ce8416de529b8a57b0c55e1635ebe89805dc/op-node/rollup/derive/altda_data_source.go#L103
-                    warn!(
-                        "Hokulea derivation discards due to recency or validity: {}",
-                        e
-                    );
-                    self.altda_commitment = None;
-                    return self.next(block_ref, batcher_addr).await;
-                }
-                HokuleaErrorKind::Critical(e) => {
-                    error!("Hokulea derivation critical: {}", e);
-                    Err(PipelineError::Provider(e).crit())
+                    match &reason {
+                        // encoded payload cannot be decoded, data is discarded and try next one
+                        DiscardReason::Decoding(e) => {
+                            warn!("Hokulea derivation discards due to decoding error: {}", e)
+                        }
+                        DiscardReason::UnsupportedDaLayer(da_layer_byte) => warn!(
+                            "Hokulea derivation discards due to unsupported da layer byte: {}",
+                            da_layer_byte
+                        ),
+                        DiscardReason::KeccakCommitmentDisabled => {
+                            warn!("Hokulea derivation discards keccak altda commitment, as keccak commitments are disabled")
+                        }
+                        _ => warn!(
+                            "Hokulea derivation discards due to recency or validity: {:?}",
+                            reason
+                        ),
+                    }
+                    self.discard(reason, block_ref, batcher_addr);
                 }
-            },
-            Ok(encoded_payload) => {
-                match encoded_payload.decode() {
-                    Ok(c) => {
-                        // EigenLabs branch https://github.com/Layr-Labs/optimism/blob/34e5ce8416de529b8a57b0c55e1635ebe89805dc/op-node/rollup/derive/altda_data_source.go#L117
-                        self.altda_commitment = None;
-                        return Ok(c);
+                Err(e) => match e {
+                    HokuleaErrorKind::Temporary(e) => {
+                        warn!("Hokulea derivation may encounter temporary errors caused by either the preimage provider
+                        or the communication channel between preimage providers. All temporary errors will result in
+                        retrying the same derivation step: {}", e);
+                        return Err(PipelineError::Provider(e).temp());
                     }
-                    Err(e) => {
-                        // encoded payload cannot be decoded, data is discarded and try next one
+                    HokuleaErrorKind::Discard(e) => {
+                        // altda commitment is discarded and try next one
                         // EigenLabs branch https://github.com/Layr-Labs/optimism/blob/34e5ce8416de529b8a57b0c55e1635ebe89805dc/op-node/rollup/derive/altda_data_source.go#L103
-                        warn!("Hokulea derivation discards due to decoding error: {}", e);
-                        self.altda_commitment = None;
-                        return self.next(block_ref, batcher_addr).await;
+                        warn!(
+                            "Hokulea derivation discards as requested by the preimage provider: {}",
+                            e
+                        );
+                        self.discard(DiscardReason::Provider(e), block_ref, batcher_addr);
+                    }
+                    HokuleaErrorKind::Critical(e) => {
+                        error!("Hokulea derivation critical: {}", e);
+                        return Err(PipelineError::Provider(e).crit());
                     }
+                },
+                Ok(Ok(c)) => {
+                    // EigenLabs branch https://github.com/Layr-Labs/optimism/blob/34e5ce8416de529b8a57b0c55e1635ebe89805dc/op-node/rollup/derive/altda_data_source.go#L117
+                    self.altda_commitment = None;
+                    self.emit(|| DerivationEvent::PayloadDecoded {
+                        len: c.len(),
+                        digest: local_altda_commitment.to_digest(),
+                        l1_block: block_ref.number,
+                    });
+                    return Ok((c, Some(local_altda_commitment)));
                 }
             }
         }
     }
+}
+
+#[async_trait]
+impl<C, B, A> DataAvailabilityProvider for EigenDADataSource<C, B, A>
+where
+    C: ChainProvider + Send + Sync + Clone + Debug,
+    B: BlobProvider + Send + Sync + Clone + Debug,
+    A: EigenDAPreimageProvider + Send + Sync + Clone + Debug,
+{
+    type Item = Bytes;
+
+    async fn next(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_addr: Address,
+    ) -> PipelineResult<Self::Item> {
+        self.next_data(block_ref, batcher_addr)
+            .await
+            .map(|(data, _)| data)
+    }
 
     fn clear(&mut self) {
         self.altda_commitment = None;
@@ -177,14 +341,19 @@ mod tests {
     use alloc::string::ToString;
     use alloc::vec::Vec;
     use alloc::{collections::VecDeque, vec};
-    use alloy_consensus::TxEnvelope;
+    use alloy_consensus::{Transaction, TxEnvelope};
     use alloy_primitives::B256;
     use alloy_rlp::Decodable;
     use eigenda_cert::AltDACommitment;
+    use eigenda_cert::{GENERIC_COMMITMENT_TYPE, KECCAK_COMMITMENT_TYPE};
     use kona_derive::test_utils::{TestBlobProvider, TestChainProvider};
-    use kona_derive::{BlobSource, CalldataSource};
+    use kona_derive::{BlobData, BlobSource, CalldataSource};
     use kona_genesis::{HardForkConfig, RollupConfig};
 
+    extern crate std;
+    use crate::{EncodedPayloadDecodingError, HokuleaStatelessError, RecencyWindows};
+    use std::sync::Mutex;
+
     const L1_INBOX_ADDRESS: Address =
         alloy_primitives::address!("0x000faef0a3d9711c3e9bbc4f3e2730dd75167da3");
     const BATCHER_ADDRESS: Address =
@@ -192,7 +361,7 @@ mod tests {
     // All three pairs of data are valid and corresponds to the addresses above, with rbn 9300876
     // https://sepolia.etherscan.io/getRawTx?tx=0x9a22ccb0029bc8b0ddd073be1a1d923b7ae2b2ea52100bae0db4424f9107e9c0
     const CALLDATA_1: &str = "0x02f904f583aa36a78212f2843b9aca0084b2d05e008301057294000faef0a3d9711c3e9bbc4f3e2730dd75167da380b9048301010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001c001a0445ab87abefec130d63733b3bcafc7ee0c0f8367e61b580be4f0cf0c3d21a03aa02d054c857c76e9dbf47d63d0b70b58200e14e9f9ba2eb47343c3b67faab93a72";
-    const ALTDA_COMMITMENT_BYTES_1: &str = "0x010002f9047ce5a04c617ac0dcf14f58a1d58e80c9902e2c199474989563dc59566d5bd5ad1b640a838deb8cf901cef901c9f9018180820001f90159f842a02f79ec81c41b992e9dec0c96fe5d970657bd5699560b1eaca902b6d8d95b69d9a014aee8fa5e2bd3a23ce376c537248acce7c29a74962218a4cc19c483d962dcf7f888f842a01c4c0eec183bf264a5b96b2ddc64e400a3f03752fb9d4296f3b4729e237ea40da01303695a7e9cba15f6ecb2e5da94826c94e557d94a491b61b42e2fb577bf5983f842a00c4bb24f65dd9d63401f8fb5aa680c36c3a18c06996511ce14544d77bc3659bba01a201aef9dceb92540f58243194aeae5c4b5953dddf17925c5a56bcb57ec19adf888f842a02a71a11141df9d0a5158602444003491763859afb77b1566a3eabafc162d4617a027bfbe487a7507ab70b6b42433850f8b7be21ab2c268f415cb68608506da9114f842a013002e07d4f2259193d9aa06a01866dc527221d65cc5c49c4c05cfc281d873c1a02d47dba83902698378718ab5c589eb9c7daa5f9641a5ce160f112bc65b40227308a0731bd6915a6ccea1380db7f0695ad67ee03bfbd59ac8c7976ee25f7ec9515037b8414cd74a3034296d0e2d63ce879dbe578e0715c29fd388c9babb38bd99ef45c64d548d60eec508758c6101b4b01ff2b65ff503fa485a8035a54edd1bc71d84430e00c1808080f9027fc401808080f9010ff842a01cd040b326ae7cd372763fafb595470d3613f6fb3d824582bf02edcb735ccb0fa017bbe7ebc3167abad8710ecd335b37a1b63d1f0119569bcf3f84d2125810a294f842a0297ac518058025f67f0c0cc4d735965f242540ddbf998491e5b66a5c9d56c712a00dc76d3bfe805d8ad41c96a5d3696ecd22c44049057fbb2b2f3e0c204f5dd745f8419f9a9a3504786f979f4011c180069d0127599773df85c02f550c8bcd4336d150a02bf5de7c6791a70185eb0eef04661bbf6f3596569843dbd9172eea27ad484249f842a020304749b8c2e65c4a82035cf1c559ea8b8d7ab9a94b6dc7d4b79299be445ae9a02b4d5e4ecb245d94af3d6c279c1a86fb452401355be715ac4887fcdcf7642ce4f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a0171e10f7d012c823ceb26e40245a97375804a82ca8f92e0dd49fc5f76c3b093ea028946cc01b7092bb709a72c07184d84821125632337d4c8f9a063afcefdc57c0f842a00df37a0480625fa5ab86d78e4664d2bacfed6c4e7562956bfc95f2b9efd1977ca0121ae7669b68221699c6b4eb057acbf2e58d4fb4b4da7aa5e4deaaac513f6ce0f842a01abcc37d2cbe680d5d6d3ebeddc3f5b09f103e2fa3a20a887c573f2ac5ab6e36a01a23d0ac964f04643eb3206db5a81e678fc484f362d3c7442657735e678298c3c20705c20805c9c3018080c480808080820001";
+    const ALTDA_COMMITMENT_BYTES_1: &str = test_utils::ALTDA_COMMITMENT_V3;
     const ENCODED_PAYLOAD_1: &str = "00000000009100000000000000000000000000000000000000000000000000000000ab80c99f814a3541886f8f4a65f61b67000000000079011b6501f88f532c00998d4648d239b1ce87da27450caaab705a5c8412149720e6dd229a4b97d25600ca7222a7ae434145a5d1440229000106a45bd00f3e0e33b07a5c23ad927eaa00f98a77e7818ff59e2c3b2c03d5ffaeb6dba4cb08b9fa2d122e8acbe726c4a70009ae086496e0d3ac00d70438c034e1f1314b70c0010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000";
     // https://sepolia.etherscan.io/getRawTx?tx=0x30321b4b3815e95627aa4ad91c8b14a56558d0fc9cd17976723384d178f01e1e, with rbn 9528135
     const CALLDATA_2: &str = "0x02f903d883aa36a78226a8843b9aca0084b2d05e0082d93094000faef0a3d9711c3e9bbc4f3e2730dd75167da380b9036701010002f90360e5a079b1d7d15095ff732f8443d96b6d293494b003e9b4377ac1c51375908afb2e9e83916347f901cef901c9f9018180820001f90159f842a008d79f9a46a4183f1d64c45fdedc36be33efc4cd27ffc9f85ac02987c2a5dddca004df46272276ced58d65a56b9a6dfa24102f066d3f0bba07e35992ede22aa4bcf888f842a0018fe73cb24305b7df1fc808f65c15d0f7921758cef87d9f38d31db4236eb9f4a01125fd3aa13adb27759b01c954dd641ad2174374f8e4cb3333970eda9fa011ecf842a00a0dd6223eed09f283a20429a7e03449c7d38b4fe674913c67e0f02d16949a41a00f52bab320eeb037ad223e361512350e35e373c79cf7f2ba1ec2aa036b49adbef888f842a02ecf0e30ff9ce9696c6c1ce5c341bfdfb56f589d3da7925fe54ee43a6667a09aa023a35883de2716167810bfca0c14af9530321908c4b78b572e9308161bb2d402f842a01fd1844a0e0f37f3c9edc39d0e14c8a4214afa7b017843dba1f467c8765df23fa004a01126fe1d6b57d7b985c61a3cb9a57f6c2e86d4dd0da956ad613bef0bcda708a000feeb6796b97a3f0179ef436770791068b9fdc15866f53af08f6d649083952eb841af27b4b47fd17adb76d6bb38831a19df88ee027ceaf22c88d846daff41ce2c7e5a814c8d0aa8034d1626e00d1c711087f412d4ccb35cacb0d490fbaa582493cb01c1808080f90163c0c0f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a027b90b5da16ef02417ad5820223e680d2c2d19a3f1d30566cfbb7b9aa30abf6da022432d9b57d271b8dd84bfb4ccd9df36b84e422cb471b35d50d55ae83a03f16ef842a0018ed79d6c0707cc6f4ec81bcea6c4cc0096f0e3635961caf3271c3c9a36a9dfa0179360dc4646a7c49bf730e1789c00622facd7836faa3c747be0f2d824cb1412f842a029273db955f9532f7b1ffe0eead7b85ac277534c892f73f0d9cf4403be36b5c6a022895e02ab90d618987ee5bf2818c93b9c5fac931d2af2b42a2d207c9d3d4966c20705c20805c2c0c0820001c080a0d8bcdbee635bd0836d858f6da41e289529956b463700fa1a886eee4019e2a2c0a045f9ddd72ebf7c56766a0bb9fbcb234fa987859273a98df8ea9d5af199b494df";
@@ -321,6 +490,23 @@ mod tests {
         calldata[..].try_into().unwrap()
     }
 
+    // returns the eigenda altda commitment kept by the source after a temporary error
+    fn pending_eigenda_altda_commitment(
+        source: &EigenDADataSource<
+            TestChainProvider,
+            TestBlobProvider,
+            TestEigenDAPreimageProvider,
+        >,
+    ) -> AltDACommitment {
+        match source.altda_commitment.clone() {
+            Some(GenericAltDACommitment::EigenDA(altda_commitment)) => altda_commitment,
+            altda_commitment => panic!(
+                "should have eigenda altda commitment, got {:?}",
+                altda_commitment
+            ),
+        }
+    }
+
     fn configure_chain_provider_with_txs(
         source: &mut EigenDADataSource<
             TestChainProvider,
@@ -396,7 +582,7 @@ mod tests {
             EigenDADataSource::new(ethereum_data_source, eigenda_preimage_source);
 
         // populate eigen source with data
-        eigenda_data_source.altda_commitment = Some(default_altda_commitment());
+        eigenda_data_source.altda_commitment = Some(default_altda_commitment().into());
 
         // clear all data
         eigenda_data_source.clear();
@@ -917,15 +1103,16 @@ mod tests {
 
                 // automatically remove any preimage error, translate it into drop
                 if source.altda_commitment.is_some() {
+                    let altda_commitment = pending_eigenda_altda_commitment(&source);
                     source
                         .eigenda_source
                         .eigenda_fetcher
-                        .insert_validity(&source.altda_commitment.clone().unwrap(), Ok(false));
+                        .insert_validity(&altda_commitment, Ok(false));
                     source
                         .eigenda_source
                         .eigenda_fetcher
                         .insert_encoded_payload(
-                            &source.altda_commitment.clone().unwrap(),
+                            &altda_commitment,
                             Ok(NOT_DECODABLE_ENCODED_PAYLOAD),
                         );
                 }
@@ -966,7 +1153,7 @@ mod tests {
             ],
             // first retry holds an altda commitment, next call will return payload and set to false,
             // third time call has temporary err, and have it to true, fourth time call set it to false,
-            // and return payload. The last call see a drop, then recursively derive to end of file
+            // and return payload. The last call see a drop, then keeps deriving to end of file
             altda_commitment_is_some: vec![true, false, true, false, false],
         };
 
@@ -991,13 +1178,501 @@ mod tests {
             // automatically remove any preimage error, translate it into drop
             // we take a short cut that only set retry on validity, but not on payload
             if source.altda_commitment.is_some() {
+                let altda_commitment = pending_eigenda_altda_commitment(&source);
                 source
                     .eigenda_source
                     .eigenda_fetcher
-                    .insert_validity(&source.altda_commitment.clone().unwrap(), Ok(true));
+                    .insert_validity(&altda_commitment, Ok(true));
             }
         }
     }
 
+    // a sink recording all derivation events
+    #[derive(Debug, Default)]
+    struct TestDerivationEventSink {
+        events: Mutex<Vec<DerivationEvent>>,
+    }
+
+    impl DerivationEventSink for TestDerivationEventSink {
+        fn on_event(&self, event: &DerivationEvent) {
+            self.events.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next_emits_derivation_events() {
+        let block_info = BLOCK_INFO;
+        let (_, altda_commitments, encoded_payloads) =
+            valid_eip1559_txs_with_altda_commitment_encoded_payload(3);
+        let digests: Vec<B256> = altda_commitments.iter().map(|a| a.to_digest()).collect();
+        let payload_len = encoded_payloads[2].decode().unwrap().len();
+        let cert_parsed = |i: usize| DerivationEvent::CertParsed {
+            digest: digests[i],
+            l1_block: block_info.number,
+            batcher_addr: BATCHER_ADDRESS,
+        };
+        let cert_discarded = |i: usize, reason: DiscardReason| DerivationEvent::CertDiscarded {
+            reason,
+            digest: digests[i],
+            l1_block: block_info.number,
+            batcher_addr: BATCHER_ADDRESS,
+        };
+
+        struct Case {
+            validity: Result<bool, TestHokuleaProviderError>,
+            encoded_payload: Result<EncodedPayload, TestHokuleaProviderError>,
+        }
+
+        struct Scenario {
+            recency_policy: RecencyWindows,
+            cases: Vec<Case>,
+            events: Vec<DerivationEvent>,
+        }
+
+        let scenarios = [
+            // drop invalid, drop not decodable, take
+            Scenario {
+                recency_policy: RecencyWindows::default(),
+                cases: vec![
+                    Case {
+                        validity: Ok(false),
+                        encoded_payload: Ok(encoded_payloads[0].clone()),
+                    },
+                    Case {
+                        validity: Ok(true),
+                        encoded_payload: Ok(NOT_DECODABLE_ENCODED_PAYLOAD),
+                    },
+                    Case {
+                        validity: Ok(true),
+                        encoded_payload: Ok(encoded_payloads[2].clone()),
+                    },
+                ],
+                events: vec![
+                    cert_parsed(0),
+                    cert_discarded(0, DiscardReason::InvalidCert),
+                    cert_parsed(1),
+                    cert_discarded(
+                        1,
+                        DiscardReason::Decoding(HokuleaStatelessError::DecodingError(
+                            EncodedPayloadDecodingError::PayloadTooShortForHeader {
+                                expected: 32,
+                                actual: 0,
+                            },
+                        )),
+                    ),
+                    cert_parsed(2),
+                    DerivationEvent::PayloadDecoded {
+                        len: payload_len,
+                        digest: digests[2],
+                        l1_block: block_info.number,
+                    },
+                ],
+            },
+            // the first cert with rbn 9300876 is not recent, drop it, drop invalid, take
+            Scenario {
+                recency_policy: RecencyWindows::default().with_v3_recency_window(100000),
+                cases: vec![
+                    Case {
+                        validity: Ok(true),
+                        encoded_payload: Ok(encoded_payloads[0].clone()),
+                    },
+                    Case {
+                        validity: Ok(false),
+                        encoded_payload: Ok(encoded_payloads[1].clone()),
+                    },
+                    Case {
+                        validity: Ok(true),
+                        encoded_payload: Ok(encoded_payloads[2].clone()),
+                    },
+                ],
+                events: vec![
+                    cert_parsed(0),
+                    cert_discarded(0, DiscardReason::NotRecentCert),
+                    cert_parsed(1),
+                    cert_discarded(1, DiscardReason::InvalidCert),
+                    cert_parsed(2),
+                    DerivationEvent::PayloadDecoded {
+                        len: payload_len,
+                        digest: digests[2],
+                        l1_block: block_info.number,
+                    },
+                ],
+            },
+        ];
+
+        for scenario in scenarios {
+            let sink = Arc::new(TestDerivationEventSink::default());
+            let mut source = default_test_eigenda_data_source().with_event_sink(sink.clone());
+            source.eigenda_source.recency_policy = Arc::new(scenario.recency_policy);
+            configure_chain_provider_with_txs(&mut source, 3, &block_info);
+            let (validities, encoded_payloads) = scenario
+                .cases
+                .into_iter()
+                .map(|case| (case.validity, case.encoded_payload))
+                .unzip();
+            set_eigenda_preimage_provider_value(
+                &mut source,
+                altda_commitments.clone(),
+                validities,
+                encoded_payloads,
+            );
+
+            assert!(source.next(&block_info, BATCHER_ADDRESS).await.is_ok());
+            assert!(matches!(
+                source.next(&block_info, BATCHER_ADDRESS).await.unwrap_err(),
+                PipelineErrorKind::Temporary(PipelineError::Eof),
+            ));
+            assert_eq!(*sink.events.lock().unwrap(), scenario.events);
+        }
+    }
+
+    #[test]
+    fn test_next_with_many_discarded_altda_commitments() {
+        const NUM_INVALID_ALTDA_COMMITMENTS: usize = 10_000;
+        // derive on a thread with a bounded stack, discarding altda commitments recursively would
+        // overflow it
+        std::thread::Builder::new()
+            .stack_size(1024 * 1024)
+            .spawn(|| {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .build()
+                    .unwrap();
+                runtime.block_on(async {
+                    let sink = Arc::new(TestDerivationEventSink::default());
+                    let mut source =
+                        default_test_eigenda_data_source().with_event_sink(sink.clone());
+                    let block_info = BLOCK_INFO;
+                    source.ethereum_source.blob_source.batcher_address = L1_INBOX_ADDRESS;
+                    let (txs, altda_commitments, _) =
+                        valid_eip1559_txs_with_altda_commitment_encoded_payload(1);
+                    // the batcher posts the same invalid altda commitment over and over
+                    source
+                        .ethereum_source
+                        .blob_source
+                        .chain_provider
+                        .insert_block_with_transactions(
+                            block_info.number,
+                            block_info,
+                            vec![txs[0].clone(); NUM_INVALID_ALTDA_COMMITMENTS],
+                        );
+                    source
+                        .eigenda_source
+                        .eigenda_fetcher
+                        .insert_validity(&altda_commitments[0], Ok(false));
+
+                    // all data is discarded in a single call, drives until EOF
+                    assert!(matches!(
+                        source.next(&block_info, BATCHER_ADDRESS).await.unwrap_err(),
+                        PipelineErrorKind::Temporary(PipelineError::Eof),
+                    ));
+                    let num_discarded = sink
+                        .events
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .filter(|e| matches!(e, DerivationEvent::CertDiscarded { .. }))
+                        .count();
+                    assert_eq!(num_discarded, NUM_INVALID_ALTDA_COMMITMENTS);
+                });
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
     // (ToDo) add V4 cert to test e2e integration, so that some altda commitment can fail due to recency issue
+
+    #[tokio::test]
+    async fn test_next_frame_eigenda() {
+        let mut source = default_test_eigenda_data_source();
+        let block_info = BLOCK_INFO;
+        let (_, altda_commitments, encoded_payloads) =
+            configure_chain_provider_with_txs(&mut source, 2, &block_info);
+        set_eigenda_preimage_provider_value(
+            &mut source,
+            altda_commitments.clone(),
+            vec![Ok(true), Ok(true)],
+            vec![
+                Ok(encoded_payloads[0].clone()),
+                Ok(encoded_payloads[1].clone()),
+            ],
+        );
+
+        for i in 0..2 {
+            let frame = source
+                .next_frame(&block_info, BATCHER_ADDRESS)
+                .await
+                .expect("should be ok");
+            assert_eq!(
+                frame,
+                DerivedFrame::EigenDA {
+                    digest: altda_commitments[i].to_digest(),
+                    payload: encoded_payloads[i].decode().unwrap(),
+                }
+            );
+        }
+        assert!(matches!(
+            source.next_frame(&block_info, BATCHER_ADDRESS).await,
+            Err(PipelineErrorKind::Temporary(PipelineError::Eof))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_next_frame_ethereum() {
+        struct Case {
+            name: &'static str,
+            ecotone_time: Option<u64>,
+        }
+        let cases = [
+            Case {
+                name: "calldata of blob source after ecotone",
+                ecotone_time: Some(0),
+            },
+            Case {
+                name: "calldata source before ecotone",
+                ecotone_time: None,
+            },
+        ];
+
+        let inbox_address =
+            alloy_primitives::address!("0xffeeddccbbaa0000000000000000000000000000");
+        let batcher_address =
+            alloy_primitives::address!("0x2f40d796917ffb642bd2e2bdd2c762a5e40fd749");
+        let block_info = BlockInfo::default();
+        let txs = [
+            valid_eip1559_txs_with_ethda(),
+            valid_eip1559_txs_with_ethda(),
+        ]
+        .concat();
+        let expected = txs[0].input().clone();
+
+        for case in cases {
+            let mut chain = TestChainProvider::default();
+            chain.insert_block_with_transactions(block_info.number, block_info, txs.clone());
+            let blob = BlobSource::new(chain.clone(), TestBlobProvider::default(), inbox_address);
+            let calldata = CalldataSource::new(chain, inbox_address);
+            let cfg = RollupConfig {
+                hardforks: HardForkConfig {
+                    ecotone_time: case.ecotone_time,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let mut source = EigenDADataSource::new(
+                EthereumDataSource::new(blob, calldata, &cfg),
+                default_test_preimage_source(),
+            );
+
+            for _ in 0..2 {
+                let frame = source
+                    .next_frame(&block_info, batcher_address)
+                    .await
+                    .expect("should be ok");
+                assert_eq!(
+                    frame,
+                    DerivedFrame::Ethereum(expected.clone()),
+                    "{}",
+                    case.name
+                );
+            }
+            assert!(
+                matches!(
+                    source.next_frame(&block_info, batcher_address).await,
+                    Err(PipelineErrorKind::Temporary(PipelineError::Eof))
+                ),
+                "{}",
+                case.name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next_with_keccak_altda_commitment() {
+        let mut source = default_test_eigenda_data_source().with_keccak_commitments(true);
+        let block_info = BLOCK_INFO;
+        // input data posted to the DA server, addressed by its keccak256 hash
+        let input_data: Bytes = vec![0u8, 1, 2, 3].into();
+        let commitment = alloy_primitives::keccak256(&input_data);
+        // 0x01 (OP derivation version byte) ++ keccak commitment
+        let calldata: Bytes = [
+            &[ALTDA_DERIVATION_VERSION, KECCAK_COMMITMENT_TYPE],
+            commitment.as_slice(),
+        ]
+        .concat()
+        .into();
+        source.ethereum_source.blob_source.open = true;
+        source.ethereum_source.blob_source.data = vec![
+            BlobData {
+                data: None,
+                calldata: Some(calldata),
+            };
+            2
+        ];
+        source
+            .eigenda_source
+            .eigenda_fetcher
+            .insert_keccak_preimage(commitment, Ok(input_data.clone()));
+
+        // keep the keccak commitment when the preimage provider encounters a temporary error
+        source.eigenda_source.eigenda_fetcher.should_preimage_err = true;
+        assert!(matches!(
+            source.next(&block_info, BATCHER_ADDRESS).await,
+            Err(PipelineErrorKind::Temporary(_))
+        ));
+        assert_eq!(
+            source.altda_commitment,
+            Some(GenericAltDACommitment::Keccak(commitment))
+        );
+
+        source.eigenda_source.eigenda_fetcher.should_preimage_err = false;
+        assert_eq!(
+            source.next(&block_info, BATCHER_ADDRESS).await,
+            Ok(input_data.clone())
+        );
+        assert!(source.altda_commitment.is_none());
+
+        assert_eq!(
+            source.next_frame(&block_info, BATCHER_ADDRESS).await,
+            Ok(DerivedFrame::AltDAKeccak {
+                commitment,
+                data: input_data,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_next_discards_keccak_altda_commitment_by_default() {
+        let block_info = BLOCK_INFO;
+        let commitment = B256::repeat_byte(0xab);
+        let keccak_calldata: Bytes = [
+            &[ALTDA_DERIVATION_VERSION, KECCAK_COMMITMENT_TYPE],
+            commitment.as_slice(),
+        ]
+        .concat()
+        .into();
+        let ethereum_calldata: Bytes = vec![0x00, 0xaa].into();
+
+        let sink = Arc::new(TestDerivationEventSink::default());
+        let mut source = default_test_eigenda_data_source().with_event_sink(sink.clone());
+        source.ethereum_source.blob_source.open = true;
+        source.ethereum_source.blob_source.data = [keccak_calldata, ethereum_calldata.clone()]
+            .into_iter()
+            .map(|calldata| BlobData {
+                data: None,
+                calldata: Some(calldata),
+            })
+            .collect();
+
+        // the keccak commitment is skipped without querying the preimage provider
+        assert_eq!(
+            source.next(&block_info, BATCHER_ADDRESS).await,
+            Ok(ethereum_calldata)
+        );
+        assert!(sink
+            .events
+            .lock()
+            .unwrap()
+            .contains(&DerivationEvent::CertDiscarded {
+                reason: DiscardReason::KeccakCommitmentDisabled,
+                digest: GenericAltDACommitment::Keccak(commitment).to_digest(),
+                l1_block: block_info.number,
+                batcher_addr: BATCHER_ADDRESS,
+            }));
+    }
+
+    #[tokio::test]
+    async fn test_next_frame_mixed_da_layers() {
+        let block_info = BLOCK_INFO;
+        let (_, altda_commitments, encoded_payloads) =
+            valid_eip1559_txs_with_altda_commitment_encoded_payload(1);
+        let eigenda_calldata: Bytes = [
+            &[ALTDA_DERIVATION_VERSION][..],
+            &alloy_primitives::hex::decode(ALTDA_COMMITMENT_BYTES_1).unwrap(),
+        ]
+        .concat()
+        .into();
+        // 0x01 (OP derivation version byte) ++ 0x01 (generic commitment) ++ da_layer_byte ++ commitment
+        let da_layer_calldata = |da_layer_byte: u8, commitment: &[u8]| -> Bytes {
+            [
+                &[
+                    ALTDA_DERIVATION_VERSION,
+                    GENERIC_COMMITMENT_TYPE,
+                    da_layer_byte,
+                ][..],
+                commitment,
+            ]
+            .concat()
+            .into()
+        };
+        let da_layer_payload: Bytes = vec![0xde, 0xad, 0xbe, 0xef].into();
+
+        let mut handler = test_utils::TestDaLayerHandler::new(7);
+        handler.insert_payload(vec![0x0a].into(), Ok(Ok(da_layer_payload.clone())));
+        handler.insert_payload(vec![0x0b].into(), Ok(Err(DiscardReason::InvalidCert)));
+        let mut da_layers = DaLayerRegistry::default();
+        da_layers.register(handler).unwrap();
+
+        let sink = Arc::new(TestDerivationEventSink::default());
+        let mut source = default_test_eigenda_data_source()
+            .with_da_layers(da_layers)
+            .with_event_sink(sink.clone());
+        set_eigenda_preimage_provider_value(
+            &mut source,
+            altda_commitments.clone(),
+            vec![Ok(true)],
+            vec![Ok(encoded_payloads[0].clone())],
+        );
+        source.ethereum_source.blob_source.open = true;
+        source.ethereum_source.blob_source.data = [
+            eigenda_calldata,
+            // no handler is registered for da_layer_byte 9
+            da_layer_calldata(9, &[0x0a]),
+            da_layer_calldata(7, &[0x0a]),
+            // discarded by the handler
+            da_layer_calldata(7, &[0x0b]),
+        ]
+        .into_iter()
+        .map(|calldata| BlobData {
+            data: None,
+            calldata: Some(calldata),
+        })
+        .collect();
+
+        assert_eq!(
+            source.next_frame(&block_info, BATCHER_ADDRESS).await,
+            Ok(DerivedFrame::EigenDA {
+                digest: altda_commitments[0].to_digest(),
+                payload: encoded_payloads[0].decode().unwrap(),
+            })
+        );
+        assert_eq!(
+            source.next_frame(&block_info, BATCHER_ADDRESS).await,
+            Ok(DerivedFrame::DaLayer {
+                da_layer_byte: 7,
+                payload: da_layer_payload,
+            })
+        );
+        assert_eq!(
+            source.next_frame(&block_info, BATCHER_ADDRESS).await,
+            Err(PipelineErrorKind::Temporary(PipelineError::Eof))
+        );
+
+        let discarded_reasons: Vec<DiscardReason> = sink
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|event| match event {
+                DerivationEvent::CertDiscarded { reason, .. } => Some(reason.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            discarded_reasons,
+            vec![
+                DiscardReason::UnsupportedDaLayer(9),
+                DiscardReason::InvalidCert
+            ]
+        );
+    }
 }
@@ -1,18 +1,24 @@
 //! Contains Kona and EigenDA blob derivation pipeline. Typically rollup or
 //! proving stack use their own derivation pipeline with customization.
 
+use crate::payload_codec::{PayloadCodec, PayloadCodecRegistry, PayloadCodecV0};
 use crate::{
-    errors::{EncodedPayloadDecodingError, HokuleaStatelessError},
+    errors::{EncodedPayloadDecodingError, EncodedPayloadEncodingError, HokuleaStatelessError},
     BYTES_PER_FIELD_ELEMENT,
 };
 use crate::{ENCODED_PAYLOAD_HEADER_LEN_BYTES, PAYLOAD_ENCODING_VERSION_0};
 use alloc::vec;
 use alloy_primitives::Bytes;
+use core::cmp::max;
 use serde::{Deserialize, Serialize};
 
 /// Represents raw payload bytes, alias
 pub type Payload = Bytes;
 
+/// Number of payload bytes carried by a single field element under PAYLOAD_ENCODING_VERSION_0,
+/// the first byte of every field element is reserved to be 0x00
+pub(crate) const PAYLOAD_BYTES_PER_FIELD_ELEMENT: usize = BYTES_PER_FIELD_ELEMENT - 1;
+
 #[derive(Default, Clone, Debug, Deserialize, Serialize, PartialEq)] //
 #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)] //
 // [EigenDAWitness] requires serde for EncodedPayload
@@ -37,6 +43,37 @@ impl EncodedPayload {
         &self.encoded_payload
     }
 
+    /// Encodes raw rollup payload bytes into an [EncodedPayload]. It is the inverse of
+    /// [EncodedPayload::decode], and uses the smallest power of 2 number of field elements
+    /// that can hold the payload. Use [EncodedPayloadBuilder] to control the resulting blob length.
+    ///
+    /// See spec <https://layr-labs.github.io/eigenda/integration/spec/3-data-structs.html#encoding-payload-version-0x0>
+    pub fn encode(
+        payload: &[u8],
+        payload_encoding_version: u8,
+    ) -> Result<Self, EncodedPayloadEncodingError> {
+        Self::encode_with(
+            payload,
+            payload_encoding_version,
+            &PayloadCodecRegistry::default(),
+        )
+    }
+
+    /// Same as [EncodedPayload::encode], but looks up the payload encoding version among the
+    /// codecs of the registry.
+    pub fn encode_with(
+        payload: &[u8],
+        payload_encoding_version: u8,
+        codecs: &PayloadCodecRegistry,
+    ) -> Result<Self, EncodedPayloadEncodingError> {
+        let codec = codecs.get(payload_encoding_version).ok_or(
+            EncodedPayloadEncodingError::UnknownEncodingVersion(payload_encoding_version),
+        )?;
+        EncodedPayloadBuilder::new(payload)
+            .with_codec(codec)
+            .build()
+    }
+
     /// Returns the number of field elements in the encoded payload
     pub fn num_field_element(&self) -> u64 {
         (self.encoded_payload.len() / BYTES_PER_FIELD_ELEMENT) as u64
@@ -79,9 +116,9 @@ impl EncodedPayload {
         Ok(())
     }
 
-    /// Validates the header (first field element = 32 bytes) of the encoded payload,
-    /// and returns the claimed length of the payload if the header is valid.
-    fn decode_header(&self) -> Result<u32, HokuleaStatelessError> {
+    /// Validates the PayloadEncodingVersion0 header (first field element = 32 bytes) of the encoded
+    /// payload, and returns the claimed length of the payload if the header is valid.
+    pub(crate) fn decode_header(&self) -> Result<u32, HokuleaStatelessError> {
         if self.encoded_payload.len() < ENCODED_PAYLOAD_HEADER_LEN_BYTES {
             return Err(EncodedPayloadDecodingError::PayloadTooShortForHeader {
                 expected: ENCODED_PAYLOAD_HEADER_LEN_BYTES,
@@ -120,9 +157,12 @@ impl EncodedPayload {
         Ok(payload_length)
     }
 
-    /// Decodes the payload from the encoded payload bytes.
+    /// Decodes the PayloadEncodingVersion0 payload from the encoded payload bytes.
     /// Removes internal padding and extracts the payload data based on the claimed length.
-    fn decode_payload(&self, payload_len: u32) -> Result<Payload, HokuleaStatelessError> {
+    pub(crate) fn decode_payload(
+        &self,
+        payload_len: u32,
+    ) -> Result<Payload, HokuleaStatelessError> {
         let body = self
             .encoded_payload
             .slice(ENCODED_PAYLOAD_HEADER_LEN_BYTES..);
@@ -151,20 +191,116 @@ impl EncodedPayload {
         Ok(decoded_body.slice(0..payload_len as usize))
     }
 
-    /// Decodes the encoded payload into raw byte data. Reverse of the encode function below
+    /// Decodes the encoded payload into raw byte data. Reverse of [EncodedPayload::encode].
     /// Returns a [EncodedPayloadDecodingError] if the encoded payload is invalid.
     ///
-    /// Applies the inverse of PayloadEncodingVersion0 to an EncodedPayload, and returns the decoded payload.
+    /// Only the built-in PayloadEncodingVersion0 is supported, see [EncodedPayload::decode_with]
+    /// for other payload encoding versions.
     pub fn decode(&self) -> Result<Payload, HokuleaStatelessError> {
-        // Check length invariant
+        self.decode_with(&PayloadCodecRegistry::default())
+    }
+
+    /// Decodes the encoded payload into raw byte data with the codec registered under the version
+    /// byte of the encoded payload header.
+    /// Returns a [EncodedPayloadDecodingError] if the encoded payload is invalid.
+    pub fn decode_with(
+        &self,
+        codecs: &PayloadCodecRegistry,
+    ) -> Result<Payload, HokuleaStatelessError> {
+        self.codec(codecs)?.decode(self)
+    }
+
+    /// Streams the payload bytes into `out`. It accepts and rejects exactly the same encoded
+    /// payloads as [EncodedPayload::decode], but on error `out` may already contain part of the
+    /// payload. The built-in PayloadEncodingVersion0 is decoded without allocating intermediate
+    /// buffers.
+    pub fn decode_into(&self, out: &mut vec::Vec<u8>) -> Result<(), HokuleaStatelessError> {
+        self.decode_into_with(&PayloadCodecRegistry::default(), out)
+    }
+
+    /// Same as [EncodedPayload::decode_into], but looks up the payload encoding version among the
+    /// codecs of the registry, see [PayloadCodec::decode_into].
+    pub fn decode_into_with(
+        &self,
+        codecs: &PayloadCodecRegistry,
+        out: &mut vec::Vec<u8>,
+    ) -> Result<(), HokuleaStatelessError> {
+        self.codec(codecs)?.decode_into(self, out)
+    }
+
+    /// Checks the invariants common to every payload encoding version, and returns the codec
+    /// registered under the version byte of the encoded payload header.
+    fn codec<'c>(
+        &self,
+        codecs: &'c PayloadCodecRegistry,
+    ) -> Result<&'c dyn PayloadCodec, HokuleaStatelessError> {
+        // Check length invariant, which also ensures there is a header
+        self.check_len_invariant()?;
+
+        // this ensures the header 32 bytes is a valid field element
+        if self.encoded_payload[0] != 0x00 {
+            return Err(EncodedPayloadDecodingError::InvalidHeaderFirstByte(
+                self.encoded_payload[0],
+            )
+            .into());
+        }
+
+        let version = self.encoded_payload[1];
+        Ok(codecs
+            .get(version)
+            .ok_or(EncodedPayloadDecodingError::UnknownEncodingVersion(version))?)
+    }
+
+    /// Returns an iterator over the payload bytes of a PayloadEncodingVersion0 encoded payload,
+    /// without copying them out of the encoded payload. The length invariant and the header are
+    /// validated upfront, the body is validated while iterating, see [DecodedChunks].
+    ///
+    /// The layout of the chunks is specific to PayloadEncodingVersion0, encoded payloads of any
+    /// other version are rejected with [EncodedPayloadDecodingError::UnknownEncodingVersion],
+    /// even if a codec is registered for it. Use [EncodedPayload::decode_into_with] instead.
+    pub fn decoded_chunks(&self) -> Result<DecodedChunks<'_>, HokuleaStatelessError> {
         self.check_len_invariant()?;
+        // rejects the header of any version but PayloadEncodingVersion0
+        let payload_len = self.decode_header()?;
+        Ok(DecodedChunks {
+            body: &self.encoded_payload[ENCODED_PAYLOAD_HEADER_LEN_BYTES..],
+            tail: &[],
+            remaining: payload_len as usize,
+            claimed: payload_len,
+            done: false,
+        })
+    }
 
-        // Decode header to get claimed payload length
-        let payload_len_in_header = self.decode_header()?;
-        debug!(target: "eigenda-datasource", "rollup payload length in bytes {:?}", payload_len_in_header);
+    /// Streams the payload bytes of a PayloadEncodingVersion0 encoded payload into `out`, without
+    /// allocating intermediate buffers. Used by [PayloadCodecV0] for [PayloadCodec::decode_into].
+    pub(crate) fn stream_payload_v0(
+        &self,
+        out: &mut vec::Vec<u8>,
+    ) -> Result<(), HokuleaStatelessError> {
+        let mut chunks = self.decoded_chunks()?;
+        // fast path over the field elements entirely filled with payload bytes, the first
+        // invalid first byte met here is also the first one met by decode
+        let num_full = (chunks.remaining / PAYLOAD_BYTES_PER_FIELD_ELEMENT)
+            .min(chunks.body.len() / BYTES_PER_FIELD_ELEMENT);
+        let (full, rest) = chunks.body.split_at(num_full * BYTES_PER_FIELD_ELEMENT);
+        for field_element in full.chunks_exact(BYTES_PER_FIELD_ELEMENT) {
+            if field_element[0] != 0x00 {
+                return Err(
+                    EncodedPayloadDecodingError::InvalidFirstByteFieldElementPadding(
+                        field_element[0],
+                    )
+                    .into(),
+                );
+            }
+            out.extend_from_slice(&field_element[1..]);
+        }
+        chunks.body = rest;
+        chunks.remaining -= num_full * PAYLOAD_BYTES_PER_FIELD_ELEMENT;
 
-        // Decode payload using the helper method
-        self.decode_payload(payload_len_in_header)
+        for chunk in chunks {
+            out.extend_from_slice(chunk?);
+        }
+        Ok(())
     }
 
     /// check_and_remove_zero_padding_for_field_elements checks if the first byte of every mulitple of 32 bytes is 0x00,
@@ -182,7 +318,8 @@ impl EncodedPayload {
         }
 
         let num_field_elements = encoded_body.len() / BYTES_PER_FIELD_ELEMENT;
-        let mut decoded_body = vec::Vec::with_capacity(num_field_elements * 31);
+        let mut decoded_body =
+            vec::Vec::with_capacity(num_field_elements * PAYLOAD_BYTES_PER_FIELD_ELEMENT);
         for chunk in encoded_body.chunks_exact(BYTES_PER_FIELD_ELEMENT) {
             if chunk[0] != 0x00 {
                 return Err(
@@ -190,69 +327,193 @@ impl EncodedPayload {
                         .into(),
                 );
             }
-            decoded_body.extend_from_slice(&chunk[1..32]);
+            decoded_body.extend_from_slice(&chunk[1..BYTES_PER_FIELD_ELEMENT]);
         }
         Ok(Bytes::from(decoded_body))
     }
 }
 
-/// Utility function to check if a number is a power of two
-fn is_power_of_two(n: usize) -> bool {
-    n != 0 && (n & (n - 1)) == 0
+/// An iterator over the payload bytes of a PayloadEncodingVersion0 encoded payload, created by
+/// [EncodedPayload::decoded_chunks]. Every item borrows the payload bytes, up to 31, of one field
+/// element of the encoded payload body.
+///
+/// The body is validated lazily, in the same order as [EncodedPayload::decode]: the first byte of
+/// every field element must be 0x00, the body must hold the claimed payload length, and all
+/// bytes after the payload must be zero. An error is yielded at most once, after which the
+/// iterator is exhausted. Bytes yielded before an error must not be trusted, hence the iterator
+/// must be drained to completion.
+#[derive(Debug, Clone)]
+pub struct DecodedChunks<'a> {
+    /// field elements of the body which have not been yielded
+    body: &'a [u8],
+    /// padding bytes of the last yielded field element
+    tail: &'a [u8],
+    /// number of payload bytes which have not been yielded
+    remaining: usize,
+    /// payload length claimed in the header
+    claimed: u32,
+    /// whether the iterator is exhausted
+    done: bool,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use alloc::vec;
-    use alloy_primitives::Bytes;
-    use rust_kzg_bn254_primitives::helpers;
-
-    /// The encode function accepts an input of opaque rollup data array into an [EncodedPayload].
-    /// [EncodedPayload] contains a header of 32 bytes and a transformation of input data
-    /// The 0 index byte of header is always 0, to comply to bn254 field element constraint
-    /// The 1 index byte of header is proxy encoding version.
-    /// The 2-5(inclusive) indices of header are storing the length of the input rollup data in big endien
-    /// The payload is prepared by padding an empty byte for every 31 bytes from the rollup data
-    /// This matches exactly the eigenda proxy implementation, whose logic is in
-    /// <https://github.com/Layr-Labs/eigenda/blob/master/encoding/utils/codec/codec.go#L12>
-    ///
-    /// The length of (header + payload) by the encode function is always multiple of 32
-    /// The eigenda proxy does not take such constraint.
-    /// See also spec <https://layr-labs.github.io/eigenda/integration/spec/3-data-structs.html#encoding-payload-version-0x0>
-    /// for the exact encoding methods.
-    fn encode(rollup_data: &[u8], payload_encoding_version: u8) -> EncodedPayload {
-        let rollup_data_size = rollup_data.len() as u32;
+impl<'a> DecodedChunks<'a> {
+    /// Validates the field elements which come after the payload. All first bytes are checked
+    /// before the padding bytes, to return the same error as [EncodedPayload::decode].
+    fn check_padding(&self) -> Result<(), HokuleaStatelessError> {
+        for field_element in self.body.chunks_exact(BYTES_PER_FIELD_ELEMENT) {
+            if field_element[0] != 0x00 {
+                return Err(
+                    EncodedPayloadDecodingError::InvalidFirstByteFieldElementPadding(
+                        field_element[0],
+                    )
+                    .into(),
+                );
+            }
+        }
+        let padding = self
+            .body
+            .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+            .map(|field_element| &field_element[1..]);
+        for bytes in core::iter::once(self.tail).chain(padding) {
+            // compare whole field elements, and only look for the offending byte on failure
+            if bytes.iter().fold(0, |acc, b| acc | b) != 0x00 {
+                let b = bytes
+                    .iter()
+                    .find(|b| **b != 0x00)
+                    .copied()
+                    .unwrap_or_default();
+                return Err(
+                    EncodedPayloadDecodingError::InvalidEncodedPayloadBodyPadding(b).into(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
 
-        let padded_rollup_data = helpers::pad_payload(rollup_data);
+impl<'a> Iterator for DecodedChunks<'a> {
+    type Item = Result<&'a [u8], HokuleaStatelessError>;
 
-        let min_blob_payload_size = padded_rollup_data.len();
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
 
-        // the first field element contains the header
-        let blob_size = min_blob_payload_size + BYTES_PER_FIELD_ELEMENT;
+        if self.remaining == 0 {
+            self.done = true;
+            return self.check_padding().err().map(Err);
+        }
 
-        // round up to the closest multiple of 32
-        let blob_size = blob_size.div_ceil(BYTES_PER_FIELD_ELEMENT) * BYTES_PER_FIELD_ELEMENT;
+        let Some((field_element, body)) = self.body.split_at_checked(BYTES_PER_FIELD_ELEMENT)
+        else {
+            // the body is exhausted before reaching the claimed length, the first bytes of all
+            // field elements have already been checked
+            self.done = true;
+            return Some(Err(
+                EncodedPayloadDecodingError::DecodedPayloadBodyTooShort {
+                    actual: self.claimed as usize - self.remaining,
+                    claimed: self.claimed,
+                }
+                .into(),
+            ));
+        };
 
-        let mut encoded_payload = vec![0u8; blob_size as usize];
+        if field_element[0] != 0x00 {
+            self.done = true;
+            return Some(Err(
+                EncodedPayloadDecodingError::InvalidFirstByteFieldElementPadding(field_element[0])
+                    .into(),
+            ));
+        }
 
-        encoded_payload[1] = payload_encoding_version;
-        // encode length as uint32
-        encoded_payload[2..6].copy_from_slice(&rollup_data_size.to_be_bytes());
+        let (chunk, tail) =
+            field_element[1..].split_at(self.remaining.min(PAYLOAD_BYTES_PER_FIELD_ELEMENT));
+        self.body = body;
+        self.tail = tail;
+        self.remaining -= chunk.len();
+        Some(Ok(chunk))
+    }
+}
 
-        encoded_payload
-            [BYTES_PER_FIELD_ELEMENT..(BYTES_PER_FIELD_ELEMENT + min_blob_payload_size as usize)]
-            .copy_from_slice(&padded_rollup_data);
+/// Builds an [EncodedPayload] from raw rollup payload bytes, the same way a batcher (via eigenda
+/// proxy) does before dispersing a blob to EigenDA. The payload is encoded by a [PayloadCodec],
+/// [PayloadCodecV0] unless specified otherwise, then zero padded up to a power of 2 number of
+/// field elements.
+#[derive(Debug, Clone)]
+pub struct EncodedPayloadBuilder<'a> {
+    /// raw rollup payload
+    payload: &'a [u8],
+    /// codec used to encode the payload
+    codec: &'a dyn PayloadCodec,
+    /// lower bound on the number of field elements of the resulting blob
+    min_num_field_elements: u64,
+}
 
-        EncodedPayload {
-            encoded_payload: Bytes::from(encoded_payload),
+impl<'a> EncodedPayloadBuilder<'a> {
+    /// Creates a builder for the payload, using PAYLOAD_ENCODING_VERSION_0
+    pub const fn new(payload: &'a [u8]) -> Self {
+        Self {
+            payload,
+            codec: &PayloadCodecV0,
+            min_num_field_elements: 0,
         }
     }
 
+    /// Sets the codec used to encode the payload
+    pub const fn with_codec(mut self, codec: &'a dyn PayloadCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Requires the resulting blob to have at least this many field elements, for example to
+    /// match the blob length committed in a DA cert. The value is rounded up to a power of 2.
+    pub const fn with_min_num_field_elements(mut self, min_num_field_elements: u64) -> Self {
+        self.min_num_field_elements = min_num_field_elements;
+        self
+    }
+
+    /// Returns the blob length, in number of field elements, of the encoded payload produced by
+    /// [EncodedPayloadBuilder::build]. It is always a power of 2, and corresponds to the length
+    /// of the blob commitment inside a DA cert.
+    pub fn blob_length(&self) -> u64 {
+        max(
+            self.codec.num_field_elements(self.payload.len()),
+            self.min_num_field_elements,
+        )
+        .next_power_of_two()
+    }
+
+    /// Returns the length in bytes of the encoded payload produced by [EncodedPayloadBuilder::build]
+    pub fn encoded_payload_len(&self) -> usize {
+        self.blob_length() as usize * BYTES_PER_FIELD_ELEMENT
+    }
+
+    /// Encodes the payload into an [EncodedPayload]
+    pub fn build(&self) -> Result<EncodedPayload, EncodedPayloadEncodingError> {
+        let mut encoded_payload = vec![0u8; self.encoded_payload_len()];
+        self.codec.encode_into(self.payload, &mut encoded_payload)?;
+        Ok(EncodedPayload {
+            encoded_payload: Bytes::from(encoded_payload),
+        })
+    }
+}
+
+/// Utility function to check if a number is a power of two
+fn is_power_of_two(n: usize) -> bool {
+    n != 0 && (n & (n - 1)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloy_primitives::Bytes;
+
     #[test]
     fn test_encode_and_decode_success() {
         let rollup_data = vec![1, 2, 3, 4];
-        let encoded_payload = encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+        let encoded_payload =
+            EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0).unwrap();
         let data_len = encoded_payload.encoded_payload.len();
         assert!(data_len.is_multiple_of(BYTES_PER_FIELD_ELEMENT) && data_len != 0);
 
@@ -264,7 +525,8 @@ mod tests {
     #[test]
     fn test_encode_and_decode_success_empty() {
         let rollup_data = vec![];
-        let encoded_payload = encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+        let encoded_payload =
+            EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0).unwrap();
         let data_len = encoded_payload.encoded_payload.len();
         // 32 byte is encoded payload header size
         assert!(data_len == 32);
@@ -277,7 +539,8 @@ mod tests {
     #[test]
     fn test_encode_and_decode_error_invalid_length() {
         let rollup_data = vec![1, 2, 3, 4];
-        let mut encoded_payload = encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+        let mut encoded_payload =
+            EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0).unwrap();
         encoded_payload.encoded_payload.truncate(33);
         let result = encoded_payload.decode();
         assert!(result.is_err());
@@ -287,10 +550,134 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_encode_unknown_version() {
+        assert_eq!(
+            EncodedPayload::encode(&[1, 2, 3, 4], 1),
+            Err(EncodedPayloadEncodingError::UnknownEncodingVersion(1))
+        );
+    }
+
+    #[test]
+    fn test_encoded_payload_builder() {
+        struct Case {
+            payload_len: usize,
+            min_num_field_elements: u64,
+            blob_length: u64,
+        }
+        let cases = [
+            // header only
+            Case {
+                payload_len: 0,
+                min_num_field_elements: 0,
+                blob_length: 1,
+            },
+            // header and one field element
+            Case {
+                payload_len: 31,
+                min_num_field_elements: 0,
+                blob_length: 2,
+            },
+            // header and two field elements, rounded up to power of 2
+            Case {
+                payload_len: 32,
+                min_num_field_elements: 0,
+                blob_length: 4,
+            },
+            // minimal blob length is respected
+            Case {
+                payload_len: 32,
+                min_num_field_elements: 16,
+                blob_length: 16,
+            },
+            // minimal blob length is rounded up to power of 2
+            Case {
+                payload_len: 32,
+                min_num_field_elements: 9,
+                blob_length: 16,
+            },
+            // minimal blob length smaller than needed by the payload is ignored
+            Case {
+                payload_len: 1000,
+                min_num_field_elements: 2,
+                blob_length: 64,
+            },
+        ];
+
+        for case in cases {
+            let rollup_data: vec::Vec<u8> = (0..case.payload_len).map(|i| i as u8).collect();
+            let builder = EncodedPayloadBuilder::new(&rollup_data)
+                .with_min_num_field_elements(case.min_num_field_elements);
+            assert_eq!(builder.blob_length(), case.blob_length);
+            assert_eq!(
+                builder.encoded_payload_len(),
+                case.blob_length as usize * BYTES_PER_FIELD_ELEMENT
+            );
+
+            let encoded_payload = builder.build().unwrap();
+            assert_eq!(encoded_payload.num_field_element(), case.blob_length);
+            assert_eq!(encoded_payload.decode(), Ok(Bytes::from(rollup_data)));
+        }
+    }
+
+    #[test]
+    fn test_decode_into_matches_decode() {
+        let mut inputs = vec![];
+        for payload_len in [0, 1, 30, 31, 32, 62, 100, 1000] {
+            let rollup_data: vec::Vec<u8> = (0..payload_len).map(|i| (i % 255 + 1) as u8).collect();
+            let encoded_payload = EncodedPayloadBuilder::new(&rollup_data)
+                .with_min_num_field_elements(8)
+                .build()
+                .unwrap();
+            inputs.push(encoded_payload.encoded_payload.to_vec());
+        }
+        // corrupt every byte of an encoded payload whose last field elements are padding
+        let valid = inputs[6].clone();
+        for i in 0..valid.len() {
+            let mut corrupted = valid.clone();
+            corrupted[i] = 0xff;
+            inputs.push(corrupted);
+        }
+        // claimed length in the header is larger than the body
+        let mut too_short = valid.clone();
+        too_short[2..6].copy_from_slice(&10_000u32.to_be_bytes());
+        inputs.push(too_short);
+
+        for input in inputs {
+            let encoded_payload = EncodedPayload::deserialize(input.into());
+            let mut streamed = vec::Vec::new();
+            let result = encoded_payload
+                .decode_into(&mut streamed)
+                .map(|_| Bytes::from(streamed));
+            assert_eq!(result, encoded_payload.decode());
+        }
+    }
+
+    #[test]
+    fn test_decoded_chunks_zero_copy() {
+        let rollup_data = vec![7u8; 100];
+        let encoded_payload =
+            EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0).unwrap();
+        let range = encoded_payload.encoded_payload.as_ptr_range();
+        let chunks: vec::Vec<&[u8]> = encoded_payload
+            .decoded_chunks()
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        // 100 bytes are spread over 4 field elements
+        assert_eq!(
+            chunks.iter().map(|c| c.len()).collect::<vec::Vec<_>>(),
+            vec![31, 31, 31, 7]
+        );
+        // every chunk borrows from the encoded payload
+        assert!(chunks.iter().all(|c| range.contains(&c.as_ptr())));
+    }
+
     #[test]
     fn test_serde_on_encoded_payload() {
         let rollup_data = vec![1, 2, 3, 4];
-        let encoded_payload = encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0);
+        let encoded_payload =
+            EncodedPayload::encode(&rollup_data, PAYLOAD_ENCODING_VERSION_0).unwrap();
         let ser = encoded_payload.serialize();
         let deserialized_encoded_payload = EncodedPayload::deserialize(ser.clone());
         assert_eq!(encoded_payload, deserialized_encoded_payload);
@@ -1,12 +1,14 @@
 //! EigenDAPreimageSource Source
 
 use crate::eigenda_data::EncodedPayload;
+use crate::events::DiscardReason;
+use crate::recency::{RecencyPolicy, RecencyWindows};
 use crate::traits::EigenDAPreimageProvider;
-use crate::HokuleaPreimageError;
 
 use crate::errors::{HokuleaErrorKind, HokuleaRecencyCheckError, HokuleaStatelessError};
-use alloy_primitives::Bytes;
-use eigenda_cert::{AltDACommitment, EigenDAVersionedCert};
+use alloc::{format, sync::Arc};
+use alloy_primitives::{Bytes, B256};
+use eigenda_cert::{AltDACommitment, AltDACommitmentParseError, GenericAltDACommitment};
 
 /// A data iterator that reads from a preimage.
 #[derive(Debug, Clone)]
@@ -16,15 +18,37 @@ where
 {
     /// Fetches eigenda preimage.
     pub eigenda_fetcher: B,
+    /// Decides the recency window of a DA cert
+    pub recency_policy: Arc<dyn RecencyPolicy>,
+    /// Compares the recency decided by the client with the verdict of the host, for debugging
+    pub check_recency_verdict: bool,
 }
 
 impl<B> EigenDAPreimageSource<B>
 where
     B: EigenDAPreimageProvider + Send,
 {
-    /// Creates a new preimage source.
-    pub const fn new(eigenda_fetcher: B) -> Self {
-        Self { eigenda_fetcher }
+    /// Creates a new preimage source, which checks recency with the default [RecencyWindows].
+    pub fn new(eigenda_fetcher: B) -> Self {
+        Self {
+            eigenda_fetcher,
+            recency_policy: Arc::new(RecencyWindows::default()),
+            check_recency_verdict: false,
+        }
+    }
+
+    /// Replaces the policy deciding the recency window of a DA cert.
+    pub fn with_recency_policy(mut self, recency_policy: Arc<dyn RecencyPolicy>) -> Self {
+        self.recency_policy = recency_policy;
+        self
+    }
+
+    /// Debug mode comparing the recency decided by the client with the recency verdict published
+    /// by the host, see [EigenDAPreimageProvider::get_recency_verdict]. A disagreement is a
+    /// [HokuleaErrorKind::Critical] error, which stops the derivation.
+    pub fn with_recency_verdict_check(mut self, check_recency_verdict: bool) -> Self {
+        self.check_recency_verdict = check_recency_verdict;
+        self
     }
 
     /// Fetches the preimages from the source for calldata.
@@ -33,40 +57,121 @@ where
         altda_commitment: &AltDACommitment,
         l1_inclusion_bn: u64,
     ) -> Result<EncodedPayload, HokuleaErrorKind> {
+        match self.try_next(altda_commitment, l1_inclusion_bn).await? {
+            Ok(encoded_payload) => Ok(encoded_payload),
+            Err(reason) => Err(reason.into()),
+        }
+    }
+
+    /// Same as [EigenDAPreimageSource::next], but returns the [DiscardReason] when the DA cert is
+    /// discarded for being invalid or not recent. The outer error is returned for the remaining
+    /// errors, including those the preimage provider maps into [HokuleaErrorKind::Discard].
+    pub async fn try_next(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+        l1_inclusion_bn: u64,
+    ) -> Result<Result<EncodedPayload, DiscardReason>, HokuleaErrorKind> {
         info!(target: "eigenda_preimage_source", "parsed an altda commitment of version {}", altda_commitment.cert_version_str());
         // get cert validty via preimage oracle, discard cert if invalid
         match self.eigenda_fetcher.get_validity(altda_commitment).await {
             Ok(true) => (),
-            Ok(false) => return Err(HokuleaPreimageError::InvalidCert.into()),
+            Ok(false) => return Ok(Err(DiscardReason::InvalidCert)),
             Err(e) => return Err(e.into()),
         }
 
-        // recency window is determined by offchain code version in the unit of L1 block
-        // V2 and V3 cert automatically maps to recency_window 0
+        // recency window is decided by the recency policy in the unit of L1 block, by default
+        // V2 and V3 cert maps to recency_window 0
         // V4 cert derives recency_window from offchain_derivation_version
-        let recency_window: u64 = match &altda_commitment.versioned_cert {
-            EigenDAVersionedCert::V2(_) => 0,
-            EigenDAVersionedCert::V3(_) => 0,
-            EigenDAVersionedCert::V4(cert) => get_recency_window(cert.offchain_derivation_version)?,
-        };
+        let recency_window = self
+            .recency_policy
+            .recency_window(&altda_commitment.versioned_cert)?;
         // get recency window size, discard the old cert if necessary
-        recency_check(l1_inclusion_bn, altda_commitment.get_rbn(), recency_window)?;
+        let is_recent =
+            match recency_check(l1_inclusion_bn, altda_commitment.get_rbn(), recency_window) {
+                Ok(()) => true,
+                Err(HokuleaStatelessError::RecencyCheckError(_)) => false,
+                Err(e) => return Err(e.into()),
+            };
+        if self.check_recency_verdict {
+            self.compare_recency_verdict(altda_commitment, is_recent)
+                .await?;
+        }
+        if !is_recent {
+            return Ok(Err(DiscardReason::NotRecentCert));
+        }
 
         // get encoded payload via preimage oracle
         self.eigenda_fetcher
             .get_encoded_payload(altda_commitment)
             .await
+            .map(Ok)
             .map_err(|e| e.into())
     }
 
-    /// parse calldata into altda commitment
+    /// Fails if the host published a recency verdict different from the one of the client
+    async fn compare_recency_verdict(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+        is_recent: bool,
+    ) -> Result<(), HokuleaErrorKind> {
+        match self
+            .eigenda_fetcher
+            .get_recency_verdict(altda_commitment)
+            .await
+        {
+            Ok(Some(verdict)) if verdict != is_recent => {
+                error!(
+                    target: "eigenda_preimage_source",
+                    "recency verdict of host {} differs from client {} for altda commitment {}",
+                    verdict,
+                    is_recent,
+                    altda_commitment.to_digest()
+                );
+                Err(HokuleaErrorKind::Critical(format!(
+                    "recency verdict of host {verdict} differs from client {is_recent}"
+                )))
+            }
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Fetches the input data of a keccak altda commitment via preimage oracle. The preimage
+    /// oracle binds the input data to its keccak256 hash, hence no further check is needed.
+    /// A preimage provider not serving keccak preimages is a [HokuleaErrorKind::Critical] error.
+    pub async fn next_keccak(&mut self, commitment: B256) -> Result<Bytes, HokuleaErrorKind> {
+        info!(target: "eigenda_preimage_source", "parsed a keccak altda commitment {}", commitment);
+        match self.eigenda_fetcher.get_keccak_preimage(commitment).await {
+            Ok(Some(input_data)) => Ok(input_data),
+            Ok(None) => Err(HokuleaErrorKind::Critical(format!(
+                "preimage provider does not serve the keccak preimage of {commitment}"
+            ))),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// parse calldata into altda commitment, keccak commitment and generic commitment of other
+    /// DA layers are rejected
     pub fn parse(&mut self, data: &Bytes) -> Result<AltDACommitment, HokuleaStatelessError> {
+        match self.parse_generic(data)? {
+            GenericAltDACommitment::EigenDA(altda_commitment) => Ok(altda_commitment),
+            _ => Err(HokuleaStatelessError::ParseError(
+                AltDACommitmentParseError::UnsupportedCommitmentType,
+            )),
+        }
+    }
+
+    /// parse calldata into altda commitment of any commitment type, see [GenericAltDACommitment]
+    pub fn parse_generic(
+        &mut self,
+        data: &Bytes,
+    ) -> Result<GenericAltDACommitment, HokuleaStatelessError> {
         if data.len() <= 2 {
             // recurse if data is mailformed
             warn!(target: "preimage_source", "Failed to decode altda commitment, skipping");
             return Err(HokuleaStatelessError::InsufficientLengthAltDACommimtment);
         }
-        let altda_commitment: AltDACommitment = match data[1..].try_into() {
+        let altda_commitment: GenericAltDACommitment = match data[1..].try_into() {
             Ok(a) => a,
             Err(e) => {
                 error!("failed to parse altda commitment {}", e);
@@ -77,16 +182,6 @@ where
     }
 }
 
-// version 0 has 48 hours recency window where 14400 = 48*60*60(second) / 12 (second/L1block)
-fn get_recency_window(offchain_derivation_version: u16) -> Result<u64, HokuleaStatelessError> {
-    match offchain_derivation_version {
-        0 => Ok(14400),
-        _ => Err(HokuleaStatelessError::UnsupportedOffchainDerivationVersion(
-            offchain_derivation_version,
-        )),
-    }
-}
-
 fn recency_check(
     l1_inclusion_bn: u64,
     rbn: u64,
@@ -119,10 +214,13 @@ mod tests {
     use crate::test_utils::{self, TestEigenDAPreimageProvider, TestHokuleaProviderError};
 
     use super::*;
+    use crate::recency::RECENCY_WINDOW_OFFCHAIN_DERIVATION_VERSION_0;
+    use crate::HokuleaPreimageError;
     use alloc::string::ToString;
     use alloc::vec;
     use alloy_primitives::hex;
-    use eigenda_cert::AltDACommitmentParseError;
+    use eigenda_cert::test_utils::versioned_certs;
+    use eigenda_cert::EigenDAVersionedCert;
 
     const CALLDATA_HEX: &str = "0x01010001f9035ef901cdf901c8f9018080820001f90158f842a013cb9a6e004f28a193672a95b2ee4a2addc14bfe705eb3c1695f34dccfdf4d7fa01de675df78f68e6f40643f148b7dcf7b30e7bbb5ec5ed66efcf82e02a148b45ef888f842a00ca1a4b18243aed65a6887cb3da7ab7a9b8138261ad5fa7a7ef61fcf45ad0f77a012969add06ec97e0b24ef9f69633114966952c02150f8bb28a55a5fac60c7644f842a00c137feb7cf2cf625b826eebd5a1ffd400446e03336c6ff07061b7a9adc32376a00cd9277cc3e8c2a6c896c4e7c045504d1cff34ec9e8a6648e8ef4f335ae5b943f887f842a02b977c12979aed6688323f70e2d5ca9e2640fe14bf0a5e26ddfac95134d9c09ea02c204a0405fb9c3cb890219c6fccff0a9a265415656c5896449884c6a64caedef841a00104c001661c0169aac0fb16db9f30b70f8e13da88c539904b61895d3494c7889fca145e3f25f772c7e951708a541d8d14bb923edea351eeb0bbc928ae5b798508a0676a73762570ea5c17427aed9db14a85b268fafc282cbbe0c3db9165487133c9b84118cf5bd976613bb6a63009b15613d137f2555d2418da654a11781ac2cf5bf2fb63d44a580d2f15628f4b1cdb9526e1f774360b8ef2e5e451f18a80411d06b42b01c1808080e5a05e27869d58bd1fe21f34d0e9120abe775896df7c0829cf4d870f576f188cbe30838a8d05f90162c0c0f888f842a02099209289cdb7e5087d0401996d2fd9b52ce5cae39c547a039f126371a7f9bca026139d9d30188c9d52468ce9dfb48c39d552243611d5b270f5497c2b8692c696f842a02b2dabbf32c0cb551d3ba9159ae5c985ebcd71d79b00fabd26a74d618065bfd6a01bef832bd3efaea9f61c0582fb123bb547546f0c5910a9dda96bcd0063d57a02f888f842a027b90b5da16ef02417ad5820223e680d2c2d19a3f1d30566cfbb7b9aa30abf6da022432d9b57d271b8dd84bfb4ccd9df36b84e422cb471b35d50d55ae83a03f16ef842a0018ed79d6c0707cc6f4ec81bcea6c4cc0096f0e3635961caf3271c3c9a36a9dfa0179360dc4646a7c49bf730e1789c00622facd7836faa3c747be0f2d824cb1412f841a02147a377c426a6b91bd27342dfe180882d130d9fbbdcb147477f025082135c189f468884960c4e83243b3aeb52ef2eb017fa81ec4b98f63bedc7c1dc27ec0bfec20705c20805c2c0c0820001";
 
@@ -167,14 +265,177 @@ mod tests {
     }
 
     #[test]
-    fn get_recency_window_test() {
-        assert_eq!(get_recency_window(0), Ok(14400));
-        assert_eq!(
-            get_recency_window(1),
-            Err(HokuleaStatelessError::UnsupportedOffchainDerivationVersion(
-                1
-            ))
-        );
+    fn test_parse_generic_altda_commitment() {
+        let mut preimage_source = default_test_preimage_source();
+        let keccak_hash = B256::repeat_byte(0xab);
+        let eigenda_calldata: Bytes = hex::decode(CALLDATA_HEX).unwrap().into();
+        let eigenda_commitment = preimage_source.parse(&eigenda_calldata).unwrap();
+        struct Case {
+            input: vec::Vec<u8>,
+            generic_result: Result<GenericAltDACommitment, HokuleaStatelessError>,
+            result: Result<AltDACommitment, HokuleaStatelessError>,
+        }
+        let cases = [
+            // 0x01 (OP derivation version byte) ++ keccak commitment
+            Case {
+                input: [&[1, 0], keccak_hash.as_slice()].concat(),
+                generic_result: Ok(GenericAltDACommitment::Keccak(keccak_hash)),
+                result: Err(HokuleaStatelessError::ParseError(
+                    AltDACommitmentParseError::UnsupportedCommitmentType,
+                )),
+            },
+            // keccak commitment with truncated hash
+            Case {
+                input: [&[1, 0], &keccak_hash[..16]].concat(),
+                generic_result: Err(HokuleaStatelessError::ParseError(
+                    AltDACommitmentParseError::InvalidKeccakCommitmentLength(17),
+                )),
+                result: Err(HokuleaStatelessError::ParseError(
+                    AltDACommitmentParseError::InvalidKeccakCommitmentLength(17),
+                )),
+            },
+            // 0x01 (OP derivation version byte) ++ eigenda altda commitment
+            Case {
+                input: eigenda_calldata.to_vec(),
+                generic_result: Ok(GenericAltDACommitment::EigenDA(eigenda_commitment.clone())),
+                result: Ok(eigenda_commitment.clone()),
+            },
+        ];
+
+        for case in cases {
+            let input: Bytes = case.input.into();
+            assert_eq!(preimage_source.parse_generic(&input), case.generic_result);
+            assert_eq!(preimage_source.parse(&input), case.result);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next_keccak() {
+        let commitment = B256::repeat_byte(0xab);
+        let preimage: Bytes = vec![0u8, 1, 2, 3].into();
+        struct Case {
+            stored: bool,
+            should_preimage_err: bool,
+            result: Result<Bytes, HokuleaErrorKind>,
+        }
+        let cases = [
+            Case {
+                stored: true,
+                should_preimage_err: false,
+                result: Ok(preimage.clone()),
+            },
+            Case {
+                stored: true,
+                should_preimage_err: true,
+                result: Err(HokuleaErrorKind::Temporary(
+                    "Preimage temporary error".to_string(),
+                )),
+            },
+            // the provider does not serve keccak preimages
+            Case {
+                stored: false,
+                should_preimage_err: false,
+                result: Err(HokuleaErrorKind::Critical(format!(
+                    "preimage provider does not serve the keccak preimage of {commitment}"
+                ))),
+            },
+        ];
+
+        for case in cases {
+            let mut preimage_source = default_test_preimage_source();
+            if case.stored {
+                preimage_source
+                    .eigenda_fetcher
+                    .insert_keccak_preimage(commitment, Ok(preimage.clone()));
+            }
+            preimage_source.eigenda_fetcher.should_preimage_err = case.should_preimage_err;
+            assert_eq!(preimage_source.next_keccak(commitment).await, case.result);
+        }
+    }
+
+    // returns the V2 cert of CALLDATA_HEX, and the V3 and V4 certs with the same content
+    fn test_versioned_certs(offchain_derivation_version: u16) -> [EigenDAVersionedCert; 3] {
+        let calldata = hex::decode(CALLDATA_HEX).unwrap().into();
+        let altda_commitment = default_test_preimage_source().parse(&calldata).unwrap();
+        versioned_certs(
+            &altda_commitment.versioned_cert,
+            offchain_derivation_version,
+        )
+    }
+
+    #[test]
+    fn test_recency_window() {
+        let [v2, v3, v4] = test_versioned_certs(0);
+        let [_, _, v4_version_1] = test_versioned_certs(1);
+        struct Case {
+            recency_policy: RecencyWindows,
+            cert: EigenDAVersionedCert,
+            result: Result<u64, HokuleaStatelessError>,
+        }
+        let cases = [
+            // by default, V2 and V3 cert skip recency check
+            Case {
+                recency_policy: RecencyWindows::default(),
+                cert: v2.clone(),
+                result: Ok(0),
+            },
+            Case {
+                recency_policy: RecencyWindows::default(),
+                cert: v3.clone(),
+                result: Ok(0),
+            },
+            // by default, V4 cert with offchain derivation version 0 has 48 hours recency window
+            Case {
+                recency_policy: RecencyWindows::default(),
+                cert: v4.clone(),
+                result: Ok(RECENCY_WINDOW_OFFCHAIN_DERIVATION_VERSION_0),
+            },
+            // by default, any other offchain derivation version is unsupported
+            Case {
+                recency_policy: RecencyWindows::default(),
+                cert: v4_version_1.clone(),
+                result: Err(HokuleaStatelessError::UnsupportedOffchainDerivationVersion(
+                    1,
+                )),
+            },
+            // opt into recency check for V3 cert, V2 cert is unaffected
+            Case {
+                recency_policy: RecencyWindows::default().with_v3_recency_window(100),
+                cert: v3.clone(),
+                result: Ok(100),
+            },
+            Case {
+                recency_policy: RecencyWindows::default().with_v3_recency_window(100),
+                cert: v2.clone(),
+                result: Ok(0),
+            },
+            Case {
+                recency_policy: RecencyWindows::default().with_v2_recency_window(100),
+                cert: v2.clone(),
+                result: Ok(100),
+            },
+            // override the recency window of an existing offchain derivation version
+            Case {
+                recency_policy: RecencyWindows::default().with_offchain_derivation_version(0, 10),
+                cert: v4.clone(),
+                result: Ok(10),
+            },
+            // add the recency window of a new offchain derivation version
+            Case {
+                recency_policy: RecencyWindows::default().with_offchain_derivation_version(1, 10),
+                cert: v4_version_1.clone(),
+                result: Ok(10),
+            },
+            Case {
+                recency_policy: RecencyWindows::default().with_offchain_derivation_version(1, 10),
+                cert: v4.clone(),
+                result: Ok(RECENCY_WINDOW_OFFCHAIN_DERIVATION_VERSION_0),
+            },
+        ];
+
+        for case in cases {
+            assert_eq!(case.recency_policy.recency_window(&case.cert), case.result);
+        }
     }
 
     #[test]
@@ -283,7 +544,7 @@ mod tests {
             },
         ];
 
-        // test against V3 cert, no recency is checked
+        // test against V2 cert, no recency is checked
         for case in cases {
             // set up preimage
             preimage_source
@@ -302,4 +563,154 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_next_with_recency_policy() {
+        let calldata = hex::decode(CALLDATA_HEX).unwrap().into();
+        let mut preimage_source = default_test_preimage_source();
+        let altda_commitment = preimage_source.parse(&calldata).unwrap();
+        let l1_inclusion_number = altda_commitment.get_rbn() + 100;
+        let encoded_payload = EncodedPayload::encode(&[1, 2, 3], 0).unwrap();
+        preimage_source
+            .eigenda_fetcher
+            .insert_validity(&altda_commitment, Ok(true));
+        preimage_source
+            .eigenda_fetcher
+            .insert_encoded_payload(&altda_commitment, Ok(encoded_payload.clone()));
+
+        struct Case {
+            recency_policy: RecencyWindows,
+            result: Result<EncodedPayload, HokuleaErrorKind>,
+        }
+        let cases = [
+            // V2 cert skips recency check by default
+            Case {
+                recency_policy: RecencyWindows::default(),
+                result: Ok(encoded_payload.clone()),
+            },
+            // V2 cert opts into recency check, l1_inclusion_bn <= rbn + recency_window
+            Case {
+                recency_policy: RecencyWindows::default().with_v2_recency_window(100),
+                result: Ok(encoded_payload.clone()),
+            },
+            // overriding V3 cert does not apply to V2 cert
+            Case {
+                recency_policy: RecencyWindows::default().with_v3_recency_window(99),
+                result: Ok(encoded_payload.clone()),
+            },
+            // V2 cert opts into recency check, and it is not recent enough
+            Case {
+                recency_policy: RecencyWindows::default().with_v2_recency_window(99),
+                result: Err(HokuleaErrorKind::Discard(
+                    "da cert is not recent enough".to_string(),
+                )),
+            },
+        ];
+
+        for case in cases {
+            preimage_source.recency_policy = Arc::new(case.recency_policy);
+            assert_eq!(
+                preimage_source
+                    .next(&altda_commitment, l1_inclusion_number)
+                    .await,
+                case.result
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next_with_recency_verdict_check() {
+        let calldata = hex::decode(CALLDATA_HEX).unwrap().into();
+        let altda_commitment = default_test_preimage_source().parse(&calldata).unwrap();
+        let l1_inclusion_number = altda_commitment.get_rbn() + 100;
+        let encoded_payload = EncodedPayload::encode(&[1, 2, 3], 0).unwrap();
+
+        struct Case {
+            recency_window: u64,
+            check_recency_verdict: bool,
+            verdict: Option<bool>,
+            result: Result<EncodedPayload, HokuleaErrorKind>,
+        }
+        let cases = [
+            // host agrees with client
+            Case {
+                recency_window: 100,
+                check_recency_verdict: true,
+                verdict: Some(true),
+                result: Ok(encoded_payload.clone()),
+            },
+            Case {
+                recency_window: 99,
+                check_recency_verdict: true,
+                verdict: Some(false),
+                result: Err(HokuleaErrorKind::Discard(
+                    "da cert is not recent enough".to_string(),
+                )),
+            },
+            // host has no verdict
+            Case {
+                recency_window: 100,
+                check_recency_verdict: true,
+                verdict: None,
+                result: Ok(encoded_payload.clone()),
+            },
+            // host disagrees with client
+            Case {
+                recency_window: 100,
+                check_recency_verdict: true,
+                verdict: Some(false),
+                result: Err(HokuleaErrorKind::Critical(
+                    "recency verdict of host false differs from client true".to_string(),
+                )),
+            },
+            Case {
+                recency_window: 99,
+                check_recency_verdict: true,
+                verdict: Some(true),
+                result: Err(HokuleaErrorKind::Critical(
+                    "recency verdict of host true differs from client false".to_string(),
+                )),
+            },
+            // the verdict is ignored outside the debug mode
+            Case {
+                recency_window: 100,
+                check_recency_verdict: false,
+                verdict: Some(false),
+                result: Ok(encoded_payload.clone()),
+            },
+            Case {
+                recency_window: 99,
+                check_recency_verdict: false,
+                verdict: Some(true),
+                result: Err(HokuleaErrorKind::Discard(
+                    "da cert is not recent enough".to_string(),
+                )),
+            },
+        ];
+
+        for case in cases {
+            let mut preimage_source = default_test_preimage_source()
+                .with_recency_policy(Arc::new(
+                    RecencyWindows::default().with_v2_recency_window(case.recency_window),
+                ))
+                .with_recency_verdict_check(case.check_recency_verdict);
+            preimage_source
+                .eigenda_fetcher
+                .insert_validity(&altda_commitment, Ok(true));
+            preimage_source
+                .eigenda_fetcher
+                .insert_encoded_payload(&altda_commitment, Ok(encoded_payload.clone()));
+            if let Some(verdict) = case.verdict {
+                preimage_source
+                    .eigenda_fetcher
+                    .insert_recency_verdict(&altda_commitment, verdict);
+            }
+            assert_eq!(
+                preimage_source
+                    .next(&altda_commitment, l1_inclusion_number)
+                    .await,
+                case.result
+            );
+        }
+    }
 }
@@ -21,7 +21,7 @@ pub enum HokuleaErrorKind {
 /// represents all application error for hokulea. The [HokuleaStatelessError] is
 /// different that the errors comes from pure data processing of altDA commitment
 /// and encoded payload
-#[derive(Debug, thiserror::Error, PartialEq)]
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
 #[error(transparent)]
 pub enum HokuleaStatelessError {
     /// Data is too short for parsing the altda commitment
@@ -65,7 +65,7 @@ impl From<HokuleaStatelessError> for HokuleaErrorKind {
 }
 
 /// List of error can happen during decoding an encoded payload
-#[derive(Debug, thiserror::Error, PartialEq)]
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
 pub enum EncodedPayloadDecodingError {
     /// the input encoded payload has wrong size
     #[error("invalid number of bytes in the encoded payload {0}, that is not multiple of bytes per field element")]
@@ -106,7 +106,26 @@ pub enum EncodedPayloadDecodingError {
     InvalidEncodedPayloadHeaderPadding(u8),
 }
 
+/// List of error can happen during encoding a payload into an encoded payload
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum EncodedPayloadEncodingError {
+    /// unknown payload encoding version
+    #[error("unknown payload encoding version: {0}")]
+    UnknownEncodingVersion(u8),
+    /// payload length must fit into the uint32 length of the encoded payload header
+    #[error("payload of {0} bytes is too large, its length must fit in a uint32")]
+    PayloadTooLarge(usize),
+}
+
+/// List of error can happen when registering a [crate::DaLayerHandler]
 #[derive(Debug, thiserror::Error, PartialEq)]
+pub enum DaLayerRegistryError {
+    /// the da_layer_byte of EigenDA is always served by the EigenDA preimage source
+    #[error("da_layer_byte {0} of EigenDA is reserved")]
+    ReservedDaLayerByte(u8),
+}
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
 #[error(transparent)]
 pub enum HokuleaRecencyCheckError {
     /// EigenDA cert is not recent
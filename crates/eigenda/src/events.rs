@@ -0,0 +1,110 @@
+//! Contains the [DerivationEvent] emitted by [crate::EigenDADataSource] and the
+//! [DerivationEventSink] trait to observe them. Hosts and provers can collect structured
+//! events about every DA cert, instead of scraping logs.
+
+use crate::errors::{
+    HokuleaErrorKind, HokuleaPreimageError, HokuleaRecencyCheckError, HokuleaStatelessError,
+};
+use alloc::{format, string::String};
+use alloy_primitives::{Address, B256};
+use core::fmt::Debug;
+
+/// The reason why a DA cert is discarded by the EigenDA blob derivation
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiscardReason {
+    /// DA cert is invalid or inconsistent offchain derivation version
+    InvalidCert,
+    /// DA cert is not recent enough, see [crate::RecencyPolicy]
+    NotRecentCert,
+    /// the encoded payload of the DA cert cannot be decoded
+    Decoding(HokuleaStatelessError),
+    /// the preimage provider asked to discard the DA cert
+    Provider(String),
+    /// no [crate::DaLayerHandler] is registered for the da_layer_byte of the generic altda
+    /// commitment
+    UnsupportedDaLayer(u8),
+    /// keccak altda commitments are disabled, see
+    /// [crate::EigenDADataSource::with_keccak_commitments]
+    KeccakCommitmentDisabled,
+}
+
+/// define conversion error, it preserves the error of the discarded cert
+impl From<DiscardReason> for HokuleaErrorKind {
+    fn from(reason: DiscardReason) -> Self {
+        match reason {
+            DiscardReason::InvalidCert => HokuleaPreimageError::InvalidCert.into(),
+            DiscardReason::NotRecentCert => {
+                HokuleaStatelessError::RecencyCheckError(HokuleaRecencyCheckError::NotRecentCert)
+                    .into()
+            }
+            DiscardReason::Decoding(e) => e.into(),
+            DiscardReason::Provider(e) => HokuleaErrorKind::Discard(e),
+            DiscardReason::UnsupportedDaLayer(da_layer_byte) => {
+                HokuleaErrorKind::Discard(format!("unsupported da layer byte {da_layer_byte}"))
+            }
+            DiscardReason::KeccakCommitmentDisabled => {
+                HokuleaErrorKind::Discard("keccak altda commitments are disabled".into())
+            }
+        }
+    }
+}
+
+/// A structured event of the EigenDA blob derivation. The batcher address identifies the batcher
+/// inbox transaction within the L1 block.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DerivationEvent {
+    /// an altda commitment is parsed from the batcher inbox transaction
+    CertParsed {
+        /// digest of the DA cert
+        digest: B256,
+        /// number of the L1 block including the DA cert
+        l1_block: u64,
+        /// batcher address of the inbox transaction
+        batcher_addr: Address,
+    },
+    /// the DA cert is discarded, the derivation moves on to the next inbox transaction
+    CertDiscarded {
+        /// why the DA cert is discarded
+        reason: DiscardReason,
+        /// digest of the DA cert
+        digest: B256,
+        /// number of the L1 block including the DA cert
+        l1_block: u64,
+        /// batcher address of the inbox transaction
+        batcher_addr: Address,
+    },
+    /// the encoded payload of the DA cert is decoded into rollup payload
+    PayloadDecoded {
+        /// length of the rollup payload in bytes
+        len: usize,
+        /// digest of the DA cert
+        digest: B256,
+        /// number of the L1 block including the DA cert
+        l1_block: u64,
+    },
+}
+
+/// Observes the [DerivationEvent] of the EigenDA blob derivation. Events are emitted
+/// synchronously from the derivation pipeline, so implementations should return quickly.
+pub trait DerivationEventSink: Debug + Send + Sync {
+    /// Called for every derivation event
+    fn on_event(&self, event: &DerivationEvent);
+
+    /// Returns false if the sink ignores all events, such that the derivation skips building
+    /// them, i.e. hashing the DA cert for its digest
+    fn enabled(&self) -> bool {
+        true
+    }
+}
+
+/// A [DerivationEventSink] ignoring all events, used by default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopDerivationEventSink;
+
+impl DerivationEventSink for NoopDerivationEventSink {
+    fn on_event(&self, _event: &DerivationEvent) {}
+
+    fn enabled(&self) -> bool {
+        false
+    }
+}
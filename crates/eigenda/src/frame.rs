@@ -0,0 +1,53 @@
+//! Contains the [DerivedFrame], the data derived by [crate::EigenDADataSource] tagged with the
+//! DA path producing it.
+
+use alloy_primitives::{Bytes, B256};
+
+/// Data derived from a batcher inbox transaction, tagged with the DA path producing it. See
+/// [crate::EigenDADataSource::next_frame].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerivedFrame {
+    /// calldata or blob of the batcher inbox transaction, which is not an altda commitment. The
+    /// ethereum source yields both alike, hence the two are not told apart.
+    Ethereum(Bytes),
+    /// rollup payload decoded from the encoded payload of a DA cert
+    EigenDA {
+        /// digest of the DA cert
+        digest: B256,
+        /// rollup payload
+        payload: Bytes,
+    },
+    /// input data of a keccak altda commitment, see [eigenda_cert::GenericAltDACommitment]
+    AltDAKeccak {
+        /// keccak256 hash of the input data
+        commitment: B256,
+        /// input data
+        data: Bytes,
+    },
+    /// rollup payload of a generic altda commitment of a DA layer other than EigenDA, see
+    /// [crate::DaLayerHandler]
+    DaLayer {
+        /// da_layer_byte of the generic altda commitment
+        da_layer_byte: u8,
+        /// rollup payload
+        payload: Bytes,
+    },
+}
+
+impl DerivedFrame {
+    /// Returns the derived data, regardless of the DA path
+    pub fn data(&self) -> &Bytes {
+        match self {
+            Self::Ethereum(data) | Self::AltDAKeccak { data, .. } => data,
+            Self::EigenDA { payload, .. } | Self::DaLayer { payload, .. } => payload,
+        }
+    }
+
+    /// Converts into the derived data, as returned by the DataAvailabilityProvider
+    pub fn into_data(self) -> Bytes {
+        match self {
+            Self::Ethereum(data) | Self::AltDAKeccak { data, .. } => data,
+            Self::EigenDA { payload, .. } | Self::DaLayer { payload, .. } => payload,
+        }
+    }
+}
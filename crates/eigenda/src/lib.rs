@@ -17,10 +17,17 @@ extern crate tracing;
 
 mod traits;
 pub use traits::{
-    EigenDAPreimageProvider, RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY,
+    EigenDAPreimageProvider, RESERVED_EIGENDA_API_BYTE_FOR_ENCODED_PAYLOAD_CHUNK,
+    RESERVED_EIGENDA_API_BYTE_FOR_RECENCY, RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY,
     RESERVED_EIGENDA_API_BYTE_INDEX,
 };
 
+mod preimage_key;
+pub use preimage_key::{
+    EigenDAPreimageKey, EncodedPayloadFetchMode, PREIMAGE_KEY_LAYOUT_VERSION,
+    PREIMAGE_KEY_LAYOUT_VERSION_0,
+};
+
 mod eigenda;
 pub use eigenda::EigenDADataSource;
 
@@ -28,11 +35,27 @@ mod eigenda_preimage;
 pub use eigenda_preimage::EigenDAPreimageSource;
 
 mod eigenda_data;
-pub use eigenda_data::{EncodedPayload, Payload};
+pub use eigenda_data::{DecodedChunks, EncodedPayload, EncodedPayloadBuilder, Payload};
+
+mod frame;
+pub use frame::DerivedFrame;
+
+mod da_layer;
+pub use da_layer::{DaLayerHandler, DaLayerRegistry, EigenDADaLayer, RegisteredDaLayerHandler};
+
+mod events;
+pub use events::{DerivationEvent, DerivationEventSink, DiscardReason, NoopDerivationEventSink};
+
+mod recency;
+pub use recency::{RecencyPolicy, RecencyWindows, RECENCY_WINDOW_OFFCHAIN_DERIVATION_VERSION_0};
+
+mod payload_codec;
+pub use payload_codec::{PayloadCodec, PayloadCodecRegistry, PayloadCodecV0};
 
 mod errors;
 pub use errors::{
-    EncodedPayloadDecodingError, HokuleaErrorKind, HokuleaPreimageError, HokuleaStatelessError,
+    DaLayerRegistryError, EncodedPayloadDecodingError, EncodedPayloadEncodingError,
+    HokuleaErrorKind, HokuleaPreimageError, HokuleaStatelessError,
 };
 
 #[cfg(test)]
@@ -40,6 +63,7 @@ mod test_utils;
 
 mod constant;
 pub use constant::ALTDA_DERIVATION_VERSION;
+pub use constant::BYTES_PER_ENCODED_PAYLOAD_CHUNK;
 pub use constant::BYTES_PER_FIELD_ELEMENT;
 pub use constant::ENCODED_PAYLOAD_HEADER_LEN_BYTES;
 pub use constant::PAYLOAD_ENCODING_VERSION_0;
@@ -0,0 +1,239 @@
+//! Contains the [PayloadCodec] trait and the [PayloadCodecRegistry]. A codec implements one
+//! payload encoding scheme, identified by the version byte of the encoded payload header.
+//! See spec <https://layr-labs.github.io/eigenda/integration/spec/3-data-structs.html#encodedpayload>
+
+use crate::eigenda_data::PAYLOAD_BYTES_PER_FIELD_ELEMENT;
+use crate::errors::{EncodedPayloadEncodingError, HokuleaStatelessError};
+use crate::PAYLOAD_ENCODING_VERSION_0;
+use crate::{EncodedPayload, Payload, BYTES_PER_FIELD_ELEMENT, ENCODED_PAYLOAD_HEADER_LEN_BYTES};
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+/// A payload encoding scheme. The version byte at index 1 of the encoded payload header selects
+/// the codec used to decode the encoded payload. Common invariants are checked by [EncodedPayload]
+/// before handing over to the codec:
+/// - the encoded payload has a power of 2 number of field elements
+/// - the first byte of the header is 0x00
+pub trait PayloadCodec: Debug + Send + Sync {
+    /// The version byte written into the encoded payload header
+    fn version(&self) -> u8;
+
+    /// Returns the number of field elements, header included, needed to encode a payload of
+    /// `payload_len` bytes. The number is not rounded up to a power of 2.
+    fn num_field_elements(&self, payload_len: usize) -> u64;
+
+    /// Writes the encoding of the payload into the encoded payload buffer. The buffer is zero
+    /// initialized, and contains a power of 2 number of field elements, no less than
+    /// [PayloadCodec::num_field_elements].
+    fn encode_into(
+        &self,
+        payload: &[u8],
+        encoded_payload: &mut [u8],
+    ) -> Result<(), EncodedPayloadEncodingError>;
+
+    /// Decodes the encoded payload into the payload.
+    fn decode(&self, encoded_payload: &EncodedPayload) -> Result<Payload, HokuleaStatelessError>;
+
+    /// Decodes the encoded payload, appending the payload to `out`. It must accept and reject
+    /// exactly the same encoded payloads as [PayloadCodec::decode], but on error `out` may
+    /// already contain part of the payload. The default implementation copies the result of
+    /// [PayloadCodec::decode], codecs override it to stream the payload instead.
+    fn decode_into(
+        &self,
+        encoded_payload: &EncodedPayload,
+        out: &mut Vec<u8>,
+    ) -> Result<(), HokuleaStatelessError> {
+        out.extend_from_slice(&self.decode(encoded_payload)?);
+        Ok(())
+    }
+}
+
+/// The built-in codec for PAYLOAD_ENCODING_VERSION_0, which requires payload to be encoded as follows
+/// - begin with 32 byte header = [0x00, version byte 0, uint32 len of data, 0x00, 0x00,..., 0x00]
+/// - followed by the encoded data [0x00, 31 bytes of data, 0x00, 31 bytes of data,...]
+///
+/// This matches the eigenda proxy implementation, whose logic is in
+/// <https://github.com/Layr-Labs/eigenda/blob/master/encoding/utils/codec/codec.go#L12>
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PayloadCodecV0;
+
+impl PayloadCodec for PayloadCodecV0 {
+    fn version(&self) -> u8 {
+        PAYLOAD_ENCODING_VERSION_0
+    }
+
+    fn num_field_elements(&self, payload_len: usize) -> u64 {
+        // one field element for the header, then 31 payload bytes per field element
+        1 + payload_len.div_ceil(PAYLOAD_BYTES_PER_FIELD_ELEMENT) as u64
+    }
+
+    fn encode_into(
+        &self,
+        payload: &[u8],
+        encoded_payload: &mut [u8],
+    ) -> Result<(), EncodedPayloadEncodingError> {
+        let payload_len = u32::try_from(payload.len())
+            .map_err(|_| EncodedPayloadEncodingError::PayloadTooLarge(payload.len()))?;
+
+        encoded_payload[1] = PAYLOAD_ENCODING_VERSION_0;
+        // encode length as uint32
+        encoded_payload[2..6].copy_from_slice(&payload_len.to_be_bytes());
+
+        // the first byte of every field element in the body stays 0x00
+        for (chunk, field_element) in payload.chunks(PAYLOAD_BYTES_PER_FIELD_ELEMENT).zip(
+            encoded_payload[ENCODED_PAYLOAD_HEADER_LEN_BYTES..]
+                .chunks_exact_mut(BYTES_PER_FIELD_ELEMENT),
+        ) {
+            field_element[1..1 + chunk.len()].copy_from_slice(chunk);
+        }
+        Ok(())
+    }
+
+    fn decode(&self, encoded_payload: &EncodedPayload) -> Result<Payload, HokuleaStatelessError> {
+        let payload_len_in_header = encoded_payload.decode_header()?;
+        debug!(target: "eigenda-datasource", "rollup payload length in bytes {:?}", payload_len_in_header);
+        encoded_payload.decode_payload(payload_len_in_header)
+    }
+
+    fn decode_into(
+        &self,
+        encoded_payload: &EncodedPayload,
+        out: &mut Vec<u8>,
+    ) -> Result<(), HokuleaStatelessError> {
+        encoded_payload.stream_payload_v0(out)
+    }
+}
+
+/// A set of [PayloadCodec] keyed by their version byte. The default registry only contains the
+/// built-in [PayloadCodecV0]. New payload encodings can be adopted by registering their codec.
+#[derive(Debug, Clone)]
+pub struct PayloadCodecRegistry {
+    codecs: BTreeMap<u8, Arc<dyn PayloadCodec>>,
+}
+
+impl Default for PayloadCodecRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            codecs: BTreeMap::new(),
+        };
+        registry.register(Arc::new(PayloadCodecV0));
+        registry
+    }
+}
+
+impl PayloadCodecRegistry {
+    /// Registers a codec under its version byte. Returns the codec previously registered under
+    /// the same version byte, if any.
+    pub fn register(&mut self, codec: Arc<dyn PayloadCodec>) -> Option<Arc<dyn PayloadCodec>> {
+        self.codecs.insert(codec.version(), codec)
+    }
+
+    /// Returns the codec registered under the version byte
+    pub fn get(&self, version: u8) -> Option<&dyn PayloadCodec> {
+        self.codecs.get(&version).map(|codec| codec.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::EncodedPayloadDecodingError;
+    use alloc::vec;
+    use alloy_primitives::Bytes;
+
+    /// A codec that packs 32 bytes of payload into every field element, relying on the payload
+    /// being a sequence of valid field elements, and stores the length in the last 4 header bytes
+    #[derive(Debug)]
+    struct TestPayloadCodecV1;
+
+    impl PayloadCodec for TestPayloadCodecV1 {
+        fn version(&self) -> u8 {
+            1
+        }
+
+        fn num_field_elements(&self, payload_len: usize) -> u64 {
+            1 + payload_len.div_ceil(BYTES_PER_FIELD_ELEMENT) as u64
+        }
+
+        fn encode_into(
+            &self,
+            payload: &[u8],
+            encoded_payload: &mut [u8],
+        ) -> Result<(), EncodedPayloadEncodingError> {
+            encoded_payload[1] = 1;
+            encoded_payload[28..32].copy_from_slice(&(payload.len() as u32).to_be_bytes());
+            encoded_payload[32..32 + payload.len()].copy_from_slice(payload);
+            Ok(())
+        }
+
+        fn decode(
+            &self,
+            encoded_payload: &EncodedPayload,
+        ) -> Result<Payload, HokuleaStatelessError> {
+            let bytes = &encoded_payload.encoded_payload;
+            let len = u32::from_be_bytes([bytes[28], bytes[29], bytes[30], bytes[31]]) as usize;
+            if bytes.len() - ENCODED_PAYLOAD_HEADER_LEN_BYTES < len {
+                return Err(EncodedPayloadDecodingError::DecodedPayloadBodyTooShort {
+                    actual: bytes.len() - ENCODED_PAYLOAD_HEADER_LEN_BYTES,
+                    claimed: len as u32,
+                }
+                .into());
+            }
+            Ok(bytes.slice(32..32 + len))
+        }
+    }
+
+    #[test]
+    fn test_default_registry() {
+        let registry = PayloadCodecRegistry::default();
+        assert_eq!(
+            registry.get(PAYLOAD_ENCODING_VERSION_0).unwrap().version(),
+            PAYLOAD_ENCODING_VERSION_0
+        );
+        assert!(registry.get(1).is_none());
+    }
+
+    #[test]
+    fn test_register_new_codec() {
+        let mut registry = PayloadCodecRegistry::default();
+        assert!(registry.register(Arc::new(TestPayloadCodecV1)).is_none());
+        // registering again replaces the previous codec
+        assert!(registry.register(Arc::new(TestPayloadCodecV1)).is_some());
+
+        let payload: Vec<u8> = vec![0; 64];
+        let encoded_payload = EncodedPayload::encode_with(&payload, 1, &registry).unwrap();
+        // 1 header and 2 field elements of payload, rounded up to power of 2
+        assert_eq!(encoded_payload.num_field_element(), 4);
+        assert_eq!(
+            encoded_payload.decode_with(&registry),
+            Ok(Bytes::from(payload.clone()))
+        );
+        // the default decode_into of the codec goes through decode
+        let mut streamed = Vec::new();
+        encoded_payload
+            .decode_into_with(&registry, &mut streamed)
+            .unwrap();
+        assert_eq!(streamed, payload);
+        // the zero-copy iterator only knows about version 0
+        assert_eq!(
+            encoded_payload.decoded_chunks().err(),
+            Some(EncodedPayloadDecodingError::UnknownEncodingVersion(1).into())
+        );
+
+        // the default registry does not know about version 1
+        assert_eq!(
+            encoded_payload.decode(),
+            Err(EncodedPayloadDecodingError::UnknownEncodingVersion(1).into())
+        );
+    }
+
+    #[test]
+    fn test_codec_v0_num_field_elements() {
+        assert_eq!(PayloadCodecV0.num_field_elements(0), 1);
+        assert_eq!(PayloadCodecV0.num_field_elements(1), 2);
+        assert_eq!(PayloadCodecV0.num_field_elements(31), 2);
+        assert_eq!(PayloadCodecV0.num_field_elements(32), 3);
+    }
+}
@@ -0,0 +1,198 @@
+//! Contains the [EigenDAPreimageKey], the address of a preimage of an altda commitment in the
+//! preimage oracle. Host and client both derive their keys from it, such that the address space
+//! can evolve in one place. More see
+//! <https://github.com/Layr-Labs/hokulea/tree/master/docs#preimage-oracle-address-space>
+
+use crate::traits::{
+    RESERVED_EIGENDA_API_BYTE_FOR_ENCODED_PAYLOAD_CHUNK, RESERVED_EIGENDA_API_BYTE_FOR_RECENCY,
+    RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY, RESERVED_EIGENDA_API_BYTE_INDEX,
+};
+use alloy_primitives::{keccak256, B256};
+use eigenda_cert::AltDACommitment;
+
+/// The EigenDA interface byte of field element addresses
+const RESERVED_EIGENDA_API_BYTE_FOR_FIELD_ELEMENT: u8 = 0;
+
+/// The layout version 0 of the addresses, whose layout version byte is 0 such that it matches the
+/// addresses derived before the layout was versioned
+pub const PREIMAGE_KEY_LAYOUT_VERSION_0: u8 = 0;
+
+/// The layout version of the addresses derived by host and client
+pub const PREIMAGE_KEY_LAYOUT_VERSION: u8 = PREIMAGE_KEY_LAYOUT_VERSION_0;
+
+/// The index of the layout version byte, right after the interface byte
+const LAYOUT_VERSION_INDEX: usize = RESERVED_EIGENDA_API_BYTE_INDEX + 1;
+
+/// The index where the 8 bytes field element or chunk index starts
+const FIELD_ELEMENT_INDEX_OFFSET: usize = 72;
+
+/// The address layout of the encoded payload in the preimage oracle. The host only populates the
+/// one the client fetches the encoded payload with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EncodedPayloadFetchMode {
+    /// One 32 bytes field element per preimage request, see [EigenDAPreimageKey::field_element_key].
+    /// FPVMs whose preimage oracle requires 32 bytes leaves must use it
+    #[default]
+    FieldElement,
+    /// One chunk of [crate::BYTES_PER_ENCODED_PAYLOAD_CHUNK] bytes per preimage request, see
+    /// [EigenDAPreimageKey::encoded_payload_chunk_key]
+    Chunk,
+}
+
+/// Address of a preimage of an altda commitment. It contains 80 bytes in total
+///  |  32 bytes  | 1 byte         | 1 byte         | 38 bytes | 8 bytes                      |
+///  | cert digest| interface byte | layout version | 0 .. 0   | field element or chunk index |
+///
+/// The layout version lets a later layout coexist with the current one, see
+/// [PREIMAGE_KEY_LAYOUT_VERSION]. The preimage is stored under the global generic key of the
+/// keccak256 hash of the address, see [EigenDAPreimageKey::hash].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EigenDAPreimageKey([u8; 80]);
+
+impl EigenDAPreimageKey {
+    /// Address of the validity of the altda commitment, a single byte preimage of 0 or 1
+    pub fn validity_key(altda_commitment: &AltDACommitment) -> Self {
+        Self::new(altda_commitment, RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY, 0)
+    }
+
+    /// Address of the recency verdict of the host for the altda commitment, a single byte
+    /// preimage of 0 or 1
+    pub fn recency_key(altda_commitment: &AltDACommitment) -> Self {
+        Self::new(altda_commitment, RESERVED_EIGENDA_API_BYTE_FOR_RECENCY, 0)
+    }
+
+    /// Address of the field element at index of the encoded payload of the altda commitment, a
+    /// 32 bytes preimage
+    pub fn field_element_key(altda_commitment: &AltDACommitment, index: u64) -> Self {
+        Self::new(
+            altda_commitment,
+            RESERVED_EIGENDA_API_BYTE_FOR_FIELD_ELEMENT,
+            index,
+        )
+    }
+
+    /// Address of the chunk at index of the encoded payload of the altda commitment, i.e. the bytes
+    /// `[index * BYTES_PER_ENCODED_PAYLOAD_CHUNK, (index + 1) * BYTES_PER_ENCODED_PAYLOAD_CHUNK)`
+    /// of the encoded payload. See [crate::BYTES_PER_ENCODED_PAYLOAD_CHUNK]
+    pub fn encoded_payload_chunk_key(altda_commitment: &AltDACommitment, index: u64) -> Self {
+        Self::new(
+            altda_commitment,
+            RESERVED_EIGENDA_API_BYTE_FOR_ENCODED_PAYLOAD_CHUNK,
+            index,
+        )
+    }
+
+    fn new(altda_commitment: &AltDACommitment, interface_byte: u8, index: u64) -> Self {
+        let mut address = altda_commitment.digest_template();
+        address[RESERVED_EIGENDA_API_BYTE_INDEX] = interface_byte;
+        address[LAYOUT_VERSION_INDEX] = PREIMAGE_KEY_LAYOUT_VERSION;
+        address[FIELD_ELEMENT_INDEX_OFFSET..].copy_from_slice(&index.to_be_bytes());
+        Self(address)
+    }
+
+    /// The 80 bytes address
+    pub fn address(&self) -> &[u8; 80] {
+        &self.0
+    }
+
+    /// The EigenDA interface byte of the address
+    pub fn interface_byte(&self) -> u8 {
+        self.0[RESERVED_EIGENDA_API_BYTE_INDEX]
+    }
+
+    /// The layout version of the address
+    pub fn layout_version(&self) -> u8 {
+        self.0[LAYOUT_VERSION_INDEX]
+    }
+
+    /// keccak256 hash of the address, to be used with the global generic preimage key type
+    pub fn hash(&self) -> B256 {
+        keccak256(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+    use eigenda_cert::test_utils::ALTDA_COMMITMENT_V3;
+
+    #[test]
+    fn test_preimage_key_layout() {
+        let altda_commitment: AltDACommitment = hex::decode(ALTDA_COMMITMENT_V3).unwrap()[..]
+            .try_into()
+            .unwrap();
+        let digest = altda_commitment.to_digest();
+
+        struct Case {
+            name: &'static str,
+            key: EigenDAPreimageKey,
+            interface_byte: u8,
+            index: u64,
+        }
+
+        let cases = [
+            Case {
+                name: "validity",
+                key: EigenDAPreimageKey::validity_key(&altda_commitment),
+                interface_byte: RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY,
+                index: 0,
+            },
+            Case {
+                name: "recency",
+                key: EigenDAPreimageKey::recency_key(&altda_commitment),
+                interface_byte: RESERVED_EIGENDA_API_BYTE_FOR_RECENCY,
+                index: 0,
+            },
+            Case {
+                name: "first field element",
+                key: EigenDAPreimageKey::field_element_key(&altda_commitment, 0),
+                interface_byte: 0,
+                index: 0,
+            },
+            Case {
+                name: "encoded payload chunk",
+                key: EigenDAPreimageKey::encoded_payload_chunk_key(&altda_commitment, 3),
+                interface_byte: RESERVED_EIGENDA_API_BYTE_FOR_ENCODED_PAYLOAD_CHUNK,
+                index: 3,
+            },
+            Case {
+                name: "last field element",
+                key: EigenDAPreimageKey::field_element_key(&altda_commitment, u64::MAX),
+                interface_byte: 0,
+                index: u64::MAX,
+            },
+        ];
+
+        for case in cases {
+            let address = case.key.address();
+            assert_eq!(&address[..32], digest.as_slice(), "case {}", case.name);
+            assert_eq!(
+                case.key.interface_byte(),
+                case.interface_byte,
+                "case {}",
+                case.name
+            );
+            assert_eq!(
+                case.key.layout_version(),
+                PREIMAGE_KEY_LAYOUT_VERSION,
+                "case {}",
+                case.name
+            );
+            assert_eq!(address[34..72], [0u8; 38], "case {}", case.name);
+            assert_eq!(
+                address[72..],
+                case.index.to_be_bytes(),
+                "case {}",
+                case.name
+            );
+            assert_eq!(case.key.hash(), keccak256(address), "case {}", case.name);
+        }
+
+        // the first field element and the validity only differ by the interface byte
+        assert_ne!(
+            EigenDAPreimageKey::field_element_key(&altda_commitment, 0).hash(),
+            EigenDAPreimageKey::validity_key(&altda_commitment).hash()
+        );
+    }
+}
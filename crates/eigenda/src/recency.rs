@@ -0,0 +1,92 @@
+//! Contains the [RecencyPolicy] trait and [RecencyWindows], the default policy. A recency policy
+//! decides the recency window, in the unit of L1 block, that a DA cert must satisfy.
+//! See spec <https://layr-labs.github.io/eigenda/integration/spec/6-secure-integration.html#1-rbn-recency-validation>
+
+use crate::errors::HokuleaStatelessError;
+use alloc::collections::BTreeMap;
+use core::fmt::Debug;
+use eigenda_cert::EigenDAVersionedCert;
+
+/// Recency window of offchain derivation version 0, which is 48 hours where
+/// 14400 = 48*60*60(second) / 12 (second/L1block)
+pub const RECENCY_WINDOW_OFFCHAIN_DERIVATION_VERSION_0: u64 = 14400;
+
+/// Decides the recency window of a DA cert. A recency window of 0 disables the recency check.
+pub trait RecencyPolicy: Debug + Send + Sync {
+    /// Returns the recency window, in the unit of L1 block, of the DA cert
+    fn recency_window(&self, cert: &EigenDAVersionedCert) -> Result<u64, HokuleaStatelessError>;
+}
+
+/// A [RecencyPolicy] configured by a recency window per cert version. V4 cert carries the
+/// offchain derivation version, which selects its recency window.
+///
+/// The default policy matches the derivation spec
+/// - V2 and V3 cert maps to recency window 0, i.e. recency check is skipped
+/// - V4 cert with offchain derivation version 0 maps to 14400
+/// - V4 cert with any other offchain derivation version is unsupported
+///
+/// Chains can opt into a recency check for older cert versions, or try alternative windows on
+/// devnets, by overriding individual windows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecencyWindows {
+    /// recency window of V2 cert
+    pub v2_recency_window: u64,
+    /// recency window of V3 cert
+    pub v3_recency_window: u64,
+    /// recency window of V4 cert keyed by offchain derivation version
+    pub offchain_derivation_versions: BTreeMap<u16, u64>,
+}
+
+impl Default for RecencyWindows {
+    fn default() -> Self {
+        Self {
+            v2_recency_window: 0,
+            v3_recency_window: 0,
+            offchain_derivation_versions: BTreeMap::from([(
+                0,
+                RECENCY_WINDOW_OFFCHAIN_DERIVATION_VERSION_0,
+            )]),
+        }
+    }
+}
+
+impl RecencyWindows {
+    /// Overrides the recency window of V2 cert
+    pub fn with_v2_recency_window(mut self, recency_window: u64) -> Self {
+        self.v2_recency_window = recency_window;
+        self
+    }
+
+    /// Overrides the recency window of V3 cert
+    pub fn with_v3_recency_window(mut self, recency_window: u64) -> Self {
+        self.v3_recency_window = recency_window;
+        self
+    }
+
+    /// Overrides or adds the recency window of V4 cert with the offchain derivation version
+    pub fn with_offchain_derivation_version(
+        mut self,
+        offchain_derivation_version: u16,
+        recency_window: u64,
+    ) -> Self {
+        self.offchain_derivation_versions
+            .insert(offchain_derivation_version, recency_window);
+        self
+    }
+}
+
+impl RecencyPolicy for RecencyWindows {
+    fn recency_window(&self, cert: &EigenDAVersionedCert) -> Result<u64, HokuleaStatelessError> {
+        match cert {
+            EigenDAVersionedCert::V2(_) => Ok(self.v2_recency_window),
+            EigenDAVersionedCert::V3(_) => Ok(self.v3_recency_window),
+            EigenDAVersionedCert::V4(cert) => self
+                .offchain_derivation_versions
+                .get(&cert.offchain_derivation_version)
+                .copied()
+                .ok_or(HokuleaStatelessError::UnsupportedOffchainDerivationVersion(
+                    cert.offchain_derivation_version,
+                )),
+        }
+    }
+}
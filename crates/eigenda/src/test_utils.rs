@@ -1,9 +1,9 @@
-//! An implementation of the [EigenDAPreimageProvider] trait for tests.
+//! Implementations of the [EigenDAPreimageProvider] and [DaLayerHandler] traits for tests.
 
 use crate::errors::HokuleaErrorKind;
-use crate::{EigenDAPreimageProvider, EncodedPayload};
-use alloy_primitives::{map::HashMap, B256};
-use eigenda_cert::AltDACommitment;
+use crate::{DaLayerHandler, DiscardReason, EigenDAPreimageProvider, EncodedPayload};
+use alloy_primitives::{map::HashMap, Bytes, B256};
+use eigenda_cert::{AltDACommitment, GenericAltDACommitment};
 
 use alloc::boxed::Box;
 use alloc::string::ToString;
@@ -41,6 +41,8 @@ impl From<TestHokuleaProviderError> for HokuleaErrorKind {
 pub(crate) struct TestEigenDAPreimageProvider {
     pub validities: HashMap<B256, Result<bool, TestHokuleaProviderError>>,
     pub encoded_payloads: HashMap<B256, Result<EncodedPayload, TestHokuleaProviderError>>,
+    pub keccak_preimages: HashMap<B256, Result<Bytes, TestHokuleaProviderError>>,
+    pub recency_verdicts: HashMap<B256, bool>,
     // a backend error propogated to the client
     pub should_preimage_err: bool,
 }
@@ -63,6 +65,23 @@ impl TestEigenDAPreimageProvider {
         self.encoded_payloads
             .insert(altda_commitment.to_digest(), encoded_payload);
     }
+
+    pub(crate) fn insert_keccak_preimage(
+        &mut self,
+        commitment: B256,
+        preimage: Result<Bytes, TestHokuleaProviderError>,
+    ) {
+        self.keccak_preimages.insert(commitment, preimage);
+    }
+
+    pub(crate) fn insert_recency_verdict(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+        verdict: bool,
+    ) {
+        self.recency_verdicts
+            .insert(altda_commitment.to_digest(), verdict);
+    }
 }
 
 #[async_trait]
@@ -96,4 +115,75 @@ impl EigenDAPreimageProvider for TestEigenDAPreimageProvider {
             .unwrap()
             .clone()
     }
+
+    async fn get_keccak_preimage(
+        &mut self,
+        commitment: B256,
+    ) -> Result<Option<Bytes>, Self::Error> {
+        if self.should_preimage_err {
+            return Err(TestHokuleaProviderError::Preimage);
+        }
+
+        self.keccak_preimages.get(&commitment).cloned().transpose()
+    }
+
+    async fn get_recency_verdict(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<Option<bool>, Self::Error> {
+        if self.should_preimage_err {
+            return Err(TestHokuleaProviderError::Preimage);
+        }
+
+        Ok(self
+            .recency_verdicts
+            .get(&altda_commitment.to_digest())
+            .copied())
+    }
+}
+
+// an in-memory stand-in for a DA layer other than EigenDA, serving payloads keyed by commitment
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TestDaLayerHandler {
+    pub da_layer_byte: u8,
+    pub payloads: HashMap<Bytes, Result<Result<Bytes, DiscardReason>, TestHokuleaProviderError>>,
+}
+
+impl TestDaLayerHandler {
+    pub(crate) fn new(da_layer_byte: u8) -> Self {
+        Self {
+            da_layer_byte,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn insert_payload(
+        &mut self,
+        commitment: Bytes,
+        payload: Result<Result<Bytes, DiscardReason>, TestHokuleaProviderError>,
+    ) {
+        self.payloads.insert(commitment, payload);
+    }
+}
+
+#[async_trait]
+impl DaLayerHandler for TestDaLayerHandler {
+    fn da_layer_byte(&self) -> u8 {
+        self.da_layer_byte
+    }
+
+    async fn next(
+        &mut self,
+        altda_commitment: &GenericAltDACommitment,
+        _l1_inclusion_bn: u64,
+    ) -> Result<Result<Bytes, DiscardReason>, HokuleaErrorKind> {
+        let GenericAltDACommitment::DaService { commitment, .. } = altda_commitment else {
+            panic!("not a generic commitment of another DA layer")
+        };
+        self.payloads
+            .get(commitment)
+            .unwrap()
+            .clone()
+            .map_err(|e| e.into())
+    }
 }
@@ -1,5 +1,6 @@
 use crate::{errors::HokuleaErrorKind, EncodedPayload};
 use alloc::{boxed::Box, string::ToString};
+use alloy_primitives::{Bytes, B256};
 use async_trait::async_trait;
 use core::fmt::Display;
 use eigenda_cert::AltDACommitment;
@@ -26,6 +27,29 @@ pub trait EigenDAPreimageProvider {
         &mut self,
         altda_commitment: &AltDACommitment,
     ) -> Result<EncodedPayload, Self::Error>;
+
+    /// Fetch the input data of a keccak altda commitment, i.e. commitment_type 0. The keccak256
+    /// hash of the input data is the commitment itself, so the preimage is addressed by
+    /// [PreimageKeyType::Keccak256](https://specs.optimism.io/fault-proof/index.html#type-2-global-keccak256-key)
+    /// Returns None if the provider does not serve keccak preimages, which is the default. It is
+    /// only called when keccak altda commitments are enabled, see
+    /// [crate::EigenDADataSource::with_keccak_commitments].
+    async fn get_keccak_preimage(
+        &mut self,
+        _commitment: B256,
+    ) -> Result<Option<Bytes>, Self::Error> {
+        Ok(None)
+    }
+
+    /// Fetch the recency verdict of the DA cert as decided by the host, i.e. by eigenda-proxy. It
+    /// is only used for debugging disagreements between proxy and client, which always decides
+    /// recency on its own. Returns None if the provider has no verdict, which is the default.
+    async fn get_recency_verdict(
+        &mut self,
+        _altda_commitment: &AltDACommitment,
+    ) -> Result<Option<bool>, Self::Error> {
+        Ok(None)
+    }
 }
 
 /// The index where INTERFACE_BYTE is located
@@ -37,3 +61,16 @@ pub const RESERVED_EIGENDA_API_BYTE_INDEX: usize = 32;
 /// routed according to the reference block number
 /// More see <https://github.com/Layr-Labs/hokulea/tree/master/docs#reserved-addresses-for-da-certificates>
 pub const RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY: u8 = 1;
+
+/// In the address space of preimage oracle, the encoded payload is also stored in chunks of
+/// [crate::BYTES_PER_ENCODED_PAYLOAD_CHUNK] bytes, which takes far fewer oracle round trips than
+/// one field element at a time. The field element addresses are kept for FPVMs requiring 32 bytes
+/// leaves.
+/// More see <https://github.com/Layr-Labs/hokulea/tree/master/docs#reserved-addresses-for-da-certificates>
+pub const RESERVED_EIGENDA_API_BYTE_FOR_ENCODED_PAYLOAD_CHUNK: u8 = 2;
+
+/// In the address space of preimage oracle, the recency verdict of the eigenda-proxy queried by
+/// the host, a single byte of 0 or 1. The client decides recency on its own, the verdict is only
+/// compared against for debugging
+/// More see <https://github.com/Layr-Labs/hokulea/tree/master/docs#reserved-addresses-for-da-certificates>
+pub const RESERVED_EIGENDA_API_BYTE_FOR_RECENCY: u8 = 3;
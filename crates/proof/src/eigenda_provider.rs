@@ -1,11 +1,12 @@
 use alloc::boxed::Box;
 use alloc::sync::Arc;
-use alloy_primitives::keccak256;
+use alloy_primitives::{Bytes, B256};
 use async_trait::async_trait;
 use eigenda_cert::AltDACommitment;
+pub use hokulea_eigenda::EncodedPayloadFetchMode;
 use hokulea_eigenda::{
-    EigenDAPreimageProvider, EncodedPayload, BYTES_PER_FIELD_ELEMENT,
-    RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY, RESERVED_EIGENDA_API_BYTE_INDEX,
+    EigenDAPreimageKey, EigenDAPreimageProvider, EncodedPayload, BYTES_PER_ENCODED_PAYLOAD_CHUNK,
+    BYTES_PER_FIELD_ELEMENT,
 };
 use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
 
@@ -20,12 +21,24 @@ use alloc::vec::Vec;
 pub struct OracleEigenDAPreimageProvider<T: CommsClient> {
     /// The preimage oracle client.
     oracle: Arc<T>,
+    /// The address layout of the encoded payload
+    fetch_mode: EncodedPayloadFetchMode,
 }
 
 impl<T: CommsClient> OracleEigenDAPreimageProvider<T> {
-    /// Constructs a new oracle-backed EigenDA provider.
+    /// Constructs a new oracle-backed EigenDA provider, fetching the encoded payload one field
+    /// element at a time.
     pub fn new(oracle: Arc<T>) -> Self {
-        Self { oracle }
+        Self {
+            oracle,
+            fetch_mode: EncodedPayloadFetchMode::default(),
+        }
+    }
+
+    /// Sets the address layout used to fetch the encoded payload
+    pub fn with_fetch_mode(mut self, fetch_mode: EncodedPayloadFetchMode) -> Self {
+        self.fetch_mode = fetch_mode;
+        self
     }
 }
 
@@ -46,15 +59,13 @@ impl<T: CommsClient + Sync + Send> EigenDAPreimageProvider for OracleEigenDAPrei
             .await
             .map_err(HokuleaOracleProviderError::Preimage)?;
 
-        let mut address_template = altda_commitment.digest_template();
-
         // make the call about validity of a altda commitment
-        address_template[RESERVED_EIGENDA_API_BYTE_INDEX] = RESERVED_EIGENDA_API_BYTE_FOR_VALIDITY;
+        let validity_key = EigenDAPreimageKey::validity_key(altda_commitment);
 
         let validity = self
             .oracle
             .get(PreimageKey::new(
-                *keccak256(address_template),
+                *validity_key.hash(),
                 PreimageKeyType::GlobalGeneric,
             ))
             .await
@@ -90,14 +101,69 @@ impl<T: CommsClient + Sync + Send> EigenDAPreimageProvider for OracleEigenDAPrei
         // data_length measurs in field element, multiply to get num bytes
         let mut encoded_payload: Vec<u8> =
             vec![0; (blob_length_fe as usize) * BYTES_PER_FIELD_ELEMENT];
-        let field_element_key = altda_commitment.digest_template();
-        self.fetch_encoded_payload(field_element_key, blob_length_fe, &mut encoded_payload)
-            .await?;
+        match self.fetch_mode {
+            EncodedPayloadFetchMode::FieldElement => {
+                self.fetch_encoded_payload(altda_commitment, blob_length_fe, &mut encoded_payload)
+                    .await?
+            }
+            EncodedPayloadFetchMode::Chunk => {
+                self.fetch_encoded_payload_chunks(altda_commitment, &mut encoded_payload)
+                    .await?
+            }
+        }
 
         Ok(EncodedPayload {
             encoded_payload: encoded_payload.into(),
         })
     }
+
+    /// Get the input data of a keccak altda commitment
+    async fn get_keccak_preimage(
+        &mut self,
+        commitment: B256,
+    ) -> Result<Option<Bytes>, Self::Error> {
+        // hint the host about a new keccak commitment, the host fetches the input data and stores it
+        // under its keccak256 hash
+        self.oracle
+            .write(&ExtendedHintType::AltDAKeccakCommitment.encode_with(&[commitment.as_slice()]))
+            .await
+            .map_err(HokuleaOracleProviderError::Preimage)?;
+
+        // the preimage oracle checks the input data against the keccak256 hash
+        let input_data = self
+            .oracle
+            .get(PreimageKey::new(*commitment, PreimageKeyType::Keccak256))
+            .await
+            .map_err(HokuleaOracleProviderError::Preimage)?;
+
+        Ok(Some(input_data.into()))
+    }
+
+    /// Query the recency verdict of the host, the altda commitment has already been hinted when
+    /// querying its validity
+    async fn get_recency_verdict(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<Option<bool>, Self::Error> {
+        let recency_key = EigenDAPreimageKey::recency_key(altda_commitment);
+
+        let verdict = self
+            .oracle
+            .get(PreimageKey::new(
+                *recency_key.hash(),
+                PreimageKeyType::GlobalGeneric,
+            ))
+            .await
+            .map_err(HokuleaOracleProviderError::Preimage)?;
+
+        // verdict is expected as a boolean, or empty if the host did not decide recency
+        match verdict.as_slice() {
+            [] => Ok(None),
+            [0] => Ok(Some(false)),
+            [1] => Ok(Some(true)),
+            _ => Err(HokuleaOracleProviderError::MalformedRecencyVerdict(verdict)),
+        }
+    }
 }
 
 impl<T: CommsClient + Sync + Send> OracleEigenDAPreimageProvider<T> {
@@ -106,23 +172,18 @@ impl<T: CommsClient + Sync + Send> OracleEigenDAPreimageProvider<T> {
     /// object inside the host
     async fn fetch_encoded_payload(
         &mut self,
-        mut field_element_key: [u8; 80],
+        altda_commitment: &AltDACommitment,
         blob_length: u64,
         encoded_payload: &mut [u8],
     ) -> Result<(), HokuleaOracleProviderError> {
         for idx_fe in 0..blob_length {
-            // last 8 bytes for index
-            let index_byte: [u8; 8] = idx_fe.to_be_bytes();
-            field_element_key[72..].copy_from_slice(&index_byte);
+            let field_element_key = EigenDAPreimageKey::field_element_key(altda_commitment, idx_fe);
 
             // get field element
             let mut field_element = [0u8; 32];
             self.oracle
                 .get_exact(
-                    PreimageKey::new(
-                        *keccak256(field_element_key),
-                        PreimageKeyType::GlobalGeneric,
-                    ),
+                    PreimageKey::new(*field_element_key.hash(), PreimageKeyType::GlobalGeneric),
                     &mut field_element,
                 )
                 .await
@@ -133,4 +194,28 @@ impl<T: CommsClient + Sync + Send> OracleEigenDAPreimageProvider<T> {
         }
         Ok(())
     }
+
+    /// Fetch the encoded payload a chunk at a time, the keys must be consistent with the ones
+    /// populated by the host. The length of every chunk is checked by the oracle
+    async fn fetch_encoded_payload_chunks(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+        encoded_payload: &mut [u8],
+    ) -> Result<(), HokuleaOracleProviderError> {
+        for (idx_chunk, chunk) in encoded_payload
+            .chunks_mut(BYTES_PER_ENCODED_PAYLOAD_CHUNK)
+            .enumerate()
+        {
+            let chunk_key =
+                EigenDAPreimageKey::encoded_payload_chunk_key(altda_commitment, idx_chunk as u64);
+            self.oracle
+                .get_exact(
+                    PreimageKey::new(*chunk_key.hash(), PreimageKeyType::GlobalGeneric),
+                    chunk,
+                )
+                .await
+                .map_err(HokuleaOracleProviderError::Preimage)?;
+        }
+        Ok(())
+    }
 }
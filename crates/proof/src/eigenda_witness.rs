@@ -3,7 +3,7 @@
 //! converted into [EigenDAWitness] with appropriate proofs.
 extern crate alloc;
 use alloc::vec::Vec;
-use alloy_primitives::FixedBytes;
+use alloy_primitives::{Bytes, FixedBytes, B256};
 
 use eigenda_cert::AltDACommitment;
 use hokulea_eigenda::EncodedPayload;
@@ -26,12 +26,15 @@ pub struct EigenDAPreimage {
     pub validities: Vec<(AltDACommitment, bool)>,
     /// encoded_payload corresponds to a da cert and its kzg proof
     pub encoded_payloads: Vec<(AltDACommitment, EncodedPayload)>,
+    /// input data corresponds to a keccak altda commitment
+    pub keccak_preimages: Vec<(B256, Bytes)>,
 }
 
 /// EigenDAWitness contains preimage and witness data to be provided into
 /// the zkVM as part of Preimage Oracle. There are three types of preimages:
 /// 1. validity of (da cert and consistent offchain derivation version),
 /// 2. encoded payload
+/// 3. input data of keccak altda commitment, which is bound to the commitment by its keccak256 hash
 ///
 /// In each type, we group (DA cert, preimage data) into a tuple, such
 /// that there is one-to-one mapping from DA cert to the value.
@@ -71,6 +74,8 @@ pub struct EigenDAWitness {
     pub validities: Vec<(AltDACommitment, bool)>,
     /// encoded_payload corresponds to a da cert and its kzg proof
     pub encoded_payloads: Vec<(AltDACommitment, EncodedPayload, FixedBytes<64>)>,
+    /// input data corresponds to a keccak altda commitment
+    pub keccak_preimages: Vec<(B256, Bytes)>,
     /// used and populated at the end of canoe proof
     /// it should only deserialize to one zk proof that proves all DA certs are
     /// correct
@@ -93,6 +98,7 @@ impl EigenDAWitness {
                 .zip(kzg_proofs)
                 .map(|((commitment, payload), proof)| (commitment, payload, proof))
                 .collect(),
+            keccak_preimages: preimage.keccak_preimages,
             canoe_proof_bytes,
         }
     }
@@ -130,6 +136,7 @@ impl EigenDAWitness {
         let preimage = EigenDAPreimage {
             validities: self.validities,
             encoded_payloads,
+            keccak_preimages: self.keccak_preimages,
         };
 
         (preimage, kzg_proofs, self.canoe_proof_bytes)
@@ -1,4 +1,4 @@
-//! This module contains the [ExtendedHintType], which adds an EigenDACommitment case to kona's [HintType] enum.
+//! This module contains the [ExtendedHintType], which adds EigenDACommitment and keccak altda commitment cases to kona's [HintType] enum.
 
 use alloc::{string::String, vec::Vec};
 use alloy_primitives::hex;
@@ -12,6 +12,7 @@ use alloc::str::FromStr;
 pub enum ExtendedHintType {
     Original(HintType),
     EigenDACert,
+    AltDAKeccakCommitment,
 }
 
 impl ExtendedHintType {
@@ -28,6 +29,7 @@ impl FromStr for ExtendedHintType {
     fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value {
             "eigenda-certificate" => Ok(Self::EigenDACert),
+            "altda-keccak-commitment" => Ok(Self::AltDAKeccakCommitment),
 
             _ => Ok(Self::Original(HintType::from_str(value)?)),
         }
@@ -38,6 +40,7 @@ impl From<ExtendedHintType> for &str {
     fn from(value: ExtendedHintType) -> Self {
         match value {
             ExtendedHintType::EigenDACert => "eigenda-certificate",
+            ExtendedHintType::AltDAKeccakCommitment => "altda-keccak-commitment",
             ExtendedHintType::Original(hint_type) => hint_type.into(),
         }
     }
@@ -0,0 +1,227 @@
+//! Native BLS aggregate signature verification of EigenDA certs — substrate-bn port of
+//! `BLSSignatureChecker.checkSignatures` in the EigenDA middleware contracts.
+//!
+//! The signed message is the hash of the batch header. The aggregate pubkey of the signers is
+//! the sum of the aggregate pubkeys of the signed quorums, minus every non-signer pubkey once
+//! per signed quorum it belongs to. The signature is then checked with the pairing equation
+//!
+//! ```text
+//! e(σ + γ·apk, G2) = e(H(m) + γ·G1, apk_g2)
+//! ```
+//!
+//! where `γ` binds the message, the aggregate pubkeys and the signature. The quorum membership
+//! of the non-signers, and the aggregate pubkeys of the quorums, are read from an
+//! [OperatorStateSnapshot] at the reference block number of the cert. Stake thresholds are not
+//! checked.
+
+extern crate alloc;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use alloy_primitives::{keccak256, B256, U256};
+use eigenda_cert::{BatchHeaderV2, CertView, G1Point, G2Point};
+use substrate_bn::{AffineG1, AffineG2, Fq, Fq2, Fr, Group, G1, G2};
+
+use crate::errors::BlsError;
+use crate::helpers::{fr_from_usize, pairings_verify};
+
+/// BN254 base field modulus
+const FP_MODULUS: U256 = U256::from_limbs([
+    0x3c208c16d87cfd47,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+]);
+
+/// `(p + 1) / 4`, the exponent of the square root in the base field
+const FP_SQRT_EXPONENT: U256 = U256::from_limbs([
+    0x4f082305b61f3f52,
+    0x65e05aa45a1c72a3,
+    0x6e14116da0605617,
+    0x0c19139cb84c680a,
+]);
+
+/// Operator state at the reference block number of a cert, as recorded by the EigenDA
+/// registries.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperatorStateSnapshot {
+    /// quorum bitmap of every non-signer, keyed by the hash of its pubkey, see [pubkey_hash]
+    pub quorum_bitmaps: BTreeMap<B256, U256>,
+    /// aggregate pubkey of every quorum
+    pub quorum_apks: BTreeMap<u8, G1Point>,
+}
+
+/// Hash of the batch header signed by the operators, `keccak256(abi.encode(batchHeader))`.
+pub fn batch_header_hash(batch_header: &BatchHeaderV2) -> B256 {
+    let mut encoded = [0u8; 64];
+    encoded[..32].copy_from_slice(&batch_header.batch_root);
+    encoded[32..]
+        .copy_from_slice(&U256::from(batch_header.reference_block_number).to_be_bytes::<32>());
+    keccak256(encoded)
+}
+
+/// Hash of an operator pubkey, `keccak256(abi.encodePacked(X, Y))`, which identifies the
+/// operator in the registries.
+pub fn pubkey_hash(pubkey: &G1Point) -> B256 {
+    keccak256([pubkey.x.to_be_bytes::<32>(), pubkey.y.to_be_bytes::<32>()].concat())
+}
+
+/// Try-and-increment hash to G1, matching `BN254.hashToG1` of the EigenDA contracts.
+pub fn hash_to_g1(message: B256) -> G1Point {
+    let mut x = U256::from_be_bytes(message.0).reduce_mod(FP_MODULUS);
+    loop {
+        let beta = x
+            .mul_mod(x, FP_MODULUS)
+            .mul_mod(x, FP_MODULUS)
+            .add_mod(U256::from(3), FP_MODULUS);
+        let y = beta.pow_mod(FP_SQRT_EXPONENT, FP_MODULUS);
+        if y.mul_mod(y, FP_MODULUS) == beta {
+            return G1Point { x, y };
+        }
+        x = x.add_mod(U256::from(1), FP_MODULUS);
+    }
+}
+
+/// Verify the aggregate signature of the cert over its batch header, given the operator state
+/// at the reference block number of the cert. Returns `Ok(false)` if the pairing check fails,
+/// and an error if the cert is inconsistent with itself or with the operator state.
+pub fn verify_cert_signature<C: CertView + ?Sized>(
+    cert: &C,
+    operator_state: &OperatorStateSnapshot,
+) -> Result<bool, BlsError> {
+    let signed_quorum_numbers = cert.signed_quorum_numbers();
+    let params = cert.nonsigner_stake_and_signature();
+    if params.quorum_apks.len() != signed_quorum_numbers.len() {
+        return Err(BlsError::QuorumApksLengthMismatch(
+            params.quorum_apks.len(),
+            signed_quorum_numbers.len(),
+        ));
+    }
+
+    let signed_quorum_bitmap = signed_quorum_numbers
+        .iter()
+        .fold(U256::ZERO, |bitmap, quorum| {
+            bitmap | (U256::from(1) << usize::from(*quorum))
+        });
+
+    // subtract every non-signer once per signed quorum it belongs to. Non-signers are sorted by
+    // pubkey hash, such that none is subtracted twice
+    let mut apk = G1::zero();
+    let mut previous_hash: Option<B256> = None;
+    for pubkey in &params.non_signer_pubkeys {
+        let hash = pubkey_hash(pubkey);
+        if previous_hash.is_some_and(|previous| previous >= hash) {
+            return Err(BlsError::UnsortedNonSigners);
+        }
+        previous_hash = Some(hash);
+
+        let quorum_bitmap = operator_state
+            .quorum_bitmaps
+            .get(&hash)
+            .ok_or(BlsError::MissingQuorumBitmap(hash))?;
+        let num_quorums = (quorum_bitmap & signed_quorum_bitmap).count_ones();
+        apk =
+            apk - g1_point_to_jacobian(pubkey, "non_signer_pubkeys")? * fr_from_usize(num_quorums);
+    }
+
+    // the aggregate pubkeys of the signed quorums are bound to the operator state
+    for (quorum, quorum_apk) in signed_quorum_numbers.iter().zip(&params.quorum_apks) {
+        let expected = operator_state
+            .quorum_apks
+            .get(quorum)
+            .ok_or(BlsError::MissingQuorumApk(*quorum))?;
+        if expected != quorum_apk {
+            return Err(BlsError::QuorumApkMismatch(*quorum));
+        }
+        apk = apk + g1_point_to_jacobian(quorum_apk, "quorum_apks")?;
+    }
+
+    let message = batch_header_hash(cert.batch_header());
+    let apk_point = jacobian_to_g1_point(apk);
+    let gamma = signature_challenge(message, &apk_point, &params.apk_g2, &params.sigma);
+
+    let sigma = g1_point_to_jacobian(&params.sigma, "sigma")?;
+    let apk_g2 = g2_point_to_jacobian(&params.apk_g2)?;
+    let hashed_message = g1_point_to_jacobian(&hash_to_g1(message), "message")?;
+    Ok(pairings_verify(
+        sigma + apk * gamma,
+        G2::one(),
+        hashed_message + G1::one() * gamma,
+        apk_g2,
+    ))
+}
+
+/// `γ = keccak256(abi.encodePacked(msgHash, apk.X, apk.Y, apkG2.X, apkG2.Y, sigma.X, sigma.Y))`
+/// reduced modulo the scalar field order.
+fn signature_challenge(message: B256, apk: &G1Point, apk_g2: &G2Point, sigma: &G1Point) -> Fr {
+    let words = [
+        U256::from_be_bytes(message.0),
+        apk.x,
+        apk.y,
+        apk_g2.x.0,
+        apk_g2.x.1,
+        apk_g2.y.0,
+        apk_g2.y.1,
+        sigma.x,
+        sigma.y,
+    ];
+    let preimage: Vec<u8> = words
+        .iter()
+        .flat_map(|word| word.to_be_bytes::<32>())
+        .collect();
+    Fr::from_bytes_be_mod_order(keccak256(preimage).as_slice())
+        .expect("keccak256 digest is 32 bytes, always valid for Fr::from_bytes_be_mod_order")
+}
+
+/// Convert a point of the cert into a substrate-bn point with curve validation. `(0, 0)` encodes
+/// the point at infinity, as in the EigenDA contracts.
+fn g1_point_to_jacobian(point: &G1Point, field: &'static str) -> Result<G1, BlsError> {
+    if point.x.is_zero() && point.y.is_zero() {
+        return Ok(G1::zero());
+    }
+    let x = u256_to_fq(point.x).ok_or(BlsError::InvalidG1Point(field))?;
+    let y = u256_to_fq(point.y).ok_or(BlsError::InvalidG1Point(field))?;
+    let affine = AffineG1::new(x, y).map_err(|_| BlsError::InvalidG1Point(field))?;
+    Ok(affine.into())
+}
+
+fn jacobian_to_g1_point(point: G1) -> G1Point {
+    match AffineG1::from_jacobian(point) {
+        Some(affine) => G1Point {
+            x: fq_to_u256(&affine.x()),
+            y: fq_to_u256(&affine.y()),
+        },
+        None => G1Point {
+            x: U256::ZERO,
+            y: U256::ZERO,
+        },
+    }
+}
+
+fn fq_to_u256(value: &Fq) -> U256 {
+    let mut bytes = [0u8; 32];
+    value
+        .to_big_endian(&mut bytes)
+        .expect("Fq::to_big_endian writes exactly 32 bytes for a valid Fq");
+    U256::from_be_bytes(bytes)
+}
+
+/// Coordinates must be canonical, as the ecAdd and ecMul precompiles reject values not smaller
+/// than the field modulus.
+fn u256_to_fq(value: U256) -> Option<Fq> {
+    if value >= FP_MODULUS {
+        return None;
+    }
+    Fq::from_be_bytes_mod_order(&value.to_be_bytes::<32>()).ok()
+}
+
+/// Convert the aggregate G2 pubkey into a substrate-bn point with curve and subgroup validation.
+/// The contracts encode an Fp2 element as `X[0]·i + X[1]`, while substrate-bn takes
+/// `(real, imaginary)`.
+fn g2_point_to_jacobian(point: &G2Point) -> Result<G2, BlsError> {
+    let fq = |value: U256| u256_to_fq(value).ok_or(BlsError::InvalidG2Point);
+    let x = Fq2::new(fq(point.x.1)?, fq(point.x.0)?);
+    let y = Fq2::new(fq(point.y.1)?, fq(point.y.0)?);
+    let affine = AffineG2::new(x, y).map_err(|_| BlsError::InvalidG2Point)?;
+    Ok(affine.into())
+}
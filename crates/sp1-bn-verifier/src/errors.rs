@@ -1,5 +1,6 @@
 extern crate alloc;
 use alloc::string::String;
+use alloy_primitives::B256;
 use thiserror::Error;
 
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -19,3 +20,21 @@ pub enum KzgError {
     #[error("{0}")]
     GenericError(String),
 }
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BlsError {
+    #[error("g1 point not on curve or not in subgroup: {0}")]
+    InvalidG1Point(&'static str),
+    #[error("apk_g2 not on curve or not in subgroup")]
+    InvalidG2Point,
+    #[error("quorum_apks has length {0}, expected one per signed quorum {1}")]
+    QuorumApksLengthMismatch(usize, usize),
+    #[error("non-signer pubkeys are not sorted by pubkey hash")]
+    UnsortedNonSigners,
+    #[error("no quorum bitmap for non-signer {0}")]
+    MissingQuorumBitmap(B256),
+    #[error("no aggregate pubkey for quorum {0}")]
+    MissingQuorumApk(u8),
+    #[error("aggregate pubkey of quorum {0} does not match operator state")]
+    QuorumApkMismatch(u8),
+}
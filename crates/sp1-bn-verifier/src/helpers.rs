@@ -245,7 +245,7 @@ fn usize_to_decimal(n: usize) -> String {
 }
 
 /// Build an `Fr` from an integer that is known to fit in the scalar field.
-fn fr_from_usize(n: usize) -> Fr {
+pub(crate) fn fr_from_usize(n: usize) -> Fr {
     Fr::from_str(&usize_to_decimal(n))
         .expect("decimal representation of usize is always a valid Fr")
 }
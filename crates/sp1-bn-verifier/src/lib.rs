@@ -1,4 +1,4 @@
-//! KZG batch verifier built on the [`substrate-bn`](https://github.com/sp1-patches/bn) fork.
+//! KZG batch verifier, and BLS signature verifier of DA certs, built on the [`substrate-bn`](https://github.com/sp1-patches/bn) fork.
 //!
 //! This crate is a `no_std`-friendly reimplementation of the EigenDA-flavoured
 //! KZG batch verification logic that lives in `rust-kzg-bn254-verifier`. The
@@ -16,9 +16,11 @@
 extern crate alloc;
 
 pub mod batch;
+pub mod bls;
 pub mod consts;
 pub mod errors;
 pub mod helpers;
 
 pub use batch::verify_blob_kzg_proof_batch;
-pub use errors::KzgError;
+pub use bls::{verify_cert_signature, OperatorStateSnapshot};
+pub use errors::{BlsError, KzgError};
@@ -0,0 +1,175 @@
+//! BLS signature verification against a cert attested on Sepolia.
+//!
+//! The operator state at the reference block number of the cert, i.e. the quorum bitmaps of the
+//! non-signers and the aggregate pubkeys of the quorums, is not part of the cert, it is
+//! reconstructed here from the cert itself. The second non-signer is only registered in quorum 1,
+//! the other ones are registered in both quorums.
+
+use alloy_primitives::{b256, hex, U256};
+use eigenda_cert::test_utils::ALTDA_COMMITMENT_V3;
+use eigenda_cert::{AltDACommitment, EigenDACertV3, EigenDAVersionedCert, G1Point};
+use hokulea_sp1_bn_verifier::bls::{batch_header_hash, hash_to_g1, pubkey_hash};
+use hokulea_sp1_bn_verifier::{verify_cert_signature, BlsError, OperatorStateSnapshot};
+
+const NON_SIGNER_QUORUM_BITMAPS: [u64; 4] = [0b11, 0b10, 0b11, 0b11];
+
+fn sepolia_cert() -> EigenDACertV3 {
+    let calldata = hex::decode(ALTDA_COMMITMENT_V3).unwrap();
+    let altda_commitment: AltDACommitment = calldata[..].try_into().unwrap();
+    match altda_commitment.versioned_cert {
+        EigenDAVersionedCert::V3(cert) => cert,
+        _ => panic!("test vector is a V3 cert"),
+    }
+}
+
+fn operator_state(cert: &EigenDACertV3) -> OperatorStateSnapshot {
+    let params = &cert.nonsigner_stake_and_signature;
+    OperatorStateSnapshot {
+        quorum_bitmaps: params
+            .non_signer_pubkeys
+            .iter()
+            .zip(NON_SIGNER_QUORUM_BITMAPS)
+            .map(|(pubkey, bitmap)| (pubkey_hash(pubkey), U256::from(bitmap)))
+            .collect(),
+        quorum_apks: cert
+            .signed_quorum_numbers
+            .iter()
+            .copied()
+            .zip(params.quorum_apks.iter().copied())
+            .collect(),
+    }
+}
+
+#[test]
+fn signed_message() {
+    let cert = sepolia_cert();
+    let message = batch_header_hash(&cert.batch_header_v2);
+    assert_eq!(
+        message,
+        b256!("b0c850c2904a791fbbe83dc5920b41580a664c40817501e18609706992fa23a3")
+    );
+    let hashed_message = hash_to_g1(message);
+    assert_eq!(
+        hashed_message,
+        G1Point {
+            x: U256::from_str_radix(
+                "14296259533225738698493266689982560921909660771467064647988805749175457360847",
+                10
+            )
+            .unwrap(),
+            y: U256::from_str_radix(
+                "14310984532794775788159053456560244015125559187531750110073882437967843766082",
+                10
+            )
+            .unwrap(),
+        }
+    );
+}
+
+#[test]
+fn verify_cert_signature_cases() {
+    struct Case {
+        name: &'static str,
+        mutate: fn(&mut EigenDACertV3, &mut OperatorStateSnapshot),
+        expected: Result<bool, BlsError>,
+    }
+
+    let cases = [
+        Case {
+            name: "valid",
+            mutate: |_, _| {},
+            expected: Ok(true),
+        },
+        Case {
+            name: "non-signer subtracted from the wrong quorums",
+            mutate: |cert, state| {
+                let pubkey = &cert.nonsigner_stake_and_signature.non_signer_pubkeys[1];
+                state
+                    .quorum_bitmaps
+                    .insert(pubkey_hash(pubkey), U256::from(0b11));
+            },
+            expected: Ok(false),
+        },
+        Case {
+            name: "negated signature",
+            mutate: |cert, _| {
+                let sigma = &mut cert.nonsigner_stake_and_signature.sigma;
+                sigma.y = U256::from_str_radix(
+                    "21888242871839275222246405745257275088696311157297823662689037894645226208583",
+                    10,
+                )
+                .unwrap()
+                    - sigma.y;
+            },
+            expected: Ok(false),
+        },
+        Case {
+            name: "different reference block number",
+            mutate: |cert, _| cert.batch_header_v2.reference_block_number += 1,
+            expected: Ok(false),
+        },
+        Case {
+            name: "signature not on curve",
+            mutate: |cert, _| cert.nonsigner_stake_and_signature.sigma.y += U256::from(1),
+            expected: Err(BlsError::InvalidG1Point("sigma")),
+        },
+        Case {
+            name: "apk_g2 not on curve",
+            mutate: |cert, _| cert.nonsigner_stake_and_signature.apk_g2.x.0 += U256::from(1),
+            expected: Err(BlsError::InvalidG2Point),
+        },
+        Case {
+            name: "missing quorum apk",
+            mutate: |cert, _| {
+                cert.nonsigner_stake_and_signature.quorum_apks.pop();
+            },
+            expected: Err(BlsError::QuorumApksLengthMismatch(1, 2)),
+        },
+        Case {
+            name: "unsorted non-signers",
+            mutate: |cert, _| {
+                cert.nonsigner_stake_and_signature
+                    .non_signer_pubkeys
+                    .swap(0, 1)
+            },
+            expected: Err(BlsError::UnsortedNonSigners),
+        },
+        Case {
+            name: "unknown non-signer",
+            mutate: |cert, state| {
+                let pubkey = &cert.nonsigner_stake_and_signature.non_signer_pubkeys[2];
+                state.quorum_bitmaps.remove(&pubkey_hash(pubkey));
+            },
+            expected: Err(BlsError::MissingQuorumBitmap(b256!(
+                "5cb462c7c3f5e1bb8b388809cb33e30420dda1e50727489226e8af4fba216552"
+            ))),
+        },
+        Case {
+            name: "unknown quorum",
+            mutate: |_, state| {
+                state.quorum_apks.remove(&1);
+            },
+            expected: Err(BlsError::MissingQuorumApk(1)),
+        },
+        Case {
+            name: "quorum apk not matching operator state",
+            mutate: |cert, _| {
+                let params = &mut cert.nonsigner_stake_and_signature;
+                params.quorum_apks.swap(0, 1);
+            },
+            expected: Err(BlsError::QuorumApkMismatch(0)),
+        },
+    ];
+
+    for case in cases {
+        let mut cert = sepolia_cert();
+        let mut state = operator_state(&cert);
+        (case.mutate)(&mut cert, &mut state);
+        assert_eq!(
+            verify_cert_signature(&cert, &state),
+            case.expected,
+            "case {}",
+            case.name
+        );
+    }
+}
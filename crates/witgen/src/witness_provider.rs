@@ -1,3 +1,4 @@
+use alloy_primitives::{Bytes, B256};
 use async_trait::async_trait;
 use eigenda_cert::AltDACommitment;
 use hokulea_eigenda::{EigenDAPreimageProvider, EncodedPayload};
@@ -62,4 +63,28 @@ impl<T: EigenDAPreimageProvider + Send> EigenDAPreimageProvider
             Err(e) => Err(e),
         }
     }
+
+    async fn get_keccak_preimage(
+        &mut self,
+        commitment: B256,
+    ) -> Result<Option<Bytes>, Self::Error> {
+        match self.provider.get_keccak_preimage(commitment).await {
+            Ok(Some(input_data)) => {
+                let mut preimage = self.preimage.lock().unwrap();
+                preimage
+                    .keccak_preimages
+                    .push((commitment, input_data.clone()));
+                Ok(Some(input_data))
+            }
+            other => other,
+        }
+    }
+
+    // the recency verdict is only for debugging, hence it is not part of the preimage
+    async fn get_recency_verdict(
+        &mut self,
+        altda_commitment: &AltDACommitment,
+    ) -> Result<Option<bool>, Self::Error> {
+        self.provider.get_recency_verdict(altda_commitment).await
+    }
 }
@@ -36,7 +36,7 @@ use canoe_verifier_address_fetcher::{
 use hokulea_client::fp_client;
 use hokulea_compute_proof::create_kzg_proofs_for_eigenda_preimage;
 use hokulea_proof::{
-    eigenda_provider::OracleEigenDAPreimageProvider,
+    eigenda_provider::{EncodedPayloadFetchMode, OracleEigenDAPreimageProvider},
     eigenda_witness::{EigenDAPreimage, EigenDAWitness},
 };
 use hokulea_witgen::witness_provider::OracleEigenDAPreimageProviderWithPreimage;
@@ -49,7 +49,9 @@ use tracing::info;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> anyhow::Result<()> {
-    let cfg = SingleChainHostWithEigenDA::try_parse()?;
+    let mut cfg = SingleChainHostWithEigenDA::try_parse()?;
+    // the client below fetches the encoded payload in chunks
+    cfg.eigenda_encoded_payload_chunks = true;
     init_tracing_subscriber(cfg.verbose)?;
 
     let hint = BidirectionalChannel::new()?;
@@ -270,7 +272,9 @@ where
 {
     let beacon = OracleBlobProvider::new(oracle.clone());
 
-    let eigenda_preimage_provider = OracleEigenDAPreimageProvider::new(oracle.clone());
+    // the preloader runs natively against the hokulea host, chunks save most oracle round trips
+    let eigenda_preimage_provider = OracleEigenDAPreimageProvider::new(oracle.clone())
+        .with_fetch_mode(EncodedPayloadFetchMode::Chunk);
     let eigenda_preimage = Arc::new(Mutex::new(EigenDAPreimage::default()));
 
     let eigenda_preimage_provider = OracleEigenDAPreimageProviderWithPreimage {